@@ -1,69 +1,356 @@
-use clap::{Parser, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use finiolib::{
-    error::{FinioError, Result},
-    formats::{csv::Csv, xml::SimpleXml, mt940::Mt940, camt053::Camt053},
-    traits::{ReadFormat, WriteFormat},
+    convert,
+    detect::{self, FormatKind, SNIFF_LEN},
+    encoding::{DecodingReader, Encoding, EncodingWriter},
+    error::{Diagnostic, FinioError, Location, Result},
+    formats::{
+        camt052::Camt052,
+        camt053::{Camt053, CamtVersion},
+        camt054::Camt054,
+        csv::Csv,
+        csv_profile::CsvProfile,
+        field86::Layout86,
+        mt940::Mt940,
+        mt942::Mt942,
+        onec::OneC,
+        xml::SimpleXml,
+    },
+    model::EntryStatus,
+    stream,
+    traits::{ParseMode, StatementReader, StatementWriter, StreamRead, StreamWrite},
+    validate::{has_errors, Validator},
 };
 use std::fs::File;
-use std::io::{self, BufReader, Write};
+use std::io::{self, BufReader, BufWriter, Cursor, Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
 
-#[derive(Copy, Clone, Debug, ValueEnum)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
 enum Fmt {
     Csv,
     Xml,
     Mt940,
+    Mt942,
+    Camt052,
     Camt053,
+    Camt054,
+    /// 1CClientBankExchange (Windows-1251)
+    #[value(name = "1c", alias = "onec")]
+    OneC,
 }
 
 #[derive(Parser, Debug)]
 #[command(name="finio", version, about="Конвертация финансовых данных")]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
-    /// Входной файл (по умолчанию stdin)
-    #[arg(short='i', long="input")]
-    input: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    input: InputArgs,
 
     /// Выходной файл (по умолчанию stdout)
     #[arg(short='o', long="output")]
     output: Option<String>,
 
-    /// Формат входа
+    /// Формат выхода
+    #[arg(long="out-format", value_enum, required = true)]
+    out_format: Option<Fmt>,
+
+    /// Кодировка выхода (по умолчанию UTF-8), напр. windows-1251, cp866
+    #[arg(long="out-encoding")]
+    out_encoding: Option<Encoding>,
+
+    /// Версия схемы CAMT на выходе (camt052/053/054), напр. 001.08
+    #[arg(long="camt-version", default_value = "001.02")]
+    camt_version: CamtVersion,
+
+    /// Раскладка поля :86: MT940 на входе и выходе: auto, text, gvc (DE), tags (NL/PL)
+    #[arg(long="mt86-layout", default_value = "auto")]
+    mt86_layout: Layout86,
+
+    /// Раскрывать пакетные проводки CAMT в проводки по операциям (TxDtls)
+    #[arg(long="expand-batches")]
+    expand_batches: bool,
+
+    /// Только проводки в этом состоянии: booked, pending, info
+    #[arg(long="status")]
+    status: Option<EntryStatus>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Проверка выписок: сходимость остатков, валюта, даты
+    Validate(InputArgs),
+}
+
+#[derive(Args, Debug)]
+struct InputArgs {
+    /// Входной файл (по умолчанию stdin)
+    #[arg(short='i', long="input")]
+    input: Option<String>,
+
+    /// Формат входа (по умолчанию — определяется по содержимому и расширению)
     #[arg(long="in-format", value_enum)]
-    in_format: Fmt,
+    in_format: Option<Fmt>,
 
-    /// Формат выхода
-    #[arg(long="out-format", value_enum)]
-    out_format: Fmt,
+    /// Кодировка входа (по умолчанию — по BOM и содержимому), напр. windows-1251
+    #[arg(long="in-encoding")]
+    in_encoding: Option<Encoding>,
+
+    /// Профиль колонок банковской выгрузки CSV (TOML или JSON); вход — CSV
+    #[arg(long="csv-profile")]
+    csv_profile: Option<String>,
+
+    /// Прерываться на первой испорченной записи (по умолчанию)
+    #[arg(long="strict", conflicts_with = "lenient")]
+    strict: bool,
+
+    /// Пропускать испорченные записи с предупреждением
+    #[arg(long="lenient")]
+    lenient: bool,
 }
 
-fn main() -> Result<()> {
+impl InputArgs {
+    fn mode(&self) -> ParseMode {
+        if self.lenient {
+            ParseMode::Lenient
+        } else {
+            ParseMode::Strict
+        }
+    }
+
+    fn source(&self) -> &str {
+        self.input.as_deref().unwrap_or("<stdin>")
+    }
+}
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
 
+    let (input, res) = match &cli.command {
+        Some(Command::Validate(input)) => (input, validate(input)),
+        None => {
+            let out_format = cli.out_format.expect("--out-format is required by clap");
+            (&cli.input, convert(&cli, out_format).map(|_| ExitCode::SUCCESS))
+        }
+    };
+    res.unwrap_or_else(|e| {
+        report(&e, input.source());
+        ExitCode::FAILURE
+    })
+}
+
+/// Печатает ошибку в stderr; ошибку разбора — с местом и строкой входа.
+fn report(e: &FinioError, source: &str) {
+    match e.diagnostic() {
+        Some(d) => render("error", d, source),
+        None => eprintln!("error: {e}"),
+    }
+}
+
+fn render(severity: &str, d: &Diagnostic, source: &str) {
+    eprintln!("{severity}[{}]: {}", d.code, d.message);
+    match &d.location {
+        Some(Location::Line { line, column }) => {
+            let col = column.map(|c| format!(":{c}")).unwrap_or_default();
+            eprintln!("  --> {source}:{line}{col}");
+        }
+        Some(Location::Record { record, line }) => eprintln!("  --> {source}:{line} (record {record})"),
+        Some(Location::Xml { path, offset }) => {
+            let at = offset.map(|o| format!(" (byte {o})")).unwrap_or_default();
+            eprintln!("  --> {source}: {path}{at}");
+        }
+        None => {}
+    }
+    if let Some(snippet) = &d.snippet {
+        let line = match d.location {
+            Some(Location::Line { line, .. } | Location::Record { line, .. }) => line.to_string(),
+            _ => String::new(),
+        };
+        let pad = " ".repeat(line.len());
+        eprintln!("{pad} |");
+        eprintln!("{line} | {snippet}");
+        // подчёркиваем значение, если известна колонка
+        if let (Some(Location::Line { column: Some(c), .. }), Some(v)) = (&d.location, &d.value) {
+            let marks = "^".repeat(v.chars().count().max(1));
+            eprintln!("{pad} | {}{marks}", " ".repeat(c - 1));
+        }
+    }
+    if let Some(field) = &d.field {
+        eprintln!("  = field: {field}");
+    }
+    if let Some(v) = &d.value {
+        eprintln!("  = value: {v:?}");
+    }
+}
+
+fn convert(cli: &Cli, out_format: Fmt) -> Result<()> {
+    let camt_version = cli.camt_version;
+
+    // writer
+    let mut writer: Box<dyn Write> = match cli.output.as_deref() {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+    if let Some(enc) = output_encoding(out_format, cli.out_encoding)? {
+        writer = Box::new(EncodingWriter::new(writer, enc)?);
+    }
+
+    let mut sink: Box<dyn StatementWriter> = match out_format {
+        Fmt::Csv => Box::new(Csv::writer(writer)),
+        Fmt::Xml => Box::new(SimpleXml::writer(writer)),
+        Fmt::Mt940 => Box::new(Mt940::writer(writer).layout86(cli.mt86_layout)),
+        Fmt::Mt942 => Box::new(Mt942::writer(writer)),
+        Fmt::Camt052 => Box::new(Camt052::writer(writer).version(camt_version)),
+        Fmt::Camt053 => Box::new(Camt053::writer(writer).version(camt_version)),
+        Fmt::Camt054 => Box::new(Camt054::writer(writer).version(camt_version)),
+        Fmt::OneC => Box::new(OneC::writer(writer)),
+    };
+
+    let view = View { expand_batches: cli.expand_batches, status: cli.status };
+    pump_input(&cli.input, cli.mt86_layout, view, sink.as_mut())
+}
+
+/// Преобразования потока проводок перед записью.
+#[derive(Debug, Default, Clone, Copy)]
+struct View {
+    expand_batches: bool,
+    status: Option<EntryStatus>,
+}
+
+/// Прогоняет вход через `sink`. В мягком режиме испорченные записи
+/// пропускаются, а после разбора печатаются предупреждения и итог.
+fn pump_input(args: &InputArgs, layout: Layout86, view: View, sink: &mut dyn StatementWriter) -> Result<()> {
+    let mut events = open_input(args, layout)?;
+    if view.expand_batches {
+        events = Box::new(convert::expand_batches(events));
+    }
+    if let Some(status) = view.status {
+        events = Box::new(convert::filter_status(events, status));
+    }
+    if args.mode() == ParseMode::Strict {
+        return stream::pump(events, sink);
+    }
+
+    let mut skipped = Vec::new();
+    let res = stream::pump(stream::skip_diagnostics(events, |d| skipped.push(d)), sink);
+    for d in &skipped {
+        render("warning", d, args.source());
+    }
+    if !skipped.is_empty() {
+        eprintln!("warning: {} record(s) skipped", skipped.len());
+    }
+    res
+}
+
+/// Перекодировка выхода, если она нужна. XML объявляет UTF-8 в прологе,
+/// а 1С — Windows-1251 в заголовке, поэтому у них кодировка фиксирована.
+fn output_encoding(out_format: Fmt, enc: Option<Encoding>) -> Result<Option<Encoding>> {
+    let fixed = match out_format {
+        Fmt::Xml | Fmt::Camt052 | Fmt::Camt053 | Fmt::Camt054 => Encoding::Utf8,
+        Fmt::OneC => Encoding::Windows1251,
+        Fmt::Csv | Fmt::Mt940 | Fmt::Mt942 => {
+            return Ok(enc.filter(|&e| e != Encoding::Utf8));
+        }
+    };
+    match enc {
+        Some(e) if e != fixed => Err(FinioError::Unsupported(
+            "--out-encoding is fixed for this format (XML: UTF-8, 1C: Windows-1251)",
+        )),
+        _ => Ok(None),
+    }
+}
+
+/// Печатает замечания; код возврата 1, если есть ошибки.
+fn validate(input: &InputArgs) -> Result<ExitCode> {
+    let mut v = Validator::new();
+    pump_input(input, Layout86::default(), View::default(), &mut v)?;
+
+    let findings = v.into_findings();
+    for f in &findings {
+        println!("{f}");
+    }
+    if has_errors(&findings) {
+        Ok(ExitCode::FAILURE)
+    } else {
+        println!("OK");
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Открывает вход и потоковый reader; формат, если не задан, определяется.
+fn open_input(args: &InputArgs, layout: Layout86) -> Result<Box<dyn StatementReader>> {
+    let profile = args.csv_profile.as_deref().map(CsvProfile::load).transpose()?;
+
     // reader
-    let reader: Box<dyn io::Read> = match cli.input {
+    let raw: Box<dyn io::Read> = match &args.input {
         Some(path) => Box::new(File::open(path)?),
         None => Box::new(io::stdin()),
     };
-    let br = BufReader::new(reader);
 
-    let st = match cli.in_format {
-        Fmt::Csv => Csv::read(br),
-        Fmt::Xml => SimpleXml::read(br),
-        Fmt::Mt940 => Mt940::read(br),
-        Fmt::Camt053 => Camt053::read(br),
-    }?;
+    // дальше всё в UTF-8: кодировка задана явно или определяется по началу
+    let mut reader: Box<dyn io::Read> = match args.in_encoding {
+        Some(enc) => Box::new(DecodingReader::new(raw, enc)),
+        None => Box::new(DecodingReader::detect(raw)?),
+    };
 
-    // writer
-    let mut writer: Box<dyn Write> = match cli.output {
-        Some(path) => Box::new(File::create(path)?),
-        None => Box::new(io::stdout()),
+    let in_format = match args.in_format {
+        _ if profile.is_some() && args.in_format.is_some_and(|f| f != Fmt::Csv) => {
+            return Err(FinioError::Unsupported("--csv-profile applies to CSV input only"));
+        }
+        // выгрузку банка по содержимому не опознать
+        _ if profile.is_some() => Fmt::Csv,
+        Some(f) => f,
+        None => {
+            // читаем «голову» для определения формата и возвращаем её в поток
+            let mut head = Vec::with_capacity(SNIFF_LEN);
+            (&mut reader).take(SNIFF_LEN as u64).read_to_end(&mut head)?;
+            let f = detect_format(&head, args.input.as_deref())?;
+            reader = Box::new(Cursor::new(head).chain(reader));
+            f
+        }
     };
+    let br = BufReader::new(reader);
+    let mode = args.mode();
 
-    match cli.out_format {
-        Fmt::Csv => Csv::write(&mut writer, &st),
-        Fmt::Xml => SimpleXml::write(&mut writer, &st),
-        Fmt::Mt940 => Mt940::write(&mut writer, &st),
-        Fmt::Camt053 => Camt053::write(&mut writer, &st),
-    }?;
+    // во входе может быть несколько выписок — конвертируем все, потоково
+    Ok(match in_format {
+        Fmt::Csv => match profile {
+            Some(p) => Box::new(Csv::reader_with(br, mode).profile(p)),
+            None => Box::new(Csv::reader_with(br, mode)),
+        },
+        Fmt::Xml => Box::new(SimpleXml::reader_with(br, mode)),
+        Fmt::Mt940 => Box::new(Mt940::reader_with(br, mode).layout86(layout)),
+        Fmt::Mt942 => Box::new(Mt942::reader_with(br, mode)),
+        Fmt::Camt052 => Box::new(Camt052::reader_with(br, mode)),
+        Fmt::Camt053 => Box::new(Camt053::reader_with(br, mode)),
+        Fmt::Camt054 => Box::new(Camt054::reader_with(br, mode)),
+        Fmt::OneC => Box::new(OneC::reader_with(br, mode)),
+    })
+}
 
-    writer.flush().map_err(FinioError::from)
+/// Содержимое надёжнее расширения; расширение — запасной вариант.
+fn detect_format(head: &[u8], input: Option<&str>) -> Result<Fmt> {
+    let by_content = detect::detect(head);
+    let by_ext = input.and_then(|p| detect::from_extension(Path::new(p)));
+    let best = match (by_content, by_ext) {
+        (Some(c), Some(e)) if e.confidence > c.confidence => Some(e),
+        (Some(c), _) => Some(c),
+        (None, e) => e,
+    };
+    match best.map(|d| d.format) {
+        Some(FormatKind::Csv) => Ok(Fmt::Csv),
+        Some(FormatKind::SimpleXml) => Ok(Fmt::Xml),
+        Some(FormatKind::Mt940) => Ok(Fmt::Mt940),
+        Some(FormatKind::Mt942) => Ok(Fmt::Mt942),
+        Some(FormatKind::Camt052) => Ok(Fmt::Camt052),
+        Some(FormatKind::Camt053) => Ok(Fmt::Camt053),
+        Some(FormatKind::Camt054) => Ok(Fmt::Camt054),
+        Some(FormatKind::OneC) => Ok(Fmt::OneC),
+        None => Err(FinioError::Unsupported(
+            "cannot detect input format, pass --in-format",
+        )),
+    }
 }
@@ -0,0 +1,244 @@
+//! Идентификаторы счёта и банка: IBAN (ISO 13616), российский счёт с БИК,
+//! прочие номера (BBAN и т. п.), BIC (ISO 9362).
+//!
+//! Readers идентификаторы не проверяют — испорченный IBAN из банка всё равно
+//! попадает в выписку как IBAN; контрольные суммы проверяет `validate`.
+
+use crate::model::Counterparty;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+
+/// Счёт выписки.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AccountId {
+    /// IBAN без пробелов, в верхнем регистре
+    Iban(String),
+    /// российский счёт (20 цифр) и БИК банка, если известен
+    Ru { account: String, bik: Option<String> },
+    /// BBAN или иной номер; `scheme` — схема (`SchmeNm` в CAMT, например `BBAN`)
+    Other { id: String, scheme: Option<String> },
+}
+
+impl AccountId {
+    /// Вид идентификатора по форме: IBAN, `БИК/счёт` или 20 цифр — российский
+    /// счёт, остальное — `Other` без схемы.
+    pub fn parse(s: &str) -> AccountId {
+        let s = s.trim();
+        let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_ascii_uppercase();
+        if looks_like_iban(&compact) {
+            return AccountId::Iban(compact);
+        }
+        let digits = |v: &str, n: usize| v.len() == n && v.bytes().all(|b| b.is_ascii_digit());
+        match s.split_once('/') {
+            Some((bik, account)) if digits(bik, 9) && digits(account, 20) => {
+                AccountId::Ru { account: account.to_string(), bik: Some(bik.to_string()) }
+            }
+            _ if digits(s, 20) => AccountId::Ru { account: s.to_string(), bik: None },
+            _ => AccountId::Other { id: s.to_string(), scheme: None },
+        }
+    }
+
+    /// Номер счёта без БИК и схемы.
+    pub fn number(&self) -> &str {
+        match self {
+            AccountId::Iban(iban) => iban,
+            AccountId::Ru { account, .. } => account,
+            AccountId::Other { id, .. } => id,
+        }
+    }
+
+    /// Проверка контрольных сумм: mod 97 и длина по стране для IBAN, ключ
+    /// по БИК для российского счёта. `Other` не проверяется.
+    pub fn check(&self) -> std::result::Result<(), &'static str> {
+        match self {
+            AccountId::Iban(iban) => check_iban(iban),
+            AccountId::Ru { account, bik } => {
+                if account.len() != 20 || !account.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err("expected 20 digits");
+                }
+                match bik {
+                    Some(bik) if !is_bik(bik) => Err("BIK must be 9 digits"),
+                    Some(bik) if !ru_key_ok(account, bik) => Err("control key does not match BIK"),
+                    _ => Ok(()),
+                }
+            }
+            AccountId::Other { .. } => Ok(()),
+        }
+    }
+}
+
+impl Default for AccountId {
+    fn default() -> Self {
+        AccountId::Other { id: String::new(), scheme: None }
+    }
+}
+
+/// Российский счёт с БИК пишется как `БИК/счёт` (так его и разбирает `parse`).
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountId::Ru { account, bik: Some(bik) } => write!(f, "{bik}/{account}"),
+            other => f.write_str(other.number()),
+        }
+    }
+}
+
+impl From<&str> for AccountId {
+    fn from(s: &str) -> Self {
+        AccountId::parse(s)
+    }
+}
+
+impl From<String> for AccountId {
+    fn from(s: String) -> Self {
+        AccountId::parse(&s)
+    }
+}
+
+impl PartialEq<&str> for AccountId {
+    fn eq(&self, other: &&str) -> bool {
+        match self {
+            AccountId::Ru { account, bik: Some(bik) } => {
+                other.split_once('/') == Some((bik.as_str(), account.as_str()))
+            }
+            id => id.number() == *other,
+        }
+    }
+}
+
+/// BIC банка (ISO 9362). У российских банков в этом поле часто БИК (9 цифр).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Bic(String);
+
+impl Bic {
+    /// 4 буквы банка, 2 буквы страны, 2 знака места, необязательные 3 знака филиала.
+    pub fn is_valid(&self) -> bool {
+        let b = self.0.as_bytes();
+        matches!(b.len(), 8 | 11)
+            && b[..6].iter().all(u8::is_ascii_uppercase)
+            && b[6..].iter().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    }
+
+    /// БИК Банка России вместо BIC.
+    pub fn is_bik(&self) -> bool {
+        is_bik(&self.0)
+    }
+}
+
+impl Deref for Bic {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Bic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for Bic {
+    fn from(s: &str) -> Self {
+        Bic(s.trim().to_ascii_uppercase())
+    }
+}
+
+impl From<String> for Bic {
+    fn from(s: String) -> Self {
+        Bic::from(s.as_str())
+    }
+}
+
+impl Counterparty {
+    /// Счёт контрагента; к российскому счёту подставляется БИК из `bic`.
+    pub fn account_id(&self) -> Option<AccountId> {
+        let id = AccountId::parse(self.account.as_deref()?);
+        Some(match id {
+            AccountId::Ru { account, bik: None } => {
+                let bik = self.bic.as_ref().filter(|b| b.is_bik()).map(ToString::to_string);
+                AccountId::Ru { account, bik }
+            }
+            id => id,
+        })
+    }
+}
+
+/// Остаток от деления на 97 числа, в котором буквы заменены на `A` = 10 … `Z` = 35.
+pub(crate) fn mod97<'a>(chars: impl Iterator<Item = &'a u8>) -> u32 {
+    chars.fold(0u32, |acc, &c| {
+        let v = match c {
+            b'0'..=b'9' => u32::from(c - b'0'),
+            _ => u32::from(c.to_ascii_uppercase() - b'A') + 10,
+        };
+        let acc = if v >= 10 { acc * 100 + v } else { acc * 10 + v };
+        acc % 97
+    })
+}
+
+/// Грубая проверка формы IBAN: страна, две цифры, 11–30 букв/цифр.
+fn looks_like_iban(s: &str) -> bool {
+    let b = s.as_bytes();
+    (15..=34).contains(&b.len())
+        && b[..2].iter().all(u8::is_ascii_uppercase)
+        && b[2..4].iter().all(u8::is_ascii_digit)
+        && b[4..].iter().all(u8::is_ascii_alphanumeric)
+}
+
+fn check_iban(iban: &str) -> std::result::Result<(), &'static str> {
+    let b = iban.as_bytes();
+    if !looks_like_iban(iban) {
+        return Err("malformed IBAN");
+    }
+    let len = IBAN_LENGTHS
+        .iter()
+        .find(|(country, _)| country.as_bytes() == &b[..2])
+        .map(|&(_, len)| len)
+        .ok_or("unknown IBAN country")?;
+    if b.len() != len {
+        return Err("IBAN length does not match country");
+    }
+    if mod97(b[4..].iter().chain(&b[..4])) != 1 {
+        return Err("IBAN check digits do not match");
+    }
+    Ok(())
+}
+
+fn is_bik(s: &str) -> bool {
+    s.len() == 9 && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Контрольный ключ счёта: три цифры БИК и 20 цифр счёта с весами 7, 1, 3;
+/// сумма младших разрядов произведений кратна 10. Для корсчёта в Банке
+/// России вместо трёх последних цифр БИК — «0» и его 5–6 цифры.
+fn ru_key_ok(account: &str, bik: &str) -> bool {
+    let (a, k) = (account.as_bytes(), bik.as_bytes());
+    let prefix = if account.starts_with("301") { [b'0', k[4], k[5]] } else { [k[6], k[7], k[8]] };
+    let sum: u32 = prefix
+        .iter()
+        .chain(a)
+        .zip([7, 1, 3].iter().cycle())
+        .map(|(&d, &w)| u32::from(d - b'0') * w % 10)
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Длина IBAN по странам (реестр SWIFT).
+const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24), ("AE", 23), ("AL", 28), ("AT", 20), ("AZ", 28), ("BA", 20), ("BE", 16),
+    ("BG", 22), ("BH", 22), ("BI", 27), ("BR", 29), ("BY", 28), ("CH", 21), ("CR", 22),
+    ("CY", 28), ("CZ", 24), ("DE", 22), ("DJ", 27), ("DK", 18), ("DO", 28), ("EE", 20),
+    ("EG", 29), ("ES", 24), ("FI", 18), ("FK", 18), ("FO", 18), ("FR", 27), ("GB", 22),
+    ("GE", 22), ("GI", 23), ("GL", 18), ("GR", 27), ("GT", 28), ("HN", 28), ("HR", 21),
+    ("HU", 28), ("IE", 22), ("IL", 23), ("IQ", 23), ("IS", 26), ("IT", 27), ("JO", 30),
+    ("KW", 30), ("KZ", 20), ("LB", 28), ("LC", 32), ("LI", 21), ("LT", 20), ("LU", 20),
+    ("LV", 21), ("LY", 25), ("MC", 27), ("MD", 24), ("ME", 22), ("MK", 19), ("MN", 20),
+    ("MR", 27), ("MT", 31), ("MU", 30), ("NI", 28), ("NL", 18), ("NO", 15), ("OM", 23),
+    ("PK", 24), ("PL", 28), ("PS", 29), ("PT", 25), ("QA", 29), ("RO", 24), ("RS", 22),
+    ("RU", 33), ("SA", 24), ("SC", 31), ("SD", 18), ("SE", 24), ("SI", 19), ("SK", 24),
+    ("SM", 27), ("SO", 23), ("ST", 25), ("SV", 28), ("TL", 23), ("TN", 24), ("TR", 26),
+    ("UA", 29), ("VA", 22), ("VG", 24), ("XK", 20), ("YE", 30),
+];
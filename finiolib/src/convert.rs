@@ -1,5 +1,64 @@
 //! Общие операции преобразований между форматами через Statement.
-use crate::model::Statement;
+use crate::{
+    error::Result,
+    model::{Entry, EntryStatus, Statement},
+    stream::StatementEvent,
+};
 
 pub fn normalize_for_mt940(st: Statement) -> Statement { st }
 pub fn normalize_for_camt(st: Statement) -> Statement { st }
+
+/// Раскрывает пакетную проводку в проводки по операциям: сумма, назначение,
+/// контрагент и EndToEndId берутся из операции, остальное — из проводки.
+/// Проводка с одной операцией или операциями без сумм возвращается как есть.
+pub fn expand_batch(e: Entry) -> Vec<Entry> {
+    if e.details.len() < 2 || e.details.iter().any(|d| d.amount.is_none()) {
+        return vec![e];
+    }
+    let mut batch = e;
+    let details = std::mem::take(&mut batch.details);
+    details
+        .into_iter()
+        .map(|mut d| {
+            let mut e = batch.clone();
+            e.amount = d.amount.unwrap_or(e.amount);
+            if let Some(ccy) = d.currency {
+                e.currency = ccy;
+            }
+            if let Some(r) = &d.refs.end_to_end_id {
+                e.reference = Some(r.clone());
+            }
+            if !d.remittance.unstructured.is_empty() {
+                e.description = d.remittance.unstructured.join(" ");
+            }
+            e.counterparty = d.counterparty.take().or(e.counterparty);
+            e.remittance = std::mem::take(&mut d.remittance);
+            e.details = vec![d];
+            e
+        })
+        .collect()
+}
+
+/// Оставляет в потоке только проводки в состоянии `status`.
+pub fn filter_status<I>(events: I, status: EntryStatus) -> impl Iterator<Item = Result<StatementEvent>>
+where
+    I: Iterator<Item = Result<StatementEvent>>,
+{
+    events.filter(move |ev| !matches!(ev, Ok(StatementEvent::Entry(e)) if e.status != status))
+}
+
+/// `expand_batch` для каждой проводки потока.
+pub fn expand_batches<I>(events: I) -> impl Iterator<Item = Result<StatementEvent>>
+where
+    I: Iterator<Item = Result<StatementEvent>>,
+{
+    events.flat_map(|ev| -> Vec<Result<StatementEvent>> {
+        match ev {
+            Ok(StatementEvent::Entry(e)) => expand_batch(*e)
+                .into_iter()
+                .map(|e| Ok(StatementEvent::Entry(Box::new(e))))
+                .collect(),
+            other => vec![other],
+        }
+    })
+}
@@ -0,0 +1,186 @@
+//! Валюта ISO 4217: буквенный и цифровой код, число знаков после запятой.
+//!
+//! Суммы в модели хранятся как есть; при записи каждый writer приводит их
+//! к числу знаков валюты (`Currency::scale`): 10.5 EUR → 10.50, 100 JPY → 100,
+//! 1.2 BHD → 1.200. Лишние значащие знаки — ошибка, а не молчаливое округление.
+
+use crate::error::{Diagnostic, ErrorCode, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Валюта из таблицы ISO 4217.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency(&'static Iso4217);
+
+#[derive(PartialEq, Eq, Hash)]
+struct Iso4217 {
+    code: &'static str,
+    numeric: u16,
+    /// `None` — у валюты нет дробных единиц по ISO (металлы, `XXX`, `XDR`)
+    minor_units: Option<u32>,
+}
+
+impl Currency {
+    pub const EUR: Currency = Currency(&Iso4217 { code: "EUR", numeric: 978, minor_units: Some(2) });
+    pub const RUB: Currency = Currency(&Iso4217 { code: "RUB", numeric: 643, minor_units: Some(2) });
+    pub const USD: Currency = Currency(&Iso4217 { code: "USD", numeric: 840, minor_units: Some(2) });
+    /// «Без валюты» — для проводок, валюта которых во входе не указана.
+    pub const XXX: Currency = Currency(&Iso4217 { code: "XXX", numeric: 999, minor_units: None });
+
+    /// Валюта по цифровому коду (`643` → RUB).
+    pub fn from_numeric(numeric: u16) -> Option<Currency> {
+        TABLE.iter().find(|c| c.numeric == numeric).map(Currency)
+    }
+
+    pub fn code(self) -> &'static str {
+        self.0.code
+    }
+
+    pub fn numeric(self) -> u16 {
+        self.0.numeric
+    }
+
+    pub fn minor_units(self) -> Option<u32> {
+        self.0.minor_units
+    }
+
+    /// Сумма с числом знаков валюты; для валют без дробных единиц ISO — как есть.
+    pub fn scale(self, amount: Decimal) -> Result<Decimal> {
+        let Some(units) = self.0.minor_units else {
+            return Ok(amount);
+        };
+        let mut a = amount.normalize();
+        if a.scale() > units {
+            let msg = format!("{} allows {units} decimal place(s)", self.0.code);
+            return Err(Diagnostic::new(ErrorCode::InvalidAmount, msg).value(amount.to_string()).into());
+        }
+        a.rescale(units);
+        Ok(a)
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.code)
+    }
+}
+
+impl fmt::Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.code)
+    }
+}
+
+impl PartialEq<&str> for Currency {
+    fn eq(&self, other: &&str) -> bool {
+        self.0.code == *other
+    }
+}
+
+/// Буквенный код; регистр не важен.
+impl FromStr for Currency {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let code = match s.trim().to_ascii_uppercase() {
+            // выведенный код рубля, до сих пор встречается в выписках
+            c if c == "RUR" => "RUB".to_string(),
+            c => c,
+        };
+        TABLE
+            .iter()
+            .find(|c| c.code == code)
+            .map(Currency)
+            .ok_or_else(|| format!("unknown ISO 4217 currency {s:?}"))
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Currency> for String {
+    fn from(c: Currency) -> String {
+        c.0.code.to_string()
+    }
+}
+
+/// Разбор кода валюты из поля `field` входного формата.
+pub(crate) fn parse_currency(s: &str, field: &str) -> Result<Currency> {
+    s.parse().map_err(|msg: String| Diagnostic::new(ErrorCode::InvalidValue, msg).field(field).value(s).into())
+}
+
+const fn iso(code: &'static str, numeric: u16, minor_units: Option<u32>) -> Iso4217 {
+    Iso4217 { code, numeric, minor_units }
+}
+
+/// Действующие валюты и фонды ISO 4217.
+static TABLE: &[Iso4217] = &[
+    iso("AED", 784, Some(2)), iso("AFN", 971, Some(2)), iso("ALL", 8, Some(2)),
+    iso("AMD", 51, Some(2)), iso("AOA", 973, Some(2)),
+    iso("ARS", 32, Some(2)), iso("AUD", 36, Some(2)), iso("AWG", 533, Some(2)),
+    iso("AZN", 944, Some(2)), iso("BAM", 977, Some(2)), iso("BBD", 52, Some(2)),
+    iso("BDT", 50, Some(2)), iso("BGN", 975, Some(2)), iso("BHD", 48, Some(3)),
+    iso("BIF", 108, Some(0)), iso("BMD", 60, Some(2)), iso("BND", 96, Some(2)),
+    iso("BOB", 68, Some(2)), iso("BOV", 984, Some(2)), iso("BRL", 986, Some(2)),
+    iso("BSD", 44, Some(2)), iso("BTN", 64, Some(2)), iso("BWP", 72, Some(2)),
+    iso("BYN", 933, Some(2)), iso("BZD", 84, Some(2)), iso("CAD", 124, Some(2)),
+    iso("CDF", 976, Some(2)), iso("CHE", 947, Some(2)), iso("CHF", 756, Some(2)),
+    iso("CHW", 948, Some(2)), iso("CLF", 990, Some(4)), iso("CLP", 152, Some(0)),
+    iso("CNY", 156, Some(2)), iso("COP", 170, Some(2)), iso("COU", 970, Some(2)),
+    iso("CRC", 188, Some(2)), iso("CUP", 192, Some(2)), iso("CVE", 132, Some(2)),
+    iso("CZK", 203, Some(2)), iso("DJF", 262, Some(0)), iso("DKK", 208, Some(2)),
+    iso("DOP", 214, Some(2)), iso("DZD", 12, Some(2)), iso("EGP", 818, Some(2)),
+    iso("ERN", 232, Some(2)), iso("ETB", 230, Some(2)), iso("EUR", 978, Some(2)),
+    iso("FJD", 242, Some(2)), iso("FKP", 238, Some(2)), iso("GBP", 826, Some(2)),
+    iso("GEL", 981, Some(2)), iso("GHS", 936, Some(2)), iso("GIP", 292, Some(2)),
+    iso("GMD", 270, Some(2)), iso("GNF", 324, Some(0)), iso("GTQ", 320, Some(2)),
+    iso("GYD", 328, Some(2)), iso("HKD", 344, Some(2)), iso("HNL", 340, Some(2)),
+    iso("HTG", 332, Some(2)), iso("HUF", 348, Some(2)), iso("IDR", 360, Some(2)),
+    iso("ILS", 376, Some(2)), iso("INR", 356, Some(2)), iso("IQD", 368, Some(3)),
+    iso("IRR", 364, Some(2)), iso("ISK", 352, Some(0)), iso("JMD", 388, Some(2)),
+    iso("JOD", 400, Some(3)), iso("JPY", 392, Some(0)), iso("KES", 404, Some(2)),
+    iso("KGS", 417, Some(2)), iso("KHR", 116, Some(2)), iso("KMF", 174, Some(0)),
+    iso("KPW", 408, Some(2)), iso("KRW", 410, Some(0)), iso("KWD", 414, Some(3)),
+    iso("KYD", 136, Some(2)), iso("KZT", 398, Some(2)), iso("LAK", 418, Some(2)),
+    iso("LBP", 422, Some(2)), iso("LKR", 144, Some(2)), iso("LRD", 430, Some(2)),
+    iso("LSL", 426, Some(2)), iso("LYD", 434, Some(3)), iso("MAD", 504, Some(2)),
+    iso("MDL", 498, Some(2)), iso("MGA", 969, Some(2)), iso("MKD", 807, Some(2)),
+    iso("MMK", 104, Some(2)), iso("MNT", 496, Some(2)), iso("MOP", 446, Some(2)),
+    iso("MRU", 929, Some(2)), iso("MUR", 480, Some(2)), iso("MVR", 462, Some(2)),
+    iso("MWK", 454, Some(2)), iso("MXN", 484, Some(2)), iso("MXV", 979, Some(2)),
+    iso("MYR", 458, Some(2)), iso("MZN", 943, Some(2)), iso("NAD", 516, Some(2)),
+    iso("NGN", 566, Some(2)), iso("NIO", 558, Some(2)), iso("NOK", 578, Some(2)),
+    iso("NPR", 524, Some(2)), iso("NZD", 554, Some(2)), iso("OMR", 512, Some(3)),
+    iso("PAB", 590, Some(2)), iso("PEN", 604, Some(2)), iso("PGK", 598, Some(2)),
+    iso("PHP", 608, Some(2)), iso("PKR", 586, Some(2)), iso("PLN", 985, Some(2)),
+    iso("PYG", 600, Some(0)), iso("QAR", 634, Some(2)), iso("RON", 946, Some(2)),
+    iso("RSD", 941, Some(2)), iso("RUB", 643, Some(2)), iso("RWF", 646, Some(0)),
+    iso("SAR", 682, Some(2)), iso("SBD", 90, Some(2)), iso("SCR", 690, Some(2)),
+    iso("SDG", 938, Some(2)), iso("SEK", 752, Some(2)), iso("SGD", 702, Some(2)),
+    iso("SHP", 654, Some(2)), iso("SLE", 925, Some(2)), iso("SOS", 706, Some(2)),
+    iso("SRD", 968, Some(2)), iso("SSP", 728, Some(2)), iso("STN", 930, Some(2)),
+    iso("SVC", 222, Some(2)), iso("SYP", 760, Some(2)), iso("SZL", 748, Some(2)),
+    iso("THB", 764, Some(2)), iso("TJS", 972, Some(2)), iso("TMT", 934, Some(2)),
+    iso("TND", 788, Some(3)), iso("TOP", 776, Some(2)), iso("TRY", 949, Some(2)),
+    iso("TTD", 780, Some(2)), iso("TWD", 901, Some(2)), iso("TZS", 834, Some(2)),
+    iso("UAH", 980, Some(2)), iso("UGX", 800, Some(0)), iso("USD", 840, Some(2)),
+    iso("USN", 997, Some(2)), iso("UYI", 940, Some(0)), iso("UYU", 858, Some(2)),
+    iso("UYW", 927, Some(4)), iso("UZS", 860, Some(2)), iso("VED", 926, Some(2)),
+    iso("VES", 928, Some(2)), iso("VND", 704, Some(0)), iso("VUV", 548, Some(0)),
+    iso("WST", 882, Some(2)), iso("XAF", 950, Some(0)), iso("XAG", 961, None),
+    iso("XAU", 959, None), iso("XBA", 955, None), iso("XBB", 956, None),
+    iso("XBC", 957, None), iso("XBD", 958, None), iso("XCD", 951, Some(2)),
+    iso("XCG", 532, Some(2)), iso("XDR", 960, None), iso("XOF", 952, Some(0)),
+    iso("XPD", 964, None), iso("XPF", 953, Some(0)), iso("XPT", 962, None),
+    iso("XSU", 994, None), iso("XTS", 963, None), iso("XUA", 965, None),
+    iso("XXX", 999, None), iso("YER", 886, Some(2)), iso("ZAR", 710, Some(2)),
+    iso("ZMW", 967, Some(2)), iso("ZWG", 924, Some(2)),
+];
@@ -0,0 +1,183 @@
+//! Определение формата входа по содержимому и расширению файла.
+//!
+//! Анализируется только начало потока (`SNIFF_LEN` байт), поэтому вызывающий
+//! код может прочитать «голову», определить формат и склеить её обратно
+//! с остатком потока (`Cursor::new(head).chain(rest)`).
+
+use crate::formats::csv::COLUMNS;
+use std::path::Path;
+
+/// Сколько байт начала потока достаточно для определения формата.
+pub const SNIFF_LEN: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormatKind {
+    Csv,
+    SimpleXml,
+    Mt940,
+    Mt942,
+    Camt052,
+    Camt053,
+    Camt054,
+    OneC,
+}
+
+/// Кандидат с уверенностью 0..=100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Detection {
+    pub format: FormatKind,
+    pub confidence: u8,
+}
+
+/// Все подходящие форматы по содержимому, от наиболее вероятного.
+pub fn candidates(head: &[u8]) -> Vec<Detection> {
+    let head = &head[..head.len().min(SNIFF_LEN)];
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start_matches('\u{feff}');
+
+    let mut out: Vec<Detection> = [
+        (FormatKind::Mt940, score_mt940(text)),
+        (FormatKind::Mt942, score_mt942(text)),
+        (FormatKind::Camt052, score_camt(text, "camt.052", "<BkToCstmrAcctRpt")),
+        (FormatKind::Camt053, score_camt(text, "camt.053", "<BkToCstmrStmt")),
+        (FormatKind::Camt054, score_camt(text, "camt.054", "<BkToCstmrDbtCdtNtfctn")),
+        (FormatKind::OneC, score_onec(text)),
+        (FormatKind::SimpleXml, score_simple_xml(text)),
+        (FormatKind::Csv, score_csv(text)),
+    ]
+    .into_iter()
+    .filter(|&(_, confidence)| confidence > 0)
+    .map(|(format, confidence)| Detection { format, confidence })
+    .collect();
+
+    out.sort_by_key(|d| std::cmp::Reverse(d.confidence));
+    out
+}
+
+/// Наиболее вероятный формат по содержимому.
+pub fn detect(head: &[u8]) -> Option<Detection> {
+    candidates(head).into_iter().next()
+}
+
+/// Формат по расширению файла; уверенность ниже, чем у анализа содержимого.
+pub fn from_extension(path: &Path) -> Option<Detection> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+
+    let (format, confidence) = match ext.as_str() {
+        "csv" => (FormatKind::Csv, 60),
+        "mt940" | "940" | "sta" | "mt" => (FormatKind::Mt940, 60),
+        "mt942" | "942" => (FormatKind::Mt942, 60),
+        "camt" | "053" => (FormatKind::Camt053, 60),
+        "052" => (FormatKind::Camt052, 60),
+        "054" => (FormatKind::Camt054, 60),
+        // *.xml неоднозначен: CAMT обычно выдаёт себя именем файла
+        "xml" if name.contains("052") => (FormatKind::Camt052, 50),
+        "xml" if name.contains("054") => (FormatKind::Camt054, 50),
+        "xml" if name.contains("camt") || name.contains("053") => (FormatKind::Camt053, 50),
+        "xml" => (FormatKind::SimpleXml, 30),
+        // выгрузки 1С: kl_to_1c.txt, 1c_to_kl.txt
+        "txt" if name.contains("1c") => (FormatKind::OneC, 50),
+        _ => return None,
+    };
+    Some(Detection { format, confidence })
+}
+
+/* ------------------------------ SCORING --------------------------------- */
+
+fn has_tag(text: &str, tag: &str) -> bool {
+    text.lines().any(|l| l.trim_start().starts_with(tag))
+}
+
+fn score_mt940(text: &str) -> u8 {
+    let has = |tag: &str| has_tag(text, tag);
+    if !has(":20:") {
+        return 0;
+    }
+    // :34F:/:13D: бывают только в MT942
+    if has(":34F:") || has(":13D:") {
+        return 30;
+    }
+    let extra = [":25:", ":28C:", ":60F:", ":61:", ":62F:"]
+        .iter()
+        .filter(|t| has(t))
+        .count() as u8;
+    (60 + extra * 10).min(100)
+}
+
+fn score_mt942(text: &str) -> u8 {
+    let has = |tag: &str| has_tag(text, tag);
+    match (has(":20:"), has(":34F:"), has(":13D:")) {
+        (true, true, true) => 100,
+        (true, true, false) | (true, false, true) => 90,
+        _ => 0,
+    }
+}
+
+/// `message` — "camt.05x" из пространства имён, `root` — начало корневого элемента.
+fn score_camt(text: &str, message: &str, root: &str) -> u8 {
+    if text.contains(&format!("urn:iso:std:iso:20022:tech:xsd:{message}")) {
+        100
+    } else if text.contains(root) {
+        80
+    } else {
+        0
+    }
+}
+
+/// Первая строка файла обмена 1С — ASCII, поэтому кодировка не мешает.
+fn score_onec(text: &str) -> u8 {
+    if text.trim_start().starts_with("1CClientBankExchange") {
+        100
+    } else {
+        0
+    }
+}
+
+fn score_simple_xml(text: &str) -> u8 {
+    match root_element(text) {
+        Some("XmlStatement" | "XmlStatements") => 95,
+        Some("Statement") => 80,
+        _ => 0,
+    }
+}
+
+fn score_csv(text: &str) -> u8 {
+    let Some(header) = text.lines().find(|l| !l.trim().is_empty()) else {
+        return 0;
+    };
+    let cols: Vec<&str> = header.split(',').map(|c| c.trim().trim_matches('"')).collect();
+    let known = cols.iter().filter(|c| COLUMNS.contains(c)).count();
+    if known == 0 {
+        return 0;
+    }
+    // колонки, без которых Csv::read не работает
+    let required = ["booking_date", "amount", "currency", "dc", "description", "account_id"];
+    if required.iter().all(|r| cols.contains(r)) {
+        100
+    } else {
+        (known * 60 / COLUMNS.len()) as u8
+    }
+}
+
+/// Имя корневого элемента: пропускаем пролог, комментарии и DOCTYPE.
+fn root_element(text: &str) -> Option<&str> {
+    let mut rest = text;
+    loop {
+        rest = rest.trim_start();
+        if let Some(r) = rest.strip_prefix("<?") {
+            rest = &r[r.find("?>")? + 2..];
+        } else if let Some(r) = rest.strip_prefix("<!--") {
+            rest = &r[r.find("-->")? + 3..];
+        } else if let Some(r) = rest.strip_prefix("<!") {
+            rest = &r[r.find('>')? + 1..];
+        } else if let Some(r) = rest.strip_prefix('<') {
+            let end = r.find(|c: char| c.is_whitespace() || c == '>' || c == '/')?;
+            let name = &r[..end];
+            // префикс пространства имён не важен
+            return Some(name.rsplit(':').next().unwrap_or(name));
+        } else {
+            return None;
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Кодировки входа и выхода.
+//!
+//! Все reader'ы/writer'ы форматов работают с UTF-8. `DecodingReader`
+//! перекодирует вход в UTF-8 до разбора, `EncodingWriter` — выход после
+//! записи. Российские банки присылают Windows-1251 и CP866, поэтому при
+//! автоопределении кроме BOM и валидности UTF-8 различаются эти две.
+
+use crate::error::FinioError;
+use encoding_rs::{CoderResult, Decoder, Encoder, EncoderResult};
+use std::fmt;
+use std::io::{self, BufRead, Chain, Cursor, Read, Write};
+use std::str::FromStr;
+
+/// Сколько байт начала потока анализирует `detect`: файл, у которого
+/// первые 64 КиБ — ASCII, считается UTF-8.
+pub const SNIFF_LEN: usize = 64 * 1024;
+
+const BUF_LEN: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    Utf8,
+    /// только чтение
+    Utf16Le,
+    /// только чтение
+    Utf16Be,
+    Windows1251,
+    Cp866,
+    Koi8R,
+}
+
+impl Encoding {
+    fn codec(self) -> &'static encoding_rs::Encoding {
+        match self {
+            Encoding::Utf8 => encoding_rs::UTF_8,
+            Encoding::Utf16Le => encoding_rs::UTF_16LE,
+            Encoding::Utf16Be => encoding_rs::UTF_16BE,
+            Encoding::Windows1251 => encoding_rs::WINDOWS_1251,
+            Encoding::Cp866 => encoding_rs::IBM866,
+            Encoding::Koi8R => encoding_rs::KOI8_R,
+        }
+    }
+
+    /// Каноническое имя (WHATWG): "utf-8", "windows-1251", "ibm866"…
+    pub fn name(self) -> &'static str {
+        self.codec().name()
+    }
+
+    /// Можно ли писать в этой кодировке (UTF-16 encoding_rs не кодирует).
+    pub fn can_encode(self) -> bool {
+        self.codec().output_encoding() == self.codec()
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Метки WHATWG ("cp1251", "windows-1251", "cp866", "koi8-r", "utf-8"…)
+/// и "dos" — так CP866 называет 1С.
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let label = s.trim();
+        if label.eq_ignore_ascii_case("dos") {
+            return Ok(Encoding::Cp866);
+        }
+        let codec = encoding_rs::Encoding::for_label(label.as_bytes())
+            .ok_or_else(|| format!("unknown encoding {s:?}"))?;
+        [
+            Encoding::Utf8,
+            Encoding::Utf16Le,
+            Encoding::Utf16Be,
+            Encoding::Windows1251,
+            Encoding::Cp866,
+            Encoding::Koi8R,
+        ]
+        .into_iter()
+        .find(|e| e.codec() == codec)
+        .ok_or_else(|| format!("unsupported encoding {}", codec.name()))
+    }
+}
+
+/// Кодировка по началу потока: BOM, затем валидность UTF-8, затем выбор
+/// между Windows-1251 и CP866 по тому, где больше «невозможных» для текста байт.
+pub fn detect(head: &[u8]) -> Encoding {
+    if let Some((codec, _)) = encoding_rs::Encoding::for_bom(head) {
+        return match codec.name() {
+            "UTF-16LE" => Encoding::Utf16Le,
+            "UTF-16BE" => Encoding::Utf16Be,
+            _ => Encoding::Utf8,
+        };
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => return Encoding::Utf8,
+        // обрезанный на границе SNIFF_LEN последний символ — не ошибка
+        Err(e) if e.error_len().is_none() => return Encoding::Utf8,
+        Err(_) => {}
+    }
+
+    // в CP866 0xB0..=0xDF — псевдографика, в тексте её почти не бывает;
+    // в Windows-1251 0x80..=0xBF — редкие знаки, кроме Ёё, №, «», тире
+    let odd_866 = head.iter().filter(|&&b| (0xB0..=0xDF).contains(&b)).count();
+    let odd_1251 = head
+        .iter()
+        .filter(|&&b| (0x80..=0xBF).contains(&b))
+        .filter(|b| ![0xA8, 0xB8, 0xB9, 0xAB, 0xBB, 0x96, 0x97].contains(*b))
+        .count();
+    if odd_866 < odd_1251 {
+        Encoding::Cp866
+    } else {
+        Encoding::Windows1251
+    }
+}
+
+/* ------------------------------- READ ----------------------------------- */
+
+/// Перекодирует вход в UTF-8 на лету; BOM снимается, некорректные
+/// последовательности заменяются на U+FFFD.
+pub struct DecodingReader<R> {
+    inner: R,
+    encoding: Encoding,
+    decoder: Decoder,
+    input: Vec<u8>,
+    in_pos: usize,
+    eof: bool,
+    /// декодер получил `last = true` и больше не вызывается
+    done: bool,
+    out: Vec<u8>,
+    out_pos: usize,
+}
+
+impl<R: Read> DecodingReader<R> {
+    pub fn new(inner: R, encoding: Encoding) -> Self {
+        DecodingReader {
+            inner,
+            encoding,
+            decoder: encoding.codec().new_decoder_with_bom_removal(),
+            input: Vec::with_capacity(BUF_LEN),
+            in_pos: 0,
+            eof: false,
+            done: false,
+            out: Vec::new(),
+            out_pos: 0,
+        }
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+}
+
+impl<R: Read> DecodingReader<Chain<Cursor<Vec<u8>>, R>> {
+    /// Определяет кодировку по первым `SNIFF_LEN` байтам и возвращает их в поток.
+    pub fn detect(mut inner: R) -> io::Result<Self> {
+        let mut head = Vec::with_capacity(SNIFF_LEN);
+        (&mut inner).take(SNIFF_LEN as u64).read_to_end(&mut head)?;
+        let encoding = detect(&head);
+        Ok(Self::new(Cursor::new(head).chain(inner), encoding))
+    }
+}
+
+impl<R: Read> BufRead for DecodingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.out_pos == self.out.len() && !self.done {
+            if self.in_pos == self.input.len() && !self.eof {
+                self.input.resize(BUF_LEN, 0);
+                let n = self.inner.read(&mut self.input)?;
+                self.input.truncate(n);
+                self.in_pos = 0;
+                self.eof = n == 0;
+            }
+            let src = &self.input[self.in_pos..];
+            let cap = self
+                .decoder
+                .max_utf8_buffer_length(src.len())
+                .unwrap_or(BUF_LEN * 3);
+            self.out.resize(cap, 0);
+            let (result, read, written, _) =
+                self.decoder.decode_to_utf8(src, &mut self.out, self.eof);
+            self.in_pos += read;
+            self.out.truncate(written);
+            self.out_pos = 0;
+            self.done = self.eof && result == CoderResult::InputEmpty;
+        }
+        Ok(&self.out[self.out_pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.out_pos = (self.out_pos + amt).min(self.out.len());
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let src = self.fill_buf()?;
+        let n = src.len().min(buf.len());
+        buf[..n].copy_from_slice(&src[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+/* ------------------------------- WRITE ---------------------------------- */
+
+/// Перекодирует UTF-8 от writer'а формата в целевую кодировку.
+/// Символ, которого нет в целевой кодировке, — ошибка `InvalidData`.
+pub struct EncodingWriter<W: Write> {
+    inner: W,
+    encoding: Encoding,
+    encoder: Encoder,
+    /// хвост UTF-8, разрезанный между вызовами `write`
+    pending: Vec<u8>,
+    out: Vec<u8>,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    pub fn new(inner: W, encoding: Encoding) -> crate::error::Result<Self> {
+        if !encoding.can_encode() {
+            return Err(FinioError::Unsupported("writing UTF-16 is not supported"));
+        }
+        Ok(EncodingWriter {
+            inner,
+            encoding,
+            encoder: encoding.codec().new_encoder(),
+            pending: Vec::new(),
+            out: Vec::with_capacity(BUF_LEN),
+        })
+    }
+
+    fn encode(&mut self, mut src: &str) -> io::Result<()> {
+        while !src.is_empty() {
+            let cap = self
+                .encoder
+                .max_buffer_length_from_utf8_without_replacement(src.len())
+                .unwrap_or(BUF_LEN);
+            self.out.resize(cap, 0);
+            let (result, read, written) =
+                self.encoder
+                    .encode_from_utf8_without_replacement(src, &mut self.out, false);
+            if let EncoderResult::Unmappable(c) = result {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("character {c:?} cannot be encoded in {}", self.encoding),
+                ));
+            }
+            self.inner.write_all(&self.out[..written])?;
+            src = &src[read..];
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for EncodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(s) => s.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+        let mut head = std::mem::take(&mut self.pending);
+        self.pending = head.split_off(valid);
+        self.encode(std::str::from_utf8(&head).expect("validated above"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -1,5 +1,10 @@
 //! Единый тип ошибок публичного API.
+//!
+//! Ошибки разбора входа — `FinioError::Diagnostic`: стабильный код, поле,
+//! исходное значение и место во входе. Функции разбора отдельных полей
+//! заполняют код/поле/значение, reader формата добавляет место (`located`).
 
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -18,6 +23,195 @@ pub enum FinioError {
 
     #[error("Unsupported operation: {0}")]
     Unsupported(&'static str),
+
+    #[error("{0}")]
+    Diagnostic(Box<Diagnostic>),
+}
+
+impl FinioError {
+    /// Диагностика, если это ошибка разбора.
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            FinioError::Diagnostic(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// Диагностика по значению; остальные ошибки возвращаются как есть.
+    pub fn into_diagnostic(self) -> std::result::Result<Diagnostic, Self> {
+        match self {
+            FinioError::Diagnostic(d) => Ok(*d),
+            other => Err(other),
+        }
+    }
+
+    /// Привязывает ошибку разбора к месту во входе (`snippet` — исходная
+    /// строка, может быть пустой). Уже привязанная ошибка не меняется;
+    /// `Parse`/`Xml` становятся диагностикой.
+    pub fn located(self, location: Location, snippet: &str) -> Self {
+        let mut d = match self {
+            FinioError::Diagnostic(d) => d,
+            FinioError::Parse(msg) => Box::new(Diagnostic::new(ErrorCode::Syntax, msg)),
+            FinioError::Xml(msg) => Box::new(Diagnostic::new(ErrorCode::Xml, msg)),
+            other => return other,
+        };
+        if d.location.is_none() {
+            // колонку строчных форматов находим по исходному значению поля
+            let location = match location {
+                Location::Line { line, column: None } => Location::Line {
+                    line,
+                    column: d.value.as_deref().and_then(|v| column_of(snippet, v)),
+                },
+                other => other,
+            };
+            d.location = Some(location);
+            d.snippet = (!snippet.is_empty()).then(|| snippet.to_string());
+        }
+        FinioError::Diagnostic(d)
+    }
+}
+
+/// Колонка (с 1, в символах) первого вхождения `value` в строку.
+fn column_of(line: &str, value: &str) -> Option<usize> {
+    if value.is_empty() {
+        return None;
+    }
+    line.find(value).map(|i| line[..i].chars().count() + 1)
+}
+
+/// Стабильный код ошибки разбора; не меняется между версиями.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// строка или элемент не соответствует синтаксису формата
+    Syntax,
+    /// обязательное поле отсутствует
+    MissingField,
+    InvalidDate,
+    InvalidAmount,
+    /// признак дебет/кредит
+    InvalidDebitCredit,
+    /// прочее недопустимое значение поля
+    InvalidValue,
+    /// нарушена структура: проводка вне выписки, незакрытая секция и т.п.
+    Structure,
+    /// некорректный XML
+    Xml,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::Syntax => "E001",
+            ErrorCode::MissingField => "E002",
+            ErrorCode::InvalidDate => "E003",
+            ErrorCode::InvalidAmount => "E004",
+            ErrorCode::InvalidDebitCredit => "E005",
+            ErrorCode::InvalidValue => "E006",
+            ErrorCode::Structure => "E007",
+            ErrorCode::Xml => "E008",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Место во входе.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// строчные форматы (MT940/MT942, 1С); строка и колонка — с 1,
+    /// колонка — в символах
+    Line { line: u64, column: Option<usize> },
+    /// CSV: номер записи (заголовок — 0) и строка файла (с 1)
+    Record { record: u64, line: u64 },
+    /// XML: путь элемента (`/Document/…/Ntry/Amt`) и смещение в байтах,
+    /// если разбор потоковый
+    Xml { path: String, offset: Option<u64> },
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::Line { line, column: Some(c) } => write!(f, "line {line}, column {c}"),
+            Location::Line { line, column: None } => write!(f, "line {line}"),
+            Location::Record { record, line } => write!(f, "record {record} (line {line})"),
+            Location::Xml { path, offset: Some(o) } => write!(f, "{path} (byte {o})"),
+            Location::Xml { path, offset: None } => f.write_str(path),
+        }
+    }
+}
+
+/// Ошибка разбора с контекстом.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub code: ErrorCode,
+    pub message: String,
+    /// поле формата: ":61: amount", "booking_date", "BookgDt"
+    pub field: Option<String>,
+    /// исходное значение поля
+    pub value: Option<String>,
+    pub location: Option<Location>,
+    /// строка (или текст элемента) входа, где найдена ошибка
+    pub snippet: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Diagnostic {
+            code,
+            message: message.into(),
+            field: None,
+            value: None,
+            location: None,
+            snippet: None,
+        }
+    }
+
+    pub fn field(mut self, field: impl Into<String>) -> Self {
+        self.field = Some(field.into());
+        self
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    pub fn at(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)?;
+        if let Some(field) = &self.field {
+            write!(f, " in {field}")?;
+        }
+        if let Some(v) = &self.value {
+            write!(f, ": {v:?}")?;
+        }
+        if let Some(loc) = &self.location {
+            write!(f, " at {loc}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<quick_xml::Error> for FinioError {
+    fn from(e: quick_xml::Error) -> Self {
+        FinioError::Xml(e.to_string())
+    }
+}
+
+impl From<Diagnostic> for FinioError {
+    fn from(d: Diagnostic) -> Self {
+        FinioError::Diagnostic(Box::new(d))
+    }
 }
 
 pub type Result<T> = std::result::Result<T, FinioError>;
@@ -0,0 +1,45 @@
+//! CAMT.052 — внутридневной отчёт по счёту (BkToCstmrAcctRpt/Rpt).
+//!
+//! Разбор и запись проводок `Ntry` общие с CAMT.053 (см. `camt053`).
+
+use crate::{
+    error::{Diagnostic, Result},
+    formats::camt053::{CamtMessage, CamtReader, CamtWriter},
+    model::Statement,
+    stream,
+    traits::{ParseMode, ReadFormat, StreamRead, StreamWrite, WriteFormat},
+};
+use std::io::{BufRead, Write};
+
+pub struct Camt052;
+
+impl WriteFormat for Camt052 {
+    fn write_statements<W: Write>(w: W, sts: &[Statement]) -> Result<()> {
+        stream::write_all(&mut Self::writer(w), sts)
+    }
+}
+
+impl StreamWrite for Camt052 {
+    type Writer<W: Write> = CamtWriter<W>;
+
+    fn writer<W: Write>(w: W) -> CamtWriter<W> {
+        CamtWriter::new(w, CamtMessage::AccountReport)
+    }
+}
+
+impl ReadFormat for Camt052 {
+    fn read_statements_with<R: BufRead>(
+        r: R,
+        mode: ParseMode,
+    ) -> Result<(Vec<Statement>, Vec<Diagnostic>)> {
+        stream::collect_with(Self::reader_with(r, mode), mode)
+    }
+}
+
+impl StreamRead for Camt052 {
+    type Reader<R: BufRead> = CamtReader<R>;
+
+    fn reader_with<R: BufRead>(r: R, mode: ParseMode) -> CamtReader<R> {
+        CamtReader::new(r, CamtMessage::AccountReport).mode(mode)
+    }
+}
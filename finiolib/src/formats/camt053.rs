@@ -1,84 +1,380 @@
 use crate::{
-    error::{FinioError, Result},
-    model::{Balance, DebitCredit, Entry, Statement},
-    traits::{ReadFormat, WriteFormat},
+    account::{AccountId, Bic},
+    currency::{parse_currency, Currency},
+    error::{Diagnostic, ErrorCode, FinioError, Location, Result},
+    model::{
+        Balance, BalanceKind, BankTxCode, Counterparty, CreditorReference, DebitCredit, Entry,
+        EntryStatus, IsoTxCode, Period, ProprietaryTxCode, Remittance, Statement, TxDates, TxDetails, TypedBalance,
+    },
+    stream::{self, StatementEvent, StatementTrailer},
+    traits::{ParseMode, ReadFormat, StatementWriter, StreamRead, StreamWrite, WriteFormat},
+    txcode::swift_to_iso,
 };
-use chrono::NaiveDate;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
 use quick_xml::{
     events::{BytesDecl, BytesStart, BytesText, Event},
+    name::QName,
     Reader, Writer,
 };
 use rust_decimal::Decimal;
+use std::collections::VecDeque;
 use std::io::{BufRead, Write};
 
 pub struct Camt053;
 
+/// Вид сообщения ISO 20022 семейства camt.05x. Структура `Ntry` у них общая,
+/// различаются корневой элемент, элемент выписки и пространство имён.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CamtMessage {
+    /// camt.052 — BkToCstmrAcctRpt/Rpt
+    AccountReport,
+    /// camt.053 — BkToCstmrStmt/Stmt
+    Statement,
+    /// camt.054 — BkToCstmrDbtCdtNtfctn/Ntfctn
+    Notification,
+}
+
+impl CamtMessage {
+    pub fn root(self) -> &'static str {
+        match self {
+            CamtMessage::AccountReport => "BkToCstmrAcctRpt",
+            CamtMessage::Statement => "BkToCstmrStmt",
+            CamtMessage::Notification => "BkToCstmrDbtCdtNtfctn",
+        }
+    }
+
+    /// Элемент одной выписки/отчёта/уведомления.
+    pub fn block(self) -> &'static str {
+        match self {
+            CamtMessage::AccountReport => "Rpt",
+            CamtMessage::Statement => "Stmt",
+            CamtMessage::Notification => "Ntfctn",
+        }
+    }
+
+    /// Идентификатор сообщения без версии: "camt.053".
+    pub fn code(self) -> &'static str {
+        match self {
+            CamtMessage::AccountReport => "camt.052",
+            CamtMessage::Statement => "camt.053",
+            CamtMessage::Notification => "camt.054",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "camt.052" => Some(CamtMessage::AccountReport),
+            "camt.053" => Some(CamtMessage::Statement),
+            "camt.054" => Some(CamtMessage::Notification),
+            _ => None,
+        }
+    }
+
+    /// Нумерация страниц (с 001.03): `StmtPgntn`, `RptPgntn`, `NtfctnPgntn`.
+    fn pagination(self) -> &'static str {
+        match self {
+            CamtMessage::AccountReport => "RptPgntn",
+            CamtMessage::Statement => "StmtPgntn",
+            CamtMessage::Notification => "NtfctnPgntn",
+        }
+    }
+
+    /// В уведомлениях camt.054 элемента `<Bal>` нет.
+    fn has_balances(self) -> bool {
+        self != CamtMessage::Notification
+    }
+}
+
+const NS_PREFIX: &str = "urn:iso:std:iso:20022:tech:xsd:";
+
+/// Эмитент `BkTxCd/Prtry` для SWIFT-типа операции без ISO-соответствия.
+const SWIFT_ISSUER: &str = "SWIFT";
+
+/// `ClrSysId/Cd` Банка России: участник — банк с БИК.
+const RU_CLEARING: &str = "RUCBC";
+
+/// Версия схемы — `NN` в `camt.05x.001.NN`. Нумерация общая для 052/053/054.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CamtVersion(pub u8);
+
+impl CamtVersion {
+    pub const V02: CamtVersion = CamtVersion(2);
+    pub const V03: CamtVersion = CamtVersion(3);
+    pub const V04: CamtVersion = CamtVersion(4);
+    pub const V05: CamtVersion = CamtVersion(5);
+    pub const V06: CamtVersion = CamtVersion(6);
+    pub const V07: CamtVersion = CamtVersion(7);
+    pub const V08: CamtVersion = CamtVersion(8);
+
+    /// Пространство имён документа этой версии.
+    pub fn namespace(self, msg: CamtMessage) -> String {
+        format!("{NS_PREFIX}{}.{self}", msg.code())
+    }
+
+    /// Разбирает `urn:iso:std:iso:20022:tech:xsd:camt.053.001.08`.
+    pub fn from_namespace(ns: &str) -> Option<(CamtMessage, CamtVersion)> {
+        let rest = ns.trim().strip_prefix(NS_PREFIX)?;
+        let (code, ver) = rest.split_at_checked(8)?;
+        let msg = CamtMessage::from_code(code)?;
+        let v = ver.strip_prefix('.')?.parse().ok()?;
+        Some((msg, v))
+    }
+
+    /// `<Ntry><Sts>`: до 001.07 — код, с 001.08 — выбор `<Sts><Cd>`.
+    fn status_is_choice(self) -> bool {
+        self >= CamtVersion::V08
+    }
+}
+
+impl Default for CamtVersion {
+    fn default() -> Self {
+        CamtVersion::V02
+    }
+}
+
+impl std::fmt::Display for CamtVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "001.{:02}", self.0)
+    }
+}
+
+/// Принимает "001.08", "08" и "8".
+impl std::str::FromStr for CamtVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let n = s.trim().strip_prefix("001.").unwrap_or(s.trim());
+        match n.parse::<u8>() {
+            Ok(v) if v >= 1 => Ok(CamtVersion(v)),
+            _ => Err(format!("invalid CAMT version {s:?}, expected e.g. 001.08")),
+        }
+    }
+}
+
 /* ------------------------------- WRITE ---------------------------------- */
 
 impl WriteFormat for Camt053 {
-    fn write<W: Write>(mut w: W, st: &Statement) -> Result<()> {
-        let mut wr = Writer::new_with_indent(&mut w, b' ', 2);
+    fn write_statements<W: Write>(w: W, sts: &[Statement]) -> Result<()> {
+        stream::write_all(&mut Self::writer(w), sts)
+    }
+}
+
+impl StreamWrite for Camt053 {
+    type Writer<W: Write> = CamtWriter<W>;
+
+    fn writer<W: Write>(w: W) -> CamtWriter<W> {
+        CamtWriter::new(w, CamtMessage::Statement)
+    }
+}
+
+/// Потоковый writer: `<Bal>` идут в `<Stmt>` раньше `<Ntry>`, поэтому если
+/// CLBD в заголовке неизвестен, проводки выписки придерживаются до trailer.
+pub struct CamtWriter<W: Write> {
+    wr: Writer<W>,
+    msg: CamtMessage,
+    version: CamtVersion,
+    /// пролог Document/BkToCstmrStmt уже записан
+    started: bool,
+    deferred: Option<Vec<Entry>>,
+    /// прочие остатки заголовка — пишутся вместе с CLBD
+    balances: Vec<TypedBalance>,
+    /// `GrpHdr/CreDtTm`; им же заполняется обязательный `CreDtTm` выписки без `created`
+    created: NaiveDateTime,
+}
+
+impl<W: Write> CamtWriter<W> {
+    pub fn new(w: W, msg: CamtMessage) -> Self {
+        CamtWriter {
+            wr: Writer::new_with_indent(w, b' ', 2),
+            msg,
+            version: CamtVersion::default(),
+            started: false,
+            deferred: None,
+            balances: Vec::new(),
+            created: Local::now().naive_local(),
+        }
+    }
+
+    /// Версия схемы на выходе (по умолчанию 001.02).
+    pub fn version(mut self, version: CamtVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Пролог и `<GrpHdr>`: `MsgId` и `CreDtTm` берутся из первой выписки.
+    fn start(&mut self, first: Option<&Statement>) -> Result<()> {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
+        if let Some(created) = first.and_then(|st| st.created) {
+            self.created = created;
+        }
+        let wr = &mut self.wr;
 
         wr.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
             .map_err(xml)?;
 
         let mut doc = BytesStart::new("Document");
-        doc.push_attribute(("xmlns", "urn:iso:std:iso:20022:tech:xsd:camt.053.001.02"));
+        doc.push_attribute(("xmlns", self.version.namespace(self.msg).as_str()));
         wr.write_event(Event::Start(doc)).map_err(xml)?;
 
-        wr.write_event(Event::Start(BytesStart::new("BkToCstmrStmt")))
-            .map_err(xml)?;
-        wr.write_event(Event::Start(BytesStart::new("Stmt"))).map_err(xml)?;
-
-        // <Id>
-        wr.write_event(Event::Start(BytesStart::new("Id"))).map_err(xml)?;
-        let id = st
-            .statement_id
-            .clone()
-            .unwrap_or_else(|| "NOTPROVIDED".into());
-        wr.write_event(Event::Text(BytesText::new(&id))).map_err(xml)?;
-        wr.write_event(Event::End(BytesStart::new("Id").to_end()))
+        wr.write_event(Event::Start(BytesStart::new(self.msg.root())))
             .map_err(xml)?;
 
-        // <Acct><Id><IBAN>
-        wr.write_event(Event::Start(BytesStart::new("Acct"))).map_err(xml)?;
-        wr.write_event(Event::Start(BytesStart::new("Id"))).map_err(xml)?;
-        wr.write_event(Event::Start(BytesStart::new("IBAN"))).map_err(xml)?;
-        wr.write_event(Event::Text(BytesText::new(&st.account_id)))
-            .map_err(xml)?;
-        wr.write_event(Event::End(BytesStart::new("IBAN").to_end()))
-            .map_err(xml)?;
-        wr.write_event(Event::End(BytesStart::new("Id").to_end()))
-            .map_err(xml)?;
-        wr.write_event(Event::End(BytesStart::new("Acct").to_end()))
-            .map_err(xml)?;
+        let msg_id = first.and_then(|st| st.statement_id.as_deref()).unwrap_or("NOTPROVIDED");
+        wr.write_event(Event::Start(BytesStart::new("GrpHdr")))?;
+        write_text(wr, "MsgId", msg_id)?;
+        write_text(wr, "CreDtTm", &format_datetime(self.created))?;
+        wr.write_event(Event::End(BytesStart::new("GrpHdr").to_end()))?;
+        Ok(())
+    }
+}
 
-        if let Some(b) = &st.opening_balance {
-            write_bal(&mut wr, "OPBD", b).map_err(xml)?;
-        }
-        if let Some(b) = &st.closing_balance {
-            write_bal(&mut wr, "CLBD", b).map_err(xml)?;
-        }
+impl<W: Write> StatementWriter for CamtWriter<W> {
+    fn begin(&mut self, st: &Statement) -> Result<()> {
+        self.start(Some(st))?;
+        write_stmt_header(&mut self.wr, self.msg, self.version, st, self.created)?;
+        self.deferred = match &st.closing_balance {
+            _ if !self.msg.has_balances() => None,
+            Some(b) => {
+                write_bal(&mut self.wr, "CLBD", b)?;
+                for b in &st.balances {
+                    write_typed_bal(&mut self.wr, b)?;
+                }
+                None
+            }
+            None => {
+                self.balances = st.balances.clone();
+                Some(Vec::new())
+            }
+        };
+        Ok(())
+    }
 
-        for e in &st.entries {
-            write_entry(&mut wr, e).map_err(xml)?;
+    fn entry(&mut self, e: &Entry) -> Result<()> {
+        match &mut self.deferred {
+            Some(es) => es.push(e.clone()),
+            None => write_entry(&mut self.wr, self.version, e)?,
         }
+        Ok(())
+    }
 
-        wr.write_event(Event::End(BytesStart::new("Stmt").to_end()))
+    fn end(&mut self, t: &StatementTrailer) -> Result<()> {
+        if let Some(es) = self.deferred.take() {
+            if let Some(b) = t.closing_balance.as_ref().filter(|_| self.msg.has_balances()) {
+                write_bal(&mut self.wr, "CLBD", b)?;
+            }
+            if self.msg.has_balances() {
+                for b in std::mem::take(&mut self.balances).iter().chain(&t.balances) {
+                    write_typed_bal(&mut self.wr, b)?;
+                }
+            }
+            for e in &es {
+                write_entry(&mut self.wr, self.version, e)?;
+            }
+        }
+        self.wr
+            .write_event(Event::End(BytesStart::new(self.msg.block()).to_end()))
             .map_err(xml)?;
-        wr.write_event(Event::End(BytesStart::new("BkToCstmrStmt").to_end()))
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.start(None)?;
+        self.wr
+            .write_event(Event::End(BytesStart::new(self.msg.root()).to_end()))
             .map_err(xml)?;
-        wr.write_event(Event::End(BytesStart::new("Document").to_end()))
+        self.wr
+            .write_event(Event::End(BytesStart::new("Document").to_end()))
             .map_err(xml)?;
+        self.wr.get_mut().flush()?;
         Ok(())
     }
 }
 
+/// `<Stmt>` (`<Rpt>`, `<Ntfctn>`) до балансов включительно OPBD;
+/// `created` — `CreDtTm`, если у выписки его нет.
+fn write_stmt_header<W: Write>(
+    wr: &mut Writer<W>,
+    msg: CamtMessage,
+    version: CamtVersion,
+    st: &Statement,
+    created: NaiveDateTime,
+) -> Result<()> {
+    wr.write_event(Event::Start(BytesStart::new(msg.block())))?;
+
+    // <Id>
+    wr.write_event(Event::Start(BytesStart::new("Id")))?;
+    let id = st
+        .statement_id
+        .clone()
+        .unwrap_or_else(|| "NOTPROVIDED".into());
+    wr.write_event(Event::Text(BytesText::new(&id)))?;
+    wr.write_event(Event::End(BytesStart::new("Id").to_end()))?;
+
+    if let Some(page) = st.page.filter(|_| version >= CamtVersion::V03) {
+        wr.write_event(Event::Start(BytesStart::new(msg.pagination())))?;
+        write_text(wr, "PgNb", &page.to_string())?;
+        write_text(wr, "LastPgInd", if st.last_page.unwrap_or(true) { "true" } else { "false" })?;
+        wr.write_event(Event::End(BytesStart::new(msg.pagination()).to_end()))?;
+    }
+    if let Some(n) = st.electronic_seq {
+        write_text(wr, "ElctrncSeqNb", &n.to_string())?;
+    }
+    if let Some(n) = st.legal_seq {
+        write_text(wr, "LglSeqNb", &n.to_string())?;
+    }
+    let cre_dt_tm = match st.created_at() {
+        Some(dt) => dt.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+        None => format_datetime(st.created.unwrap_or(created)),
+    };
+    write_text(wr, "CreDtTm", &cre_dt_tm)?;
+    if let Some(p) = &st.period {
+        wr.write_event(Event::Start(BytesStart::new("FrToDt")))?;
+        write_text(wr, "FrDtTm", &format_datetime(p.from))?;
+        write_text(wr, "ToDtTm", &format_datetime(p.to))?;
+        wr.write_event(Event::End(BytesStart::new("FrToDt").to_end()))?;
+    }
+
+    // <Acct>; БИК российского счёта — в <Svcr> рядом с BIC обслуживающего банка
+    wr.write_event(Event::Start(BytesStart::new("Acct")))?;
+    write_acct_id(wr, &st.account_id)?;
+    if let Some(ccy) = st.account_currency {
+        write_text(wr, "Ccy", ccy.code())?;
+    }
+    if let Some(owner) = &st.account_owner {
+        wr.write_event(Event::Start(BytesStart::new("Ownr")))?;
+        write_text(wr, "Nm", owner)?;
+        wr.write_event(Event::End(BytesStart::new("Ownr").to_end()))?;
+    }
+    let (bic, servicer_bik) = match &st.servicer_bic {
+        Some(b) if b.is_bik() => (None, Some(&**b)),
+        b => (b.as_ref(), None),
+    };
+    let bik = match &st.account_id {
+        AccountId::Ru { bik: Some(bik), .. } => Some(bik.as_str()),
+        _ => servicer_bik,
+    };
+    if bic.is_some() || bik.is_some() {
+        wr.write_event(Event::Start(BytesStart::new("Svcr")))?;
+        write_fin_instn_ids(wr, version, bic, bik)?;
+        wr.write_event(Event::End(BytesStart::new("Svcr").to_end()))?;
+    }
+    wr.write_event(Event::End(BytesStart::new("Acct").to_end()))?;
+
+    if let Some(b) = st.opening_balance.as_ref().filter(|_| msg.has_balances()) {
+        write_bal(wr, "OPBD", b)?;
+    }
+    Ok(())
+}
+
+/// `<Bal>`: в CAMT сумма без знака, направление — в `CdtDbtInd`.
 fn write_bal<W: Write>(
     wr: &mut Writer<W>,
     tp: &str,
     b: &Balance,
-) -> std::result::Result<(), quick_xml::Error> {
+) -> Result<()> {
     wr.write_event(Event::Start(BytesStart::new("Bal")))?;
     wr.write_event(Event::Start(BytesStart::new("Tp")))?;
     wr.write_event(Event::Start(BytesStart::new("CdOrPrtry")))?;
@@ -88,12 +384,9 @@ fn write_bal<W: Write>(
     wr.write_event(Event::End(BytesStart::new("CdOrPrtry").to_end()))?;
     wr.write_event(Event::End(BytesStart::new("Tp").to_end()))?;
 
-    let amt_str = b.amount.to_string();
-    wr.write_event(Event::Start(
-        BytesStart::new("Amt").with_attributes([("Ccy", b.currency.as_str())]),
-    ))?;
-    wr.write_event(Event::Text(BytesText::new(&amt_str)))?;
-    wr.write_event(Event::End(BytesStart::new("Amt").to_end()))?;
+    write_amt(wr, b.amount.abs(), b.currency)?;
+    let ind = if b.amount.is_sign_negative() { "DBIT" } else { "CRDT" };
+    write_text(wr, "CdtDbtInd", ind)?;
 
     let d = b.date.format("%Y-%m-%d").to_string();
     wr.write_event(Event::Start(BytesStart::new("Dt")))?;
@@ -106,10 +399,27 @@ fn write_bal<W: Write>(
     Ok(())
 }
 
+/// `<Bal>` из списка `balances`; входящий остаток продолжения — `PRCD`
+/// (исходящий предыдущей страницы), промежуточный исходящий — `ITBD`.
+fn write_typed_bal<W: Write>(
+    wr: &mut Writer<W>,
+    b: &TypedBalance,
+) -> Result<()> {
+    let code = match &b.kind {
+        BalanceKind::InterimOpening => "PRCD",
+        BalanceKind::InterimClosing => "ITBD",
+        BalanceKind::ClosingAvailable => "CLAV",
+        BalanceKind::ForwardAvailable => "FWAV",
+        BalanceKind::Other(code) => code,
+    };
+    write_bal(wr, code, &b.balance())
+}
+
 fn write_entry<W: Write>(
     wr: &mut Writer<W>,
+    version: CamtVersion,
     e: &Entry,
-) -> std::result::Result<(), quick_xml::Error> {
+) -> Result<()> {
     wr.write_event(Event::Start(BytesStart::new("Ntry")))?;
 
     // <NtryRef>REF...</NtryRef> — если есть reference
@@ -122,12 +432,7 @@ fn write_entry<W: Write>(
     }
 
     // <Amt Ccy="...">...</Amt>
-    let amt = e.amount.to_string();
-    wr.write_event(Event::Start(
-        BytesStart::new("Amt").with_attributes([("Ccy", e.currency.as_str())]),
-    ))?;
-    wr.write_event(Event::Text(BytesText::new(&amt)))?;
-    wr.write_event(Event::End(BytesStart::new("Amt").to_end()))?;
+    write_amt(wr, e.amount, e.currency)?;
 
     // <CdtDbtInd>CRDT|DBIT</CdtDbtInd>
     let ind = match e.dc {
@@ -138,6 +443,28 @@ fn write_entry<W: Write>(
     wr.write_event(Event::Text(BytesText::new(ind)))?;
     wr.write_event(Event::End(BytesStart::new("CdtDbtInd").to_end()))?;
 
+    if e.reversal {
+        write_text(wr, "RvslInd", "true")?;
+    }
+
+    // <Sts> обязателен во всех версиях; форма зависит от версии
+    wr.write_event(Event::Start(BytesStart::new("Sts")))?;
+    if version.status_is_choice() {
+        write_text(wr, "Cd", e.status.code())?;
+    } else {
+        wr.write_event(Event::Text(BytesText::new(e.status.code())))?;
+    }
+    wr.write_event(Event::End(BytesStart::new("Sts").to_end()))?;
+
+    // по XSD: Sts, BookgDt, ValDt
+    // <BookgDt><Dt>YYYY-MM-DD</Dt></BookgDt>
+    let bd = e.booking_date.format("%Y-%m-%d").to_string();
+    wr.write_event(Event::Start(BytesStart::new("BookgDt")))?;
+    wr.write_event(Event::Start(BytesStart::new("Dt")))?;
+    wr.write_event(Event::Text(BytesText::new(&bd)))?;
+    wr.write_event(Event::End(BytesStart::new("Dt").to_end()))?;
+    wr.write_event(Event::End(BytesStart::new("BookgDt").to_end()))?;
+
     // <ValDt><Dt>YYYY-MM-DD</Dt></ValDt>
     if let Some(vd) = e.value_date {
         let vd = vd.format("%Y-%m-%d").to_string();
@@ -148,13 +475,68 @@ fn write_entry<W: Write>(
         wr.write_event(Event::End(BytesStart::new("ValDt").to_end()))?;
     }
 
-    // <BookgDt><Dt>YYYY-MM-DD</Dt></BookgDt>
-    let bd = e.booking_date.format("%Y-%m-%d").to_string();
-    wr.write_event(Event::Start(BytesStart::new("BookgDt")))?;
-    wr.write_event(Event::Start(BytesStart::new("Dt")))?;
-    wr.write_event(Event::Text(BytesText::new(&bd)))?;
-    wr.write_event(Event::End(BytesStart::new("Dt").to_end()))?;
-    wr.write_event(Event::End(BytesStart::new("BookgDt").to_end()))?;
+    if let Some(r) = e.bank_reference.as_deref().filter(|r| !r.is_empty()) {
+        write_text(wr, "AcctSvcrRef", r)?;
+    }
+
+    // <BkTxCd>: ISO-код (свой или по таблице SWIFT) и проприетарный код;
+    // без своего проприетарного в Prtry идёт SWIFT-тип с эмитентом SWIFT
+    if let Some(code) = &e.bank_tx_code {
+        let iso = code.iso_code(e.dc);
+        let prtry = code.proprietary.clone().or_else(|| {
+            code.swift.as_ref().map(|swift| ProprietaryTxCode {
+                code: swift.clone(),
+                issuer: Some(SWIFT_ISSUER.into()),
+            })
+        });
+        if iso.is_some() || prtry.is_some() {
+            wr.write_event(Event::Start(BytesStart::new("BkTxCd")))?;
+            if let Some(iso) = iso {
+                wr.write_event(Event::Start(BytesStart::new("Domn")))?;
+                write_text(wr, "Cd", &iso.domain)?;
+                wr.write_event(Event::Start(BytesStart::new("Fmly")))?;
+                write_text(wr, "Cd", &iso.family)?;
+                write_text(wr, "SubFmlyCd", &iso.sub_family)?;
+                wr.write_event(Event::End(BytesStart::new("Fmly").to_end()))?;
+                wr.write_event(Event::End(BytesStart::new("Domn").to_end()))?;
+            }
+            if let Some(p) = prtry {
+                wr.write_event(Event::Start(BytesStart::new("Prtry")))?;
+                write_text(wr, "Cd", &p.code)?;
+                if let Some(issuer) = &p.issuer {
+                    write_text(wr, "Issr", issuer)?;
+                }
+                wr.write_event(Event::End(BytesStart::new("Prtry").to_end()))?;
+            }
+            wr.write_event(Event::End(BytesStart::new("BkTxCd").to_end()))?;
+        }
+    }
+
+    // <NtryDtls>: по <TxDtls> на операцию; без операций — одна с контрагентом
+    // и назначением проводки
+    let cp = e.counterparty.as_ref().filter(|c| !c.is_empty());
+    let rmt = &e.remittance;
+    if !e.details.is_empty() || cp.is_some() || !rmt.is_empty() {
+        wr.write_event(Event::Start(BytesStart::new("NtryDtls")))?;
+        if e.details.len() > 1 {
+            wr.write_event(Event::Start(BytesStart::new("Btch")))?;
+            write_text(wr, "NbOfTxs", &e.details.len().to_string())?;
+            wr.write_event(Event::End(BytesStart::new("Btch").to_end()))?;
+        }
+        match e.details.as_slice() {
+            [] => write_tx_details(wr, version, e.dc, &TxDetails::default(), cp, rmt)?,
+            [d] => {
+                let own = (!d.remittance.is_empty()).then_some(&d.remittance);
+                write_tx_details(wr, version, e.dc, d, d.counterparty.as_ref().or(cp), own.unwrap_or(rmt))?
+            }
+            many => {
+                for d in many {
+                    write_tx_details(wr, version, e.dc, d, d.counterparty.as_ref(), &d.remittance)?;
+                }
+            }
+        }
+        wr.write_event(Event::End(BytesStart::new("NtryDtls").to_end()))?;
+    }
 
     // <AddtlNtryInf>...</AddtlNtryInf>
     if !e.description.is_empty() {
@@ -167,6 +549,251 @@ fn write_entry<W: Write>(
     Ok(())
 }
 
+/// `<TxDtls>`: референсы и сумма операции, контрагент `cp`, назначение `rmt`.
+fn write_tx_details<W: Write>(
+    wr: &mut Writer<W>,
+    version: CamtVersion,
+    dc: DebitCredit,
+    d: &TxDetails,
+    cp: Option<&Counterparty>,
+    rmt: &Remittance,
+) -> Result<()> {
+    wr.write_event(Event::Start(BytesStart::new("TxDtls")))?;
+    let r = &d.refs;
+    let refs = [
+        ("MsgId", &r.msg_id),
+        ("InstrId", &r.instr_id),
+        ("EndToEndId", &r.end_to_end_id),
+        ("TxId", &r.tx_id),
+        ("MndtId", &r.mandate_id),
+    ];
+    if refs.iter().any(|(_, v)| v.is_some()) {
+        wr.write_event(Event::Start(BytesStart::new("Refs")))?;
+        for (name, v) in refs {
+            if let Some(v) = v {
+                write_text(wr, name, v)?;
+            }
+        }
+        wr.write_event(Event::End(BytesStart::new("Refs").to_end()))?;
+    }
+    if let (Some(amount), Some(ccy)) = (d.amount, d.currency) {
+        wr.write_event(Event::Start(BytesStart::new("AmtDtls")))?;
+        wr.write_event(Event::Start(BytesStart::new("TxAmt")))?;
+        write_amt(wr, amount, ccy)?;
+        wr.write_event(Event::End(BytesStart::new("TxAmt").to_end()))?;
+        wr.write_event(Event::End(BytesStart::new("AmtDtls").to_end()))?;
+    }
+    if let Some(cp) = cp.filter(|c| !c.is_empty()) {
+        write_counterparty(wr, version, dc, cp)?;
+    }
+    if !rmt.is_empty() {
+        wr.write_event(Event::Start(BytesStart::new("RmtInf")))?;
+        for line in &rmt.unstructured {
+            write_text(wr, "Ustrd", line)?;
+        }
+        if let Some(r) = &rmt.creditor_reference {
+            wr.write_event(Event::Start(BytesStart::new("Strd")))?;
+            wr.write_event(Event::Start(BytesStart::new("CdtrRefInf")))?;
+            if r.kind.is_some() || r.issuer.is_some() {
+                wr.write_event(Event::Start(BytesStart::new("Tp")))?;
+                if let Some(kind) = &r.kind {
+                    // коды ISO — четыре заглавные буквы, прочее — проприетарный тип
+                    let iso = kind.len() == 4 && kind.bytes().all(|b| b.is_ascii_uppercase());
+                    wr.write_event(Event::Start(BytesStart::new("CdOrPrtry")))?;
+                    write_text(wr, if iso { "Cd" } else { "Prtry" }, kind)?;
+                    wr.write_event(Event::End(BytesStart::new("CdOrPrtry").to_end()))?;
+                }
+                if let Some(issuer) = &r.issuer {
+                    write_text(wr, "Issr", issuer)?;
+                }
+                wr.write_event(Event::End(BytesStart::new("Tp").to_end()))?;
+            }
+            write_text(wr, "Ref", &r.reference)?;
+            wr.write_event(Event::End(BytesStart::new("CdtrRefInf").to_end()))?;
+            wr.write_event(Event::End(BytesStart::new("Strd").to_end()))?;
+        }
+        wr.write_event(Event::End(BytesStart::new("RmtInf").to_end()))?;
+    }
+    write_tx_dates(wr, &d.dates)?;
+    wr.write_event(Event::End(BytesStart::new("TxDtls").to_end()))?;
+    Ok(())
+}
+
+/// `RltdDts` в порядке схемы; состав элементов одинаков в 001.02–001.08.
+fn write_tx_dates<W: Write>(wr: &mut Writer<W>, t: &TxDates) -> Result<()> {
+    if t.is_empty() {
+        return Ok(());
+    }
+    let date = |d: Option<NaiveDate>| d.map(|d| d.format("%Y-%m-%d").to_string());
+    let dates = [
+        ("AccptncDtTm", t.acceptance.map(format_datetime)),
+        ("TradActvtyCtrctlSttlmDt", date(t.trade_settlement)),
+        ("TradDt", date(t.trade)),
+        ("IntrBkSttlmDt", date(t.interbank_settlement)),
+        ("StartDt", date(t.start)),
+        ("EndDt", date(t.end)),
+        ("TxDtTm", t.transaction.map(format_datetime)),
+    ];
+    wr.write_event(Event::Start(BytesStart::new("RltdDts")))?;
+    for (name, v) in dates {
+        if let Some(v) = v {
+            write_text(wr, name, &v)?;
+        }
+    }
+    wr.write_event(Event::End(BytesStart::new("RltdDts").to_end()))?;
+    Ok(())
+}
+
+/// Контрагент кредита — `Dbtr`, дебета — `Cdtr`. С 001.08 имя и адрес
+/// вложены в `<Pty>`, с 001.04 BIC пишется в `<BICFI>`.
+fn write_counterparty<W: Write>(
+    wr: &mut Writer<W>,
+    version: CamtVersion,
+    dc: DebitCredit,
+    cp: &Counterparty,
+) -> std::result::Result<(), quick_xml::Error> {
+    let role = match dc {
+        DebitCredit::Credit => "Dbtr",
+        DebitCredit::Debit => "Cdtr",
+    };
+
+    if cp.name.is_some() || cp.address.is_some() || cp.account.is_some() {
+        wr.write_event(Event::Start(BytesStart::new("RltdPties")))?;
+        if cp.name.is_some() || cp.address.is_some() {
+            wr.write_event(Event::Start(BytesStart::new(role)))?;
+            let pty = version >= CamtVersion::V08;
+            if pty {
+                wr.write_event(Event::Start(BytesStart::new("Pty")))?;
+            }
+            if let Some(name) = &cp.name {
+                write_text(wr, "Nm", name)?;
+            }
+            if let Some(addr) = &cp.address {
+                wr.write_event(Event::Start(BytesStart::new("PstlAdr")))?;
+                write_text(wr, "AdrLine", addr)?;
+                wr.write_event(Event::End(BytesStart::new("PstlAdr").to_end()))?;
+            }
+            if pty {
+                wr.write_event(Event::End(BytesStart::new("Pty").to_end()))?;
+            }
+            wr.write_event(Event::End(BytesStart::new(role).to_end()))?;
+        }
+        if let Some(account) = cp.account_id() {
+            let acct = format!("{role}Acct");
+            wr.write_event(Event::Start(BytesStart::new(acct.as_str())))?;
+            write_acct_id(wr, &account)?;
+            wr.write_event(Event::End(BytesStart::new(acct.as_str()).to_end()))?;
+        }
+        wr.write_event(Event::End(BytesStart::new("RltdPties").to_end()))?;
+    }
+
+    if let Some(bic) = &cp.bic {
+        let agt = format!("{role}Agt");
+        wr.write_event(Event::Start(BytesStart::new("RltdAgts")))?;
+        wr.write_event(Event::Start(BytesStart::new(agt.as_str())))?;
+        write_fin_instn(wr, version, bic)?;
+        wr.write_event(Event::End(BytesStart::new(agt.as_str()).to_end()))?;
+        wr.write_event(Event::End(BytesStart::new("RltdAgts").to_end()))?;
+    }
+    Ok(())
+}
+
+/// `ActiveOrHistoricCurrencyAndAmount`: сумма в знаках валюты, `Ccy` — код.
+fn write_amt<W: Write>(wr: &mut Writer<W>, amount: Decimal, ccy: Currency) -> Result<()> {
+    let amt = ccy.scale(amount)?.to_string();
+    wr.write_event(Event::Start(BytesStart::new("Amt").with_attributes([("Ccy", ccy.code())])))?;
+    wr.write_event(Event::Text(BytesText::new(&amt)))?;
+    wr.write_event(Event::End(BytesStart::new("Amt").to_end()))?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    wr: &mut Writer<W>,
+    name: &str,
+    text: &str,
+) -> std::result::Result<(), quick_xml::Error> {
+    wr.write_event(Event::Start(BytesStart::new(name)))?;
+    wr.write_event(Event::Text(BytesText::new(text)))?;
+    wr.write_event(Event::End(BytesStart::new(name).to_end()))?;
+    Ok(())
+}
+
+/// `<Id>` счёта: `IBAN` или `Othr/Id` со схемой `SchmeNm`.
+fn write_acct_id<W: Write>(
+    wr: &mut Writer<W>,
+    id: &AccountId,
+) -> std::result::Result<(), quick_xml::Error> {
+    wr.write_event(Event::Start(BytesStart::new("Id")))?;
+    match id {
+        AccountId::Iban(iban) => write_text(wr, "IBAN", iban)?,
+        other => {
+            wr.write_event(Event::Start(BytesStart::new("Othr")))?;
+            write_text(wr, "Id", other.number())?;
+            if let AccountId::Other { scheme: Some(scheme), .. } = other {
+                // внешние коды ISO — до 4 символов, прочее — Prtry
+                let el = if scheme.len() <= 4 { "Cd" } else { "Prtry" };
+                wr.write_event(Event::Start(BytesStart::new("SchmeNm")))?;
+                write_text(wr, el, scheme)?;
+                wr.write_event(Event::End(BytesStart::new("SchmeNm").to_end()))?;
+            }
+            wr.write_event(Event::End(BytesStart::new("Othr").to_end()))?;
+        }
+    }
+    wr.write_event(Event::End(BytesStart::new("Id").to_end()))?;
+    Ok(())
+}
+
+/// `<FinInstnId>`: BIC (с 001.04 — `BICFI`) или БИК в `ClrSysMmbId`
+/// (система `RUCBC` — Банк России); прочие коды — тоже в `ClrSysMmbId`.
+fn write_fin_instn<W: Write>(
+    wr: &mut Writer<W>,
+    version: CamtVersion,
+    bic: &Bic,
+) -> std::result::Result<(), quick_xml::Error> {
+    if bic.is_bik() {
+        write_fin_instn_ids(wr, version, None, Some(bic))
+    } else {
+        write_fin_instn_ids(wr, version, Some(bic), None)
+    }
+}
+
+/// `<FinInstnId>` с BIC и/или БИК — по схеме BIC идёт первым. Код не по
+/// шаблону BIC (BLZ из `?30` и т.п.) пишется номером участника без системы.
+fn write_fin_instn_ids<W: Write>(
+    wr: &mut Writer<W>,
+    version: CamtVersion,
+    bic: Option<&Bic>,
+    bik: Option<&str>,
+) -> std::result::Result<(), quick_xml::Error> {
+    wr.write_event(Event::Start(BytesStart::new("FinInstnId")))?;
+    let (bic, other) = match bic {
+        Some(b) if b.is_valid() => (Some(b), None),
+        b => (None, b.map(|b| &**b)),
+    };
+    if let Some(bic) = bic {
+        let bic_el = if version >= CamtVersion::V04 { "BICFI" } else { "BIC" };
+        write_text(wr, bic_el, bic)?;
+    }
+    let member = bik.map(|bik| (Some(RU_CLEARING), bik)).or(other.map(|id| (None, id)));
+    if let Some((system, id)) = member {
+        wr.write_event(Event::Start(BytesStart::new("ClrSysMmbId")))?;
+        if let Some(system) = system {
+            wr.write_event(Event::Start(BytesStart::new("ClrSysId")))?;
+            write_text(wr, "Cd", system)?;
+            wr.write_event(Event::End(BytesStart::new("ClrSysId").to_end()))?;
+        }
+        write_text(wr, "MmbId", id)?;
+        wr.write_event(Event::End(BytesStart::new("ClrSysMmbId").to_end()))?;
+    }
+    wr.write_event(Event::End(BytesStart::new("FinInstnId").to_end()))?;
+    Ok(())
+}
+
+/// `ISODateTime` без часового пояса — время в модели местное.
+fn format_datetime(dt: NaiveDateTime) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
 fn xml<E: std::fmt::Display>(e: E) -> FinioError {
     FinioError::Xml(e.to_string())
 }
@@ -174,154 +801,713 @@ fn xml<E: std::fmt::Display>(e: E) -> FinioError {
 /* ------------------------------- READ ----------------------------------- */
 
 impl ReadFormat for Camt053 {
-    fn read<R: BufRead>(r: R) -> Result<Statement> {
+    fn read_statements_with<R: BufRead>(
+        r: R,
+        mode: ParseMode,
+    ) -> Result<(Vec<Statement>, Vec<Diagnostic>)> {
+        stream::collect_with(Self::reader_with(r, mode), mode)
+    }
+}
+
+impl StreamRead for Camt053 {
+    type Reader<R: BufRead> = CamtReader<R>;
+
+    fn reader_with<R: BufRead>(r: R, mode: ParseMode) -> CamtReader<R> {
+        CamtReader::new(r, CamtMessage::Statement).mode(mode)
+    }
+}
+
+impl<R: BufRead> CamtReader<R> {
+    pub fn new(r: R, msg: CamtMessage) -> Self {
         let mut reader = Reader::from_reader(r);
         reader.trim_text(true);
+        CamtReader {
+            reader,
+            msg,
+            mode: ParseMode::Strict,
+            version: None,
+            buf: Vec::new(),
+            path: Vec::new(),
+            header: None,
+            amt_ccy: String::new(),
+            tx: None,
+            debtor: Counterparty::default(),
+            creditor: Counterparty::default(),
+            bal: None,
+            late_balances: Vec::new(),
+            late_closing: None,
+            pending: None,
+            booked: false,
+            out: VecDeque::new(),
+            done: false,
+        }
+    }
 
-        let mut st = Statement {
-            statement_id: None,
-            account_id: String::new(),
-            opening_balance: None,
-            closing_balance: None,
-            entries: Vec::new(),
-        };
+    /// В мягком режиме `<Ntry>` с ошибкой в поле пропускается целиком.
+    pub fn mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// Pull-парсер поверх событий quick-xml: каждый `<Stmt>` внутри
+/// BkToCstmrStmt (`<Rpt>`, `<Ntfctn>` для camt.052/054) — отдельная
+/// выписка, каждый `<Ntry>` отдаётся сразу.
+///
+/// Положение курсора — стек открытых элементов: поле берётся только по
+/// своему пути ISO 20022 от `<Stmt>` (`Acct/Id/IBAN`, `Ntry/Amt`, `Bal/Dt/Dt`…),
+/// одноимённые элементы в других местах (`AmtDtls/…/Amt`, `Othr/Id`) не мешают.
+pub struct CamtReader<R> {
+    reader: Reader<R>,
+    msg: CamtMessage,
+    mode: ParseMode,
+    /// версия из xmlns корневого Document
+    version: Option<CamtVersion>,
+    buf: Vec<u8>,
+    /// открытые элементы от `<Document>`
+    path: Vec<String>,
+    /// реквизиты текущего <Stmt>, пока не отданы (до первого <Ntry>)
+    header: Option<Statement>,
+    /// атрибут Ccy последнего открытого `<Amt>`
+    amt_ccy: String,
 
-        let mut buf = Vec::new();
-        let mut text_buf = String::new();
+    /// текущая `<TxDtls>`
+    tx: Option<TxDetails>,
+    /// RltdPties/RltdAgts текущей `<TxDtls>`; контрагент выбирается по CdtDbtInd
+    debtor: Counterparty,
+    creditor: Counterparty,
 
-        // «флаги» текущего положения курсора
-        let mut in_iban = false;
-        let mut in_id = false;       // Id внутри Stmt (не путать с Acct/Id/IBAN)
-        let mut in_amt = false;
-        let mut amt_ccy = String::new();
-        let mut in_cdt_dbt = false;
-        let mut in_book_dt = false;
-        let mut in_val_dt = false;
-        let mut in_addtl = false;
-        let mut in_ntry_ref = false;
+    /// текущий `<Bal>`
+    bal: Option<BalDraft>,
+    /// остатки после первого `<Ntry>` — уходят в trailer
+    late_balances: Vec<TypedBalance>,
+    late_closing: Option<Balance>,
 
-        let mut pending: Option<Entry> = None;
+    pending: Option<Entry>,
+    /// в текущей `<Ntry>` была `<BookgDt>`
+    booked: bool,
+    out: VecDeque<StatementEvent>,
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for CamtReader<R> {
+    type Item = Result<StatementEvent>;
 
+    fn next(&mut self) -> Option<Self::Item> {
         loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(e)) => {
-                    match e.local_name().as_ref() {
-                        b"IBAN" => in_iban = true,
-                        b"Id" => in_id = true,
-                        b"Amt" => {
-                            in_amt = true;
-                            amt_ccy.clear();
-                            for a in e.attributes().flatten() {
-                                if a.key.as_ref() == b"Ccy" {
-                                    if let Ok(v) = String::from_utf8(a.value.into_owned()) {
-                                        amt_ccy = v;
-                                    }
-                                }
-                            }
-                        }
-                        b"CdtDbtInd" => in_cdt_dbt = true,
-                        b"BookgDt" => in_book_dt = true,
-                        b"ValDt" => in_val_dt = true,
-                        b"AddtlNtryInf" => in_addtl = true,
-                        b"NtryRef" => in_ntry_ref = true,
-                        b"Ntry" => {
-                            let booking_date = NaiveDate::from_ymd_opt(1970, 1, 1)
-                                .ok_or_else(|| FinioError::Parse("invalid default booking date 1970-01-01".into()))?;
-                            pending = Some(Entry {
-                                booking_date,
-                                value_date: None,
-                                amount: Decimal::ZERO,
-                                currency: "XXX".into(),
-                                dc: DebitCredit::Credit,
-                                description: String::new(),
-                                reference: None,
-                            });
-                        }
-                        _ => {}
+            if let Some(ev) = self.out.pop_front() {
+                return Some(Ok(ev));
+            }
+            if self.done {
+                return None;
+            }
+            if let Err(e) = self.step() {
+                // ошибка в поле проводки не ломает разбор остального документа
+                let skip = self.mode == ParseMode::Lenient
+                    && e.diagnostic().is_some()
+                    && (self.pending.take().is_some() || self.bal.take().is_some());
+                self.done = !skip;
+                let at = Location::Xml {
+                    path: format!("/{}", self.path.join("/")),
+                    offset: Some(self.reader.buffer_position() as u64),
+                };
+                return Some(Err(e.located(at, "")));
+            }
+        }
+    }
+}
+
+impl<R: BufRead> CamtReader<R> {
+    /// Версия схемы, объявленная в документе; известна после `<Document>`.
+    pub fn version(&self) -> Option<CamtVersion> {
+        self.version
+    }
+
+    /// Читает документ целиком и сообщает, какая версия схемы была разобрана.
+    pub fn into_statements(mut self) -> Result<(Vec<Statement>, Option<CamtVersion>)> {
+        let sts = stream::collect(&mut self)?;
+        Ok((sts, self.version))
+    }
+
+    /// Обрабатывает одно XML-событие, складывая готовые события выписки в `out`.
+    fn step(&mut self) -> Result<()> {
+        self.buf.clear();
+        match self.reader.read_event_into(&mut self.buf) {
+            Ok(Event::Start(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                if let Err(err) = check_structure(&self.path, self.msg, &name) {
+                    // в мягком режиме чужое поддерево пропускается целиком, иначе
+                    // его закрывающий тег снимет со стека чужой элемент
+                    if self.mode == ParseMode::Lenient {
+                        let end = e.name().as_ref().to_vec();
+                        let mut skip = Vec::new();
+                        self.reader.read_to_end_into(QName(&end), &mut skip).map_err(xml)?;
                     }
+                    return Err(err);
                 }
-                Ok(Event::Text(t)) => {
-                    text_buf.clear();
-                    text_buf.push_str(
-                        &t.unescape()
-                            .map_err(|e| FinioError::Xml(e.to_string()))?
-                            .to_string(),
-                    );
-
-                    if in_iban {
-                        st.account_id = text_buf.clone();
-                    } else if in_id && st.statement_id.is_none() {
-                        // первый <Id> внутри Stmt — это идентификатор выписки
-                        st.statement_id = Some(text_buf.clone());
-                    } else if in_amt {
-                        if let Some(ref mut e) = pending {
-                            // сумма + валюта из атрибута Amt Ccy
-                            e.amount = Decimal::from_str_exact(&text_buf)
-                                .or_else(|_| text_buf.parse())
-                                .map_err(|e| FinioError::Parse(format!("camt amount: {e}")))?;
-                            if !amt_ccy.is_empty() {
-                                e.currency = amt_ccy.clone();
-                            }
+                self.path.push(name);
+                match e.local_name().as_ref() {
+                    b"Document" => {
+                        if let Some(v) = document_version(&e, self.msg)? {
+                            self.version = Some(v);
                         }
-                    } else if in_cdt_dbt {
-                        if let Some(ref mut e) = pending {
-                            e.dc = match text_buf.as_str() {
-                                "CRDT" => DebitCredit::Credit,
-                                "DBIT" => DebitCredit::Debit,
-                                other => {
-                                    return Err(FinioError::Parse(format!(
-                                        "CdtDbtInd {}",
-                                        other
-                                    )))
+                    }
+                    b"Amt" => {
+                        self.amt_ccy.clear();
+                        for a in e.attributes().flatten() {
+                            if a.key.as_ref() == b"Ccy" {
+                                if let Ok(v) = String::from_utf8(a.value.into_owned()) {
+                                    self.amt_ccy = v;
                                 }
                             }
                         }
-                    } else if in_book_dt {
-                        if let Some(ref mut e) = pending {
-                            e.booking_date = NaiveDate::parse_from_str(&text_buf, "%Y-%m-%d")
-                                .map_err(|e| FinioError::Parse(format!("{e}")))?;
+                    }
+                    _ if stmt_path(&self.path, self.msg).is_none() => {}
+                    tag if tag == self.msg.block().as_bytes() => {
+                        self.header = Some(Statement::default());
+                    }
+                    b"Bal" => self.bal = Some(BalDraft::default()),
+                    b"Ntry" => {
+                        // реквизиты выписки заканчиваются на первой проводке
+                        if let Some(h) = self.header.take() {
+                            self.out.push_back(StatementEvent::Header(Box::new(h)));
                         }
-                    } else if in_val_dt {
-                        if let Some(ref mut e) = pending {
-                            e.value_date = Some(
-                                NaiveDate::parse_from_str(&text_buf, "%Y-%m-%d")
-                                    .map_err(|e| FinioError::Parse(format!("{e}")))?,
-                            );
+                        // дата-заглушка: без <BookgDt> проводка не выпускается
+                        self.booked = false;
+                        self.pending = Some(Entry {
+                            booking_date: NaiveDate::default(),
+                            value_date: None,
+                            amount: Decimal::ZERO,
+                            currency: Currency::XXX,
+                            dc: DebitCredit::Credit,
+                            description: String::new(),
+                            reference: None,
+                            reversal: false,
+                            status: EntryStatus::Booked,
+                            funds_code: None,
+                            bank_reference: None,
+                            supplementary: None,
+                            counterparty: None,
+                            posting_text: None,
+                            bank_tx_code: None,
+                            remittance: Remittance::default(),
+                            details: Vec::new(),
+                        });
+                    }
+                    b"TxDtls" => {
+                        self.tx = Some(TxDetails::default());
+                        self.debtor = Counterparty::default();
+                        self.creditor = Counterparty::default();
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Empty(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                check_structure(&self.path, self.msg, &name)?;
+            }
+            Ok(Event::Text(t)) => {
+                let text = t
+                    .unescape()
+                    .map_err(|e| FinioError::Xml(e.to_string()))?
+                    .into_owned();
+                self.text(text)?;
+            }
+            Ok(Event::End(e)) => {
+                // глубина от <Stmt> до pop: 0 — закрывается сама выписка
+                let depth = stmt_path(&self.path, self.msg).map(<[String]>::len);
+                self.path.pop();
+                match (depth, e.local_name().as_ref()) {
+                    (Some(0), _) => {
+                        if let Some(h) = self.header.take() {
+                            self.out.push_back(StatementEvent::Header(Box::new(h)));
                         }
-                    } else if in_addtl {
-                        if let Some(ref mut e) = pending {
-                            e.description = text_buf.clone();
+                        let balances = std::mem::take(&mut self.late_balances);
+                        self.out.push_back(StatementEvent::Trailer(StatementTrailer {
+                            closing_balance: self.late_closing.take(),
+                            balances,
+                        }));
+                    }
+                    (Some(1), b"Bal") => self.finish_bal()?,
+                    (Some(3), b"TxDtls") => self.finish_tx(),
+                    (Some(1), b"Ntry") => {
+                        if self.pending.is_some() && !self.booked {
+                            return Err(Diagnostic::new(ErrorCode::MissingField, "entry without booking date")
+                                .field("Ntry/BookgDt")
+                                .into());
                         }
-                    } else if in_ntry_ref {
-                        if let Some(ref mut e) = pending {
-                            e.reference = Some(text_buf.clone());
+                        if let Some(mut e) = self.pending.take() {
+                            // контрагент и назначение единственной операции — проводки
+                            if let [d] = e.details.as_mut_slice() {
+                                e.counterparty = d.counterparty.take();
+                                e.remittance = std::mem::take(&mut d.remittance);
+                                if d.is_empty() {
+                                    e.details.clear();
+                                }
+                            }
+                            // без AddtlNtryInf описание — неструктурированное назначение
+                            if e.description.is_empty() {
+                                e.description = e.remittance.unstructured.join(" ");
+                            }
+                            e.bank_tx_code = e.bank_tx_code.take().and_then(|c| normalize_tx_code(c, e.dc));
+                            self.out.push_back(StatementEvent::Entry(Box::new(e)));
                         }
                     }
+                    _ => {}
                 }
-                Ok(Event::End(e)) => {
-                    match e.local_name().as_ref() {
-                        b"IBAN" => in_iban = false,
-                        b"Id" => in_id = false,
-                        b"Amt" => in_amt = false,
-                        b"CdtDbtInd" => in_cdt_dbt = false,
-                        b"BookgDt" => in_book_dt = false,
-                        b"ValDt" => in_val_dt = false,
-                        b"AddtlNtryInf" => in_addtl = false,
-                        b"NtryRef" => in_ntry_ref = false,
-                        b"Ntry" => {
-                            if let Some(e) = pending.take() {
-                                st.entries.push(e);
-                            }
-                        }
+            }
+            Ok(Event::Eof) => {
+                if let Some(open) = self.path.last() {
+                    let d = Diagnostic::new(ErrorCode::Structure, format!("unexpected end of document inside <{open}>"));
+                    return Err(d.into());
+                }
+                self.done = true;
+            }
+            Err(e) => return Err(FinioError::Xml(e.to_string())),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn text(&mut self, text: String) -> Result<()> {
+        // копия пути: обработчики ниже меняют self
+        let path = self.path.clone();
+        let Some(rel) = stmt_path(&path, self.msg) else {
+            return Ok(());
+        };
+        let rel: Vec<&str> = rel.iter().map(String::as_str).collect();
+        match rel.as_slice() {
+            ["Id"] => {
+                if let Some(h) = self.header.as_mut() {
+                    h.statement_id = Some(text);
+                }
+            }
+            // IBAN или иной идентификатор счёта (`Othr/Id`: внутренний номер, счёт в РФ…)
+            ["Acct", "Id", "IBAN"] => {
+                if let Some(h) = self.header.as_mut() {
+                    h.account_id = AccountId::Iban(text);
+                }
+            }
+            ["Acct", "Id", "Othr", "Id"] => {
+                if let Some(h) = self.header.as_mut() {
+                    h.account_id = AccountId::parse(&text);
+                }
+            }
+            ["Acct", "Id", "Othr", "SchmeNm", "Cd" | "Prtry"] => {
+                if let Some(AccountId::Other { scheme, .. }) = self.header.as_mut().map(|h| &mut h.account_id) {
+                    *scheme = Some(text);
+                }
+            }
+            ["Acct", "Svcr", "FinInstnId", "ClrSysMmbId", "MmbId"] => {
+                if let Some(h) = self.header.as_mut() {
+                    match &mut h.account_id {
+                        AccountId::Ru { bik, .. } => *bik = Some(text),
+                        _ if h.servicer_bic.is_none() => h.servicer_bic = Some(Bic::from(text)),
                         _ => {}
                     }
                 }
-                Ok(Event::Eof) => break,
-                Err(e) => return Err(FinioError::Xml(e.to_string())),
-                _ => {}
             }
-            buf.clear();
+            ["Acct", "Svcr", "FinInstnId", "BIC" | "BICFI"] => {
+                if let Some(h) = self.header.as_mut() {
+                    h.servicer_bic = Some(Bic::from(text));
+                }
+            }
+            ["Acct", "Ccy"] => {
+                if let Some(h) = self.header.as_mut() {
+                    h.account_currency = Some(parse_currency(&text, "Acct/Ccy")?);
+                }
+            }
+            ["Acct", "Ownr", "Nm"] => {
+                if let Some(h) = self.header.as_mut() {
+                    h.account_owner = Some(text);
+                }
+            }
+            [pgntn, "PgNb"] if *pgntn == self.msg.pagination() => {
+                if let Some(h) = self.header.as_mut() {
+                    h.page = Some(parse_value(&text, "PgNb")?);
+                }
+            }
+            [pgntn, "LastPgInd"] if *pgntn == self.msg.pagination() => {
+                if let Some(h) = self.header.as_mut() {
+                    h.last_page = Some(parse_value(&text, "LastPgInd")?);
+                }
+            }
+            ["ElctrncSeqNb"] => {
+                if let Some(h) = self.header.as_mut() {
+                    h.electronic_seq = Some(parse_value(&text, "ElctrncSeqNb")?);
+                }
+            }
+            ["LglSeqNb"] => {
+                if let Some(h) = self.header.as_mut() {
+                    h.legal_seq = Some(parse_value(&text, "LglSeqNb")?);
+                }
+            }
+            ["CreDtTm"] => {
+                if let Some(h) = self.header.as_mut() {
+                    h.created = Some(parse_datetime(&text, "CreDtTm")?);
+                    h.created_offset = DateTime::parse_from_rfc3339(text.trim()).ok().map(|dt| *dt.offset());
+                }
+            }
+            // FrDtTm по схеме раньше ToDtTm
+            ["FrToDt", "FrDtTm"] => {
+                if let Some(h) = self.header.as_mut() {
+                    let from = parse_datetime(&text, "FrDtTm")?;
+                    h.period = Some(Period { from, to: from });
+                }
+            }
+            ["FrToDt", "ToDtTm"] => {
+                if let Some(h) = self.header.as_mut() {
+                    let to = parse_datetime(&text, "ToDtTm")?;
+                    h.period = Some(Period { from: h.period.map_or(to, |p| p.from), to });
+                }
+            }
+            ["Bal", rest @ ..] => self.bal_text(rest, &text)?,
+            ["Ntry", rest @ ..] => self.ntry_text(rest, &text)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl<R> CamtReader<R> {
+    /// Текст внутри `<Bal>`: `Tp/CdOrPrtry/Cd`, `Amt`, `CdtDbtInd`, `Dt/Dt` или `Dt/DtTm`.
+    fn bal_text(&mut self, rel: &[&str], text: &str) -> Result<()> {
+        let Some(b) = self.bal.as_mut() else {
+            return Ok(());
+        };
+        match rel {
+            ["Tp", "CdOrPrtry", "Cd"] => b.code = text.to_string(),
+            ["Amt"] => {
+                b.amount = Some(parse_amount(text, "Bal/Amt")?);
+                b.currency = Some(parse_currency(&self.amt_ccy, "Bal/Amt/@Ccy")?);
+            }
+            ["CdtDbtInd"] => b.dc = Some(parse_cdt_dbt(text, "Bal/CdtDbtInd")?),
+            ["Dt", "Dt"] => b.date = Some(parse_date(text, "Bal/Dt/Dt")?),
+            ["Dt", "DtTm"] => b.date = Some(parse_date(text, "Bal/Dt/DtTm")?),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Закрывает `<Bal>`: OPBD/CLBD — входящий и исходящий остатки, прочие —
+    /// в `balances`. Остаток кладётся в заголовок выписки или, если проводки
+    /// уже пошли, в trailer.
+    fn finish_bal(&mut self) -> Result<()> {
+        let Some(b) = self.bal.as_ref() else {
+            return Ok(());
+        };
+        let missing = |field: &str| Diagnostic::new(ErrorCode::MissingField, "missing balance field").field(field);
+        let amount = b.amount.ok_or_else(|| missing("Bal/Amt"))?;
+        let currency = b.currency.ok_or_else(|| missing("Bal/Amt"))?;
+        let date = b.date.ok_or_else(|| missing("Bal/Dt"))?;
+        let Some(b) = self.bal.take() else {
+            return Ok(());
+        };
+        // сумма в CAMT без знака; без CdtDbtInd (старые файлы finio) — как есть
+        let signed = match b.dc {
+            Some(DebitCredit::Debit) => -amount.abs(),
+            Some(DebitCredit::Credit) => amount.abs(),
+            None => amount,
+        };
+        let plain = Balance { date, amount: signed, currency };
+        let kind = match b.code.as_str() {
+            "OPBD" => {
+                if let Some(h) = self.header.as_mut() {
+                    h.opening_balance = Some(plain);
+                }
+                return Ok(());
+            }
+            "CLBD" => {
+                match self.header.as_mut() {
+                    Some(h) => h.closing_balance = Some(plain),
+                    None => self.late_closing = Some(plain),
+                }
+                return Ok(());
+            }
+            code => code.parse().map_err(|_| missing("Bal/Tp/CdOrPrtry/Cd"))?,
+        };
+        let balance = TypedBalance::new(kind, &plain);
+        match self.header.as_mut() {
+            Some(h) => h.balances.push(balance),
+            None => self.late_balances.push(balance),
+        }
+        Ok(())
+    }
+
+    /// Текст внутри `<Ntry>` по пути от него.
+    fn ntry_text(&mut self, rel: &[&str], text: &str) -> Result<()> {
+        let Some(e) = self.pending.as_mut() else {
+            return Ok(());
+        };
+        match rel {
+            ["NtryRef"] => e.reference = Some(text.to_string()),
+            ["Amt"] => {
+                e.amount = parse_amount(text, "Ntry/Amt")?;
+                e.currency = parse_currency(&self.amt_ccy, "Ntry/Amt/@Ccy")?;
+            }
+            ["CdtDbtInd"] => e.dc = parse_cdt_dbt(text, "Ntry/CdtDbtInd")?,
+            ["RvslInd"] => e.reversal = matches!(text.trim(), "true" | "1"),
+            ["Sts"] | ["Sts", "Cd"] => {
+                e.status = text.parse().map_err(|msg: String| {
+                    Diagnostic::new(ErrorCode::InvalidValue, msg).field("Ntry/Sts").value(text)
+                })?
+            }
+            ["BookgDt", "Dt" | "DtTm"] => {
+                e.booking_date = parse_date(text, "Ntry/BookgDt")?;
+                self.booked = true;
+            }
+            ["ValDt", "Dt" | "DtTm"] => e.value_date = Some(parse_date(text, "Ntry/ValDt")?),
+            ["AcctSvcrRef"] => e.bank_reference = Some(text.to_string()),
+            ["AddtlNtryInf"] => e.description = text.to_string(),
+            ["BkTxCd", rest @ ..] => tx_code_text(e.bank_tx_code.get_or_insert_default(), rest, text),
+            ["NtryDtls", "TxDtls", rest @ ..] => self.tx_text(rest, text)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Текст внутри `NtryDtls/TxDtls` по пути от неё.
+    fn tx_text(&mut self, rel: &[&str], text: &str) -> Result<()> {
+        if let ["RltdPties" | "RltdAgts", rest @ ..] = rel {
+            self.party_text(rest, text);
+            return Ok(());
+        }
+        let Some(d) = self.tx.as_mut() else {
+            return Ok(());
+        };
+        let value = Some(text.to_string());
+        match rel {
+            ["Refs", "MsgId"] => d.refs.msg_id = value,
+            ["Refs", "InstrId"] => d.refs.instr_id = value,
+            ["Refs", "EndToEndId"] => d.refs.end_to_end_id = value,
+            ["Refs", "TxId"] => d.refs.tx_id = value,
+            ["Refs", "MndtId"] => d.refs.mandate_id = value,
+            // с 001.04 сумма операции ещё и прямо в <TxDtls><Amt>
+            ["AmtDtls", "TxAmt", "Amt"] | ["Amt"] => {
+                d.amount = Some(parse_amount(text, "TxDtls/AmtDtls/TxAmt/Amt")?);
+                d.currency = Some(parse_currency(&self.amt_ccy, "TxDtls/AmtDtls/TxAmt/Amt/@Ccy")?);
+            }
+            ["RmtInf", "Ustrd"] => d.remittance.unstructured.push(text.to_string()),
+            ["RltdDts", "AccptncDtTm"] => d.dates.acceptance = Some(parse_datetime(text, "TxDtls/RltdDts/AccptncDtTm")?),
+            ["RltdDts", "TradActvtyCtrctlSttlmDt"] => {
+                d.dates.trade_settlement = Some(parse_date(text, "TxDtls/RltdDts/TradActvtyCtrctlSttlmDt")?)
+            }
+            ["RltdDts", "TradDt"] => d.dates.trade = Some(parse_date(text, "TxDtls/RltdDts/TradDt")?),
+            ["RltdDts", "IntrBkSttlmDt"] => {
+                d.dates.interbank_settlement = Some(parse_date(text, "TxDtls/RltdDts/IntrBkSttlmDt")?)
+            }
+            ["RltdDts", "StartDt"] => d.dates.start = Some(parse_date(text, "TxDtls/RltdDts/StartDt")?),
+            ["RltdDts", "EndDt"] => d.dates.end = Some(parse_date(text, "TxDtls/RltdDts/EndDt")?),
+            ["RltdDts", "TxDtTm"] => d.dates.transaction = Some(parse_datetime(text, "TxDtls/RltdDts/TxDtTm")?),
+            ["RmtInf", "Strd", "CdtrRefInf", rest @ ..] => {
+                let r = d.remittance.creditor_reference.get_or_insert_with(|| CreditorReference {
+                    kind: None,
+                    issuer: None,
+                    reference: String::new(),
+                });
+                match rest {
+                    ["Tp", "CdOrPrtry", "Cd" | "Prtry"] => r.kind = value,
+                    ["Tp", "Issr"] => r.issuer = value,
+                    ["Ref"] => r.reference = text.to_string(),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Закрывает `<TxDtls>`: для кредита контрагент — плательщик, для дебета — получатель.
+    fn finish_tx(&mut self) {
+        let (Some(mut d), Some(e)) = (self.tx.take(), self.pending.as_mut()) else {
+            return;
+        };
+        let cp = match e.dc {
+            DebitCredit::Credit => std::mem::take(&mut self.debtor),
+            DebitCredit::Debit => std::mem::take(&mut self.creditor),
+        };
+        d.counterparty = (!cp.is_empty()).then_some(cp);
+        e.details.push(d);
+    }
+
+    /// Текст внутри `RltdPties`/`RltdAgts` проводки.
+    /// С 001.08 реквизиты стороны вложены в `<Pty>`, BIC называется `BICFI` с 001.04.
+    fn party_text(&mut self, rel: &[&str], text: &str) {
+        let rel: Vec<&str> = rel.iter().copied().filter(|p| *p != "Pty").collect();
+        let Some((role, rest)) = rel.split_first() else {
+            return;
+        };
+        let party = match *role {
+            "Dbtr" | "DbtrAcct" | "DbtrAgt" => &mut self.debtor,
+            "Cdtr" | "CdtrAcct" | "CdtrAgt" => &mut self.creditor,
+            // UltmtDbtr, IntrmyAgt1 и прочие участники не нужны
+            _ => return,
+        };
+        let value = Some(text.to_string());
+        match (*role, rest) {
+            ("Dbtr" | "Cdtr", ["Nm"]) => party.name = value,
+            ("Dbtr" | "Cdtr", ["PstlAdr", _]) => {
+                let addr = party.address.get_or_insert_with(String::new);
+                if !addr.is_empty() {
+                    addr.push_str(", ");
+                }
+                addr.push_str(text);
+            }
+            ("DbtrAcct" | "CdtrAcct", ["Id", "IBAN"] | ["Id", "Othr", "Id"]) => party.account = value,
+            ("DbtrAgt" | "CdtrAgt", ["FinInstnId", "BIC" | "BICFI"] | ["FinInstnId", "ClrSysMmbId", "MmbId"]) => {
+                party.bic = value.map(Bic::from)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Текст внутри `Ntry/BkTxCd` по пути от него.
+fn tx_code_text(code: &mut BankTxCode, rel: &[&str], text: &str) {
+    let iso = || IsoTxCode {
+        domain: String::new(),
+        family: String::new(),
+        sub_family: String::new(),
+    };
+    let prtry = || ProprietaryTxCode { code: String::new(), issuer: None };
+    match rel {
+        ["Domn", "Cd"] => code.iso.get_or_insert_with(iso).domain = text.to_string(),
+        ["Domn", "Fmly", "Cd"] => code.iso.get_or_insert_with(iso).family = text.to_string(),
+        ["Domn", "Fmly", "SubFmlyCd"] => code.iso.get_or_insert_with(iso).sub_family = text.to_string(),
+        ["Prtry", "Cd"] => code.proprietary.get_or_insert_with(prtry).code = text.to_string(),
+        ["Prtry", "Issr"] => code.proprietary.get_or_insert_with(prtry).issuer = Some(text.to_string()),
+        _ => {}
+    }
+}
+
+/// Сумма `<Amt>`: точка как разделитель.
+fn parse_amount(s: &str, field: &str) -> Result<Decimal> {
+    Decimal::from_str_exact(s).or_else(|_| s.parse()).map_err(|e| {
+        Diagnostic::new(ErrorCode::InvalidAmount, format!("invalid amount ({e})"))
+            .field(field)
+            .value(s)
+            .into()
+    })
+}
+
+/// `CRDT`/`DBIT`.
+fn parse_cdt_dbt(s: &str, field: &str) -> Result<DebitCredit> {
+    match s {
+        "CRDT" => Ok(DebitCredit::Credit),
+        "DBIT" => Ok(DebitCredit::Debit),
+        other => Err(Diagnostic::new(ErrorCode::InvalidDebitCredit, "expected CRDT or DBIT")
+            .field(field)
+            .value(other)
+            .into()),
+    }
+}
+
+/// Путь открытых элементов от `<Stmt>` (без него); `None` — курсор вне выписки.
+fn stmt_path(path: &[String], msg: CamtMessage) -> Option<&[String]> {
+    match path {
+        [doc, root, block, rest @ ..] if doc == "Document" && root == msg.root() && block == msg.block() => {
+            Some(rest)
+        }
+        _ => None,
+    }
+}
+
+/// Отвергает элемент `name` не на своём месте: чужой корень сообщения,
+/// `<Stmt>` вне него, `<Ntry>`/`<Bal>` не прямо в `<Stmt>`.
+fn check_structure(path: &[String], msg: CamtMessage, name: &str) -> Result<()> {
+    let (root, block) = (msg.root(), msg.block());
+    let parent = path.last().map(String::as_str);
+    let message = match (parent, name) {
+        (None, "Document") => return Ok(()),
+        (None, _) => format!("expected <Document>, found <{name}>"),
+        (Some("Document"), _) if name != root => format!("expected <{root}>, found <{name}>"),
+        _ if name == block && parent != Some(root) => format!("<{block}> outside <{root}>"),
+        (_, "Ntry" | "Bal") if stmt_path(path, msg).is_none_or(|p| !p.is_empty()) => {
+            format!("<{name}> not directly inside <{block}>")
+        }
+        _ => return Ok(()),
+    };
+    Err(Diagnostic::new(ErrorCode::Structure, message).field(name).into())
+}
+
+/// `<Bal>` до закрывающего тега.
+#[derive(Default)]
+struct BalDraft {
+    code: String,
+    amount: Option<Decimal>,
+    currency: Option<Currency>,
+    dc: Option<DebitCredit>,
+    date: Option<NaiveDate>,
+}
+
+/// Неполный ISO-код отбрасывается; `Prtry` с эмитентом `SWIFT` — это тип из
+/// `:61:`, и ISO-код, выведенный из него по таблице, не хранится отдельно.
+fn normalize_tx_code(mut code: BankTxCode, dc: DebitCredit) -> Option<BankTxCode> {
+    code.iso = code
+        .iso
+        .filter(|c| !c.domain.is_empty() && !c.family.is_empty() && !c.sub_family.is_empty());
+    match code.proprietary.take() {
+        Some(p) if p.issuer.as_deref() == Some(SWIFT_ISSUER) => {
+            if code.iso == swift_to_iso(&p.code, dc) {
+                code.iso = None;
+            }
+            code.swift = Some(p.code);
+        }
+        p => code.proprietary = p.filter(|p| !p.code.is_empty()),
+    }
+    (code != BankTxCode::default()).then_some(code)
+}
+
+/// Версия и вид сообщения по пространству имён `<Document>`; документ
+/// другого сообщения (camt.052 в reader'е camt.053 и т.п.) отвергается.
+fn document_version(e: &BytesStart, expected: CamtMessage) -> Result<Option<CamtVersion>> {
+    for a in e.attributes().flatten() {
+        let v = String::from_utf8_lossy(&a.value);
+        if let Some((msg, version)) = CamtVersion::from_namespace(&v) {
+            if msg != expected {
+                let d = Diagnostic::new(
+                    ErrorCode::Structure,
+                    format!("expected {} document, found {}", expected.code(), msg.code()),
+                );
+                return Err(d.field("Document/@xmlns").value(v).into());
+            }
+            return Ok(Some(version));
         }
-        Ok(st)
     }
+    Ok(None)
+}
+
+/// Дата из `<Dt>` или `<DtTm>` (ISO 8601; время и зона отбрасываются).
+/// `ISODateTime`: со смещением берётся местное время отправителя.
+fn parse_datetime(s: &str, field: &str) -> Result<NaiveDateTime> {
+    let s = s.trim();
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.naive_local())
+        .or_else(|_| NaiveDateTime::parse_from_str(s.trim_end_matches('Z'), "%Y-%m-%dT%H:%M:%S%.f"))
+        .map_err(|e| {
+            Diagnostic::new(ErrorCode::InvalidDate, format!("invalid date-time ({e})"))
+                .field(field)
+                .value(s)
+                .into()
+        })
+}
+
+/// Число или `true`/`false` из элемента `field`.
+fn parse_value<T: std::str::FromStr>(s: &str, field: &str) -> Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    s.trim().parse().map_err(|e| {
+        Diagnostic::new(ErrorCode::InvalidValue, format!("{e}")).field(field).value(s).into()
+    })
+}
+
+fn parse_date(s: &str, field: &str) -> Result<NaiveDate> {
+    let d = s.get(..10).unwrap_or(s);
+    NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|e| {
+        Diagnostic::new(ErrorCode::InvalidDate, format!("invalid date ({e})"))
+            .field(field)
+            .value(s)
+            .into()
+    })
 }
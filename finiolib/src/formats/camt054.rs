@@ -0,0 +1,46 @@
+//! CAMT.054 — уведомление о дебете/кредите (BkToCstmrDbtCdtNtfctn/Ntfctn).
+//!
+//! Разбор и запись проводок `Ntry` общие с CAMT.053 (см. `camt053`).
+//! Остатков `<Bal>` в уведомлениях нет: при записи они опускаются.
+
+use crate::{
+    error::{Diagnostic, Result},
+    formats::camt053::{CamtMessage, CamtReader, CamtWriter},
+    model::Statement,
+    stream,
+    traits::{ParseMode, ReadFormat, StreamRead, StreamWrite, WriteFormat},
+};
+use std::io::{BufRead, Write};
+
+pub struct Camt054;
+
+impl WriteFormat for Camt054 {
+    fn write_statements<W: Write>(w: W, sts: &[Statement]) -> Result<()> {
+        stream::write_all(&mut Self::writer(w), sts)
+    }
+}
+
+impl StreamWrite for Camt054 {
+    type Writer<W: Write> = CamtWriter<W>;
+
+    fn writer<W: Write>(w: W) -> CamtWriter<W> {
+        CamtWriter::new(w, CamtMessage::Notification)
+    }
+}
+
+impl ReadFormat for Camt054 {
+    fn read_statements_with<R: BufRead>(
+        r: R,
+        mode: ParseMode,
+    ) -> Result<(Vec<Statement>, Vec<Diagnostic>)> {
+        stream::collect_with(Self::reader_with(r, mode), mode)
+    }
+}
+
+impl StreamRead for Camt054 {
+    type Reader<R: BufRead> = CamtReader<R>;
+
+    fn reader_with<R: BufRead>(r: R, mode: ParseMode) -> CamtReader<R> {
+        CamtReader::new(r, CamtMessage::Notification).mode(mode)
+    }
+}
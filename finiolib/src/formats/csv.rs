@@ -1,13 +1,51 @@
 //! Простой CSV: заголовки:
-//! booking_date,value_date,amount,currency,dc,description,reference,account_id,opening_amount,opening_currency,opening_date,closing_amount,closing_currency,closing_date
+//! booking_date,value_date,amount,currency,dc,description,reference,account_id,opening_amount,opening_currency,opening_date,closing_amount,closing_currency,closing_date,statement_id,
+//! counterparty_name,counterparty_account,counterparty_bic,counterparty_address,
+//! bank_tx_iso,bank_tx_swift,bank_tx_proprietary,bank_tx_issuer,balances,status,
+//! created,electronic_seq,legal_seq,page,last_page,period_from,period_to,
+//! account_currency,account_owner,servicer_bic
+//!
+//! Одна строка — одна проводка; реквизиты выписки повторяются в каждой строке.
+//! Подряд идущие строки с одинаковыми `account_id`/`statement_id` образуют одну
+//! выписку. Колонки `statement_id`, `counterparty_*`, `bank_tx_*`, `balances`
+//! и `status` (booked, pending, info; по умолчанию booked) необязательны при чтении. `balances` — прочие остатки выписки через `;`:
+//! `interim_closing C 2025-10-31 100.00 EUR; forward_available C 2025-11-01 90.00 EUR`.
+//! Реквизиты выписки от `created` до `servicer_bic` тоже необязательны; дата-время
+//! (`created`, `period_*`) — `YYYY-MM-DDTHH:MM:SS`, `last_page` — true/false.
+//!
+//! Выгрузки банков со своими колонками читаются через `CsvReader::profile`
+//! (см. `csv_profile`).
 
-use crate::{error::{FinioError, Result}, model::{Balance, DebitCredit, Entry, Statement}};
-use chrono::NaiveDate;
-use csv::{ReaderBuilder, WriterBuilder};
+use crate::{
+    account::{AccountId, Bic},
+    currency::parse_currency,
+    error::{Diagnostic, ErrorCode, Location, Result},
+    formats::csv_profile::{AmountSign, Column, CsvProfile, ProfileColumns},
+    model::{
+        Balance, BankTxCode, Counterparty, DebitCredit, Entry, EntryStatus, Period,
+        ProprietaryTxCode, Remittance, Statement, TypedBalance,
+    },
+    stream::{self, StatementEvent, StatementTrailer},
+    traits::{ParseMode, StatementWriter, StreamRead, StreamWrite},
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
 use rust_decimal::Decimal;
+use std::collections::VecDeque;
 use std::io::{BufRead, Write};
 
-#[derive(serde::Deserialize)]
+/// Колонки в порядке записи (по ним же формат опознаётся в `detect`).
+pub const COLUMNS: [&str; 35] = [
+    "booking_date", "value_date", "amount", "currency", "dc", "description", "reference",
+    "account_id", "opening_amount", "opening_currency", "opening_date",
+    "closing_amount", "closing_currency", "closing_date", "statement_id",
+    "counterparty_name", "counterparty_account", "counterparty_bic", "counterparty_address",
+    "bank_tx_iso", "bank_tx_swift", "bank_tx_proprietary", "bank_tx_issuer", "balances",
+    "status", "created", "electronic_seq", "legal_seq", "page", "last_page",
+    "period_from", "period_to", "account_currency", "account_owner", "servicer_bic",
+];
+
+#[derive(serde::Deserialize, Default)]
 struct CsvRow {
     booking_date: String,
     value_date: Option<String>,
@@ -25,6 +63,33 @@ struct CsvRow {
     closing_amount: Option<String>,
     closing_currency: Option<String>,
     closing_date: Option<String>,
+
+    statement_id: Option<String>,
+
+    counterparty_name: Option<String>,
+    counterparty_account: Option<String>,
+    counterparty_bic: Option<String>,
+    counterparty_address: Option<String>,
+
+    bank_tx_iso: Option<String>,
+    bank_tx_swift: Option<String>,
+    bank_tx_proprietary: Option<String>,
+    bank_tx_issuer: Option<String>,
+
+    balances: Option<String>,
+
+    status: Option<String>,
+
+    created: Option<String>,
+    electronic_seq: Option<String>,
+    legal_seq: Option<String>,
+    page: Option<String>,
+    last_page: Option<String>,
+    period_from: Option<String>,
+    period_to: Option<String>,
+    account_currency: Option<String>,
+    account_owner: Option<String>,
+    servicer_bic: Option<String>,
 }
 
 #[derive(serde::Serialize)]
@@ -36,7 +101,7 @@ struct CsvOutRow<'a> {
     dc: &'a str,
     description: &'a str,
     reference: &'a Option<String>,
-    account_id: &'a str,
+    account_id: String,
 
     opening_amount: Option<String>,
     opening_currency: Option<&'a str>,
@@ -45,102 +110,582 @@ struct CsvOutRow<'a> {
     closing_amount: Option<String>,
     closing_currency: Option<&'a str>,
     closing_date: Option<String>,
+
+    statement_id: &'a Option<String>,
+
+    counterparty_name: Option<&'a str>,
+    counterparty_account: Option<&'a str>,
+    counterparty_bic: Option<&'a str>,
+    counterparty_address: Option<&'a str>,
+
+    bank_tx_iso: Option<String>,
+    bank_tx_swift: Option<&'a str>,
+    bank_tx_proprietary: Option<&'a str>,
+    bank_tx_issuer: Option<&'a str>,
+
+    balances: Option<String>,
+
+    status: String,
+
+    created: Option<String>,
+    electronic_seq: Option<u64>,
+    legal_seq: Option<u64>,
+    page: Option<u32>,
+    last_page: Option<bool>,
+    period_from: Option<String>,
+    period_to: Option<String>,
+    account_currency: Option<&'a str>,
+    account_owner: Option<&'a str>,
+    servicer_bic: Option<&'a str>,
 }
 
 pub struct Csv;
 
 impl crate::traits::ReadFormat for Csv {
-    fn read<R: BufRead>(r: R) -> Result<Statement> {
-        let mut rdr = ReaderBuilder::new().flexible(true).from_reader(r);
-        let mut entries = Vec::new();
-        let mut account_id = String::new();
-        let mut opening: Option<Balance> = None;
-        let mut closing: Option<Balance> = None;
-
-        for rec in rdr.deserialize::<CsvRow>() {
-            let row = rec?;
-            if account_id.is_empty() {
-                account_id = row.account_id.clone();
+    fn read_statements_with<R: BufRead>(
+        r: R,
+        mode: ParseMode,
+    ) -> Result<(Vec<Statement>, Vec<Diagnostic>)> {
+        stream::collect_with(Self::reader_with(r, mode), mode)
+    }
+}
+
+impl StreamRead for Csv {
+    type Reader<R: BufRead> = CsvReader<R>;
+
+    fn reader_with<R: BufRead>(r: R, mode: ParseMode) -> CsvReader<R> {
+        CsvReader {
+            rdr: ReaderBuilder::new().flexible(true).from_reader(r),
+            mode,
+            headers: None,
+            record: StringRecord::new(),
+            key: None,
+            closing_known: false,
+            closing: None,
+            out: VecDeque::new(),
+            done: false,
+            profile: None,
+            columns: None,
+        }
+    }
+}
+
+/// Построчный reader: реквизиты выписки берутся из первой её строки.
+pub struct CsvReader<R> {
+    rdr: csv::Reader<R>,
+    /// в мягком режиме испорченная строка пропускается целиком
+    mode: ParseMode,
+    headers: Option<StringRecord>,
+    record: StringRecord,
+    /// (account_id, statement_id) текущей выписки
+    key: Option<(String, Option<String>)>,
+    closing_known: bool,
+    /// закрывающий баланс, впервые встреченный после первой строки
+    closing: Option<Balance>,
+    out: VecDeque<Result<StatementEvent>>,
+    done: bool,
+    profile: Option<CsvProfile>,
+    /// колонки профиля по заголовку выгрузки
+    columns: Option<ProfileColumns<Column>>,
+}
+
+impl<R: BufRead> Iterator for CsvReader<R> {
+    type Item = Result<StatementEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(ev) = self.out.pop_front() {
+                return Some(ev);
+            }
+            if self.done {
+                return None;
             }
+            if let Err(e) = self.read_row() {
+                self.done |= self.mode == ParseMode::Strict || e.diagnostic().is_none();
+                self.out.push_back(Err(e));
+            }
+        }
+    }
+}
 
-            if opening.is_none() {
-                if let (Some(a), Some(c), Some(d)) = (&row.opening_amount, &row.opening_currency, &row.opening_date) {
-                    opening = Some(Balance {
-                        amount: a.parse().map_err(|e| FinioError::Parse(format!("opening amount: {e}")))?,
-                        currency: c.clone(),
-                        date: NaiveDate::parse_from_str(d, "%Y-%m-%d")
-                            .map_err(|e| FinioError::Parse(format!("opening date: {e}")))?,
-                    });
-                }
+impl<R: BufRead> CsvReader<R> {
+    /// Банковская выгрузка: разделители, шапка и колонки — по профилю.
+    pub fn profile(mut self, profile: CsvProfile) -> Self {
+        self.rdr = ReaderBuilder::new()
+            .flexible(true)
+            .has_headers(false)
+            .delimiter(profile.delimiter as u8)
+            .quote(profile.quote as u8)
+            .from_reader(self.rdr.into_inner());
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Пропускает шапку выгрузки и находит колонки профиля в заголовке.
+    /// Ошибка здесь — ошибка всего файла, а не записи.
+    fn read_profile_header(&mut self) -> Result<()> {
+        let Some(p) = &self.profile else {
+            return Ok(());
+        };
+        self.done = true;
+        p.check()?;
+        for _ in 0..p.skip_rows {
+            if !self.rdr.read_record(&mut self.record)? {
+                break;
             }
-            if closing.is_none() {
-                if let (Some(a), Some(c), Some(d)) = (&row.closing_amount, &row.closing_currency, &row.closing_date) {
-                    closing = Some(Balance {
-                        amount: a.parse().map_err(|e| FinioError::Parse(format!("closing amount: {e}")))?,
-                        currency: c.clone(),
-                        date: NaiveDate::parse_from_str(d, "%Y-%m-%d")
-                            .map_err(|e| FinioError::Parse(format!("closing date: {e}")))?,
-                    });
-                }
+        }
+        let header = (p.has_header && self.rdr.read_record(&mut self.record)?).then(|| self.record.clone());
+        self.columns = Some(p.resolve(header.as_ref())?);
+        self.headers = Some(header.unwrap_or_default());
+        self.done = false;
+        Ok(())
+    }
+
+    /// Читает и разбирает следующую запись; ошибки привязываются к ней.
+    fn read_row(&mut self) -> Result<()> {
+        if self.headers.is_none() {
+            match self.profile {
+                Some(_) => self.read_profile_header()?,
+                None => self.headers = Some(self.rdr.headers()?.clone()),
             }
+        }
+        if !self.rdr.read_record(&mut self.record)? {
+            self.done = true;
+            self.close();
+            return Ok(());
+        }
+        let at = self
+            .record
+            .position()
+            .map(|p| Location::Record { record: p.record(), line: p.line() });
+        let raw = self.record.iter().collect::<Vec<_>>().join(",");
+        let row = match (&self.profile, &self.columns) {
+            (Some(p), Some(cols)) => profiled_row(p, cols, &self.record),
+            _ => self
+                .record
+                .deserialize(self.headers.as_ref())
+                .map(Some)
+                .map_err(|e| self.deserialize_error(e)),
+        };
+        row.and_then(|row| match row {
+            Some(row) => self.row(row),
+            None => Ok(()),
+        })
+        .map_err(|e| match at {
+            Some(at) => e.located(at, &raw),
+            None => e,
+        })
+    }
 
-            let dc = match row.dc.as_str() {
-                "D" | "d" | "debit" => DebitCredit::Debit,
-                "C" | "c" | "credit" => DebitCredit::Credit,
-                other => return Err(FinioError::Parse(format!("unknown dc: {other}"))),
-            };
+    /// Ошибка serde по строке (не хватает колонок) — диагностика с именем колонки.
+    fn deserialize_error(&self, e: csv::Error) -> crate::error::FinioError {
+        let csv::ErrorKind::Deserialize { err, .. } = e.kind() else {
+            return e.into();
+        };
+        let mut d = Diagnostic::new(ErrorCode::MissingField, err.to_string());
+        let column = err
+            .field()
+            .and_then(|i| self.headers.as_ref()?.get(i as usize));
+        if let Some(c) = column {
+            d = d.field(c);
+        }
+        d.into()
+    }
+}
+
+impl<R> CsvReader<R> {
+    fn row(&mut self, row: CsvRow) -> Result<()> {
+        let statement_id = row.statement_id.clone().filter(|s| !s.is_empty());
 
-            entries.push(Entry {
-                booking_date: NaiveDate::parse_from_str(&row.booking_date, "%Y-%m-%d")
-                    .map_err(|e| FinioError::Parse(format!("booking_date: {e}")))?,
-                value_date: match row.value_date {
-                    Some(v) => Some(NaiveDate::parse_from_str(&v, "%Y-%m-%d")
-                        .map_err(|e| FinioError::Parse(format!("value_date: {e}")))?),
+        // смена счёта или идентификатора выписки — начало новой выписки
+        let key = (row.account_id.clone(), statement_id.clone());
+        if self.key.as_ref() != Some(&key) {
+            self.close();
+            let period = match (&row.period_from, &row.period_to) {
+                (Some(from), Some(to)) => Some(Period {
+                    from: parse_datetime(from, "period_from")?,
+                    to: parse_datetime(to, "period_to")?,
+                }),
+                _ => None,
+            };
+            let header = Statement {
+                statement_id,
+                electronic_seq: parse_opt(&row.electronic_seq, "electronic_seq")?,
+                legal_seq: parse_opt(&row.legal_seq, "legal_seq")?,
+                page: parse_opt(&row.page, "page")?,
+                last_page: parse_opt(&row.last_page, "last_page")?,
+                created: match &row.created {
+                    Some(v) => Some(parse_datetime(v, "created")?),
+                    None => None,
+                },
+                created_offset: None,
+                period,
+                account_id: AccountId::parse(&row.account_id),
+                account_currency: match &row.account_currency {
+                    Some(v) => Some(parse_currency(v, "account_currency")?),
                     None => None,
                 },
-                amount: row.amount.parse::<Decimal>()
-                    .map_err(|e| FinioError::Parse(format!("amount: {e}")))?,
-                currency: row.currency,
-                dc,
-                description: row.description,
-                reference: row.reference,
-            });
+                account_owner: row.account_owner.clone(),
+                servicer_bic: row.servicer_bic.as_deref().map(Bic::from),
+                opening_balance: row_balance(&row.opening_amount, &row.opening_currency, &row.opening_date, "opening")?,
+                closing_balance: row_balance(&row.closing_amount, &row.closing_currency, &row.closing_date, "closing")?,
+                balances: match &row.balances {
+                    Some(v) => parse_balances(v)?,
+                    None => Vec::new(),
+                },
+                entries: Vec::new(),
+                ..Statement::default()
+            };
+            self.closing_known = header.closing_balance.is_some();
+            self.key = Some(key);
+            self.out.push_back(Ok(StatementEvent::Header(Box::new(header))));
+        } else if !self.closing_known {
+            self.closing = row_balance(&row.closing_amount, &row.closing_currency, &row.closing_date, "closing")?;
+            self.closing_known = self.closing.is_some();
         }
 
-        Ok(Statement {
-            statement_id: None,
-            account_id,
-            opening_balance: opening,
-            closing_balance: closing,
-            entries,
-        })
+        let dc = match row.dc.as_str() {
+            "D" | "d" | "debit" => DebitCredit::Debit,
+            "C" | "c" | "credit" => DebitCredit::Credit,
+            other => {
+                return Err(Diagnostic::new(ErrorCode::InvalidDebitCredit, "expected D or C")
+                    .field("dc")
+                    .value(other)
+                    .into())
+            }
+        };
+        let iso = match row.bank_tx_iso {
+            Some(v) => Some(v.parse().map_err(|msg: String| {
+                Diagnostic::new(ErrorCode::InvalidValue, msg).field("bank_tx_iso").value(&v)
+            })?),
+            None => None,
+        };
+        let status = match row.status {
+            Some(v) => v.parse().map_err(|msg: String| {
+                Diagnostic::new(ErrorCode::InvalidValue, msg).field("status").value(&v)
+            })?,
+            None => EntryStatus::Booked,
+        };
+        let bank_tx_code = BankTxCode {
+            iso,
+            swift: row.bank_tx_swift,
+            proprietary: row
+                .bank_tx_proprietary
+                .map(|code| ProprietaryTxCode { code, issuer: row.bank_tx_issuer }),
+        };
+
+        self.out.push_back(Ok(StatementEvent::Entry(Box::new(Entry {
+            booking_date: parse_date(&row.booking_date, "booking_date")?,
+            value_date: match row.value_date {
+                Some(v) => Some(parse_date(&v, "value_date")?),
+                None => None,
+            },
+            amount: parse_amount(&row.amount, "amount")?,
+            currency: parse_currency(&row.currency, "currency")?,
+            dc,
+            description: row.description,
+            reference: row.reference,
+            reversal: false,
+            status,
+            funds_code: None,
+            bank_reference: None,
+            supplementary: None,
+            counterparty: Some(Counterparty {
+                name: row.counterparty_name,
+                account: row.counterparty_account,
+                bic: row.counterparty_bic.map(Bic::from),
+                address: row.counterparty_address,
+                ..Counterparty::default()
+            })
+            .filter(|c| !c.is_empty()),
+            posting_text: None,
+            bank_tx_code: (bank_tx_code != BankTxCode::default()).then_some(bank_tx_code),
+            remittance: Remittance::default(),
+            details: Vec::new(),
+        }))));
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        if self.key.take().is_some() {
+            let closing_balance = self.closing.take();
+            self.out.push_back(Ok(StatementEvent::Trailer(StatementTrailer { closing_balance, balances: Vec::new() })));
+        }
     }
 }
 
-impl crate::traits::WriteFormat for Csv {
-    fn write<W: Write>(mut w: W, st: &Statement) -> Result<()> {
-        let mut wrt = WriterBuilder::new().from_writer(&mut w);
-
-        for e in &st.entries {
-            let out = CsvOutRow {
-                booking_date: e.booking_date.format("%Y-%m-%d").to_string(),
-                value_date: e.value_date.map(|d| d.format("%Y-%m-%d").to_string()),
-                amount: e.amount.to_string(),
-                currency: &e.currency,
-                dc: match e.dc { DebitCredit::Debit => "D", DebitCredit::Credit => "C" },
-                description: &e.description,
-                reference: &e.reference,
-                account_id: &st.account_id,
-                opening_amount: st.opening_balance.as_ref().map(|b| b.amount.to_string()),
-                opening_currency: st.opening_balance.as_ref().map(|b| b.currency.as_str()),
-                opening_date: st.opening_balance.as_ref().map(|b| b.date.format("%Y-%m-%d").to_string()),
-                closing_amount: st.closing_balance.as_ref().map(|b| b.amount.to_string()),
-                closing_currency: st.closing_balance.as_ref().map(|b| b.currency.as_str()),
-                closing_date: st.closing_balance.as_ref().map(|b| b.date.format("%Y-%m-%d").to_string()),
+/// Запись выгрузки по профилю — в колонки `Csv`; `None` — строка без даты
+/// проводки (итог, подвал).
+fn profiled_row(p: &CsvProfile, cols: &ProfileColumns<Column>, rec: &StringRecord) -> Result<Option<CsvRow>> {
+    let cell = |c: &Option<Column>| cell(rec, c);
+    let required = |c: &Option<Column>| {
+        cell(c).ok_or_else(|| -> crate::error::FinioError {
+            Diagnostic::new(ErrorCode::MissingField, "empty cell").field(column_name(c)).into()
+        })
+    };
+    let Some(booking_date) = cell(&cols.booking_date) else {
+        return Ok(None);
+    };
+    let booking_date = p.date(booking_date, column_name(&cols.booking_date))?;
+    let value_date = match cell(&cols.value_date) {
+        Some(v) => Some(p.date(v, column_name(&cols.value_date))?),
+        None => None,
+    };
+
+    let (amount, dc) = match p.sign {
+        AmountSign::Signed => {
+            let a = p.amount(required(&cols.amount)?, column_name(&cols.amount))?;
+            (a.abs(), if a.is_sign_negative() { DebitCredit::Debit } else { DebitCredit::Credit })
+        }
+        AmountSign::Indicator => (
+            p.amount(required(&cols.amount)?, column_name(&cols.amount))?.abs(),
+            p.dc(required(&cols.dc)?, column_name(&cols.dc))?,
+        ),
+        // заполнена одна из колонок; нулевое списание — не списание
+        AmountSign::Split => {
+            let debit = match cell(&cols.debit) {
+                Some(v) => Some(p.amount(v, column_name(&cols.debit))?).filter(|a| !a.is_zero()),
+                None => None,
             };
-            wrt.serialize(out)?;
+            match debit {
+                Some(a) => (a.abs(), DebitCredit::Debit),
+                None => {
+                    let a = p.amount(required(&cols.credit)?, column_name(&cols.credit))?;
+                    (a.abs(), DebitCredit::Credit)
+                }
+            }
+        }
+    };
+    let currency = match (cell(&cols.currency), p.currency) {
+        (Some(c), _) => c.to_string(),
+        (None, Some(c)) => c.code().to_string(),
+        (None, None) => required(&cols.currency)?.to_string(),
+    };
+
+    let text = |c: &Option<Column>| cell(c).map(str::to_string);
+    Ok(Some(CsvRow {
+        booking_date: booking_date.format("%Y-%m-%d").to_string(),
+        value_date: value_date.map(|d| d.format("%Y-%m-%d").to_string()),
+        amount: amount.to_string(),
+        currency,
+        dc: match dc { DebitCredit::Debit => "D", DebitCredit::Credit => "C" }.into(),
+        description: text(&cols.description).unwrap_or_default(),
+        reference: text(&cols.reference),
+        account_id: text(&cols.account_id).or_else(|| p.account_id.clone()).unwrap_or_default(),
+        statement_id: text(&cols.statement_id),
+        counterparty_name: text(&cols.counterparty_name),
+        counterparty_account: text(&cols.counterparty_account),
+        counterparty_bic: text(&cols.counterparty_bic),
+        ..CsvRow::default()
+    }))
+}
+
+/// Непустое значение колонки профиля.
+fn cell<'r>(rec: &'r StringRecord, c: &Option<Column>) -> Option<&'r str> {
+    c.as_ref()
+        .and_then(|c| rec.get(c.index))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+fn column_name(c: &Option<Column>) -> &str {
+    c.as_ref().map_or("", |c| c.name.as_str())
+}
+
+/// Баланс из тройки колонок amount/currency/date; неполная тройка — баланса нет.
+fn row_balance(
+    amount: &Option<String>,
+    currency: &Option<String>,
+    date: &Option<String>,
+    what: &str,
+) -> Result<Option<Balance>> {
+    let (Some(a), Some(c), Some(d)) = (amount, currency, date) else {
+        return Ok(None);
+    };
+    Ok(Some(Balance {
+        amount: parse_amount(a, &format!("{what}_amount"))?,
+        currency: parse_currency(c, &format!("{what}_currency"))?,
+        date: parse_date(d, &format!("{what}_date"))?,
+    }))
+}
+
+/// Колонка `balances`: `вид D|C дата сумма валюта` через `;`.
+fn parse_balances(s: &str) -> Result<Vec<TypedBalance>> {
+    let invalid = |msg: &str, v: &str| -> crate::error::FinioError {
+        Diagnostic::new(ErrorCode::InvalidValue, msg).field("balances").value(v).into()
+    };
+    s.split(';')
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+        .map(|b| {
+            let [kind, dc, date, amount, currency] = b.split_whitespace().collect::<Vec<_>>()[..] else {
+                return Err(invalid("expected `kind D|C date amount currency`", b));
+            };
+            Ok(TypedBalance {
+                kind: kind.parse().map_err(|msg: String| invalid(&msg, kind))?,
+                dc: match dc {
+                    "D" => DebitCredit::Debit,
+                    "C" => DebitCredit::Credit,
+                    _ => return Err(invalid("expected D or C", dc)),
+                },
+                date: parse_date(date, "balances")?,
+                amount: parse_amount(amount, "balances")?,
+                currency: parse_currency(currency, "balances")?,
+            })
+        })
+        .collect()
+}
+
+fn format_balances(bs: &[TypedBalance]) -> Result<Option<String>> {
+    let parts = bs
+        .iter()
+        .map(|b| {
+            let dc = match b.dc { DebitCredit::Debit => "D", DebitCredit::Credit => "C" };
+            let amount = b.currency.scale(b.amount)?;
+            Ok(format!("{} {dc} {} {amount} {}", b.kind, b.date.format("%Y-%m-%d"), b.currency))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((!parts.is_empty()).then(|| parts.join("; ")))
+}
+
+fn parse_date(s: &str, column: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| {
+        Diagnostic::new(ErrorCode::InvalidDate, format!("expected YYYY-MM-DD ({e})"))
+            .field(column)
+            .value(s)
+            .into()
+    })
+}
+
+fn parse_datetime(s: &str, column: &str) -> Result<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").map_err(|e| {
+        Diagnostic::new(ErrorCode::InvalidDate, format!("expected YYYY-MM-DDTHH:MM:SS ({e})"))
+            .field(column)
+            .value(s)
+            .into()
+    })
+}
+
+/// Необязательная колонка-число или true/false.
+fn parse_opt<T: std::str::FromStr>(s: &Option<String>, column: &str) -> Result<Option<T>>
+where
+    T::Err: std::fmt::Display,
+{
+    let Some(s) = s else {
+        return Ok(None);
+    };
+    s.parse().map(Some).map_err(|e| {
+        Diagnostic::new(ErrorCode::InvalidValue, format!("{e}")).field(column).value(s).into()
+    })
+}
+
+fn parse_amount(s: &str, column: &str) -> Result<Decimal> {
+    s.parse().map_err(|e| {
+        Diagnostic::new(ErrorCode::InvalidAmount, format!("invalid amount ({e})"))
+            .field(column)
+            .value(s)
+            .into()
+    })
+}
+
+impl crate::traits::WriteFormat for Csv {
+    fn write_statements<W: Write>(w: W, sts: &[Statement]) -> Result<()> {
+        stream::write_all(&mut Self::writer(w), sts)
+    }
+}
+
+impl StreamWrite for Csv {
+    type Writer<W: Write> = CsvWriter<W>;
+
+    fn writer<W: Write>(w: W) -> CsvWriter<W> {
+        CsvWriter {
+            wrt: WriterBuilder::new().from_writer(w),
+            header: Statement::default(),
+            deferred: None,
         }
-        wrt.flush()?;
+    }
+}
+
+/// Закрывающий баланс повторяется в каждой строке, поэтому если его нет
+/// в заголовке, строки выписки придерживаются до trailer.
+pub struct CsvWriter<W: Write> {
+    wrt: csv::Writer<W>,
+    header: Statement,
+    deferred: Option<Vec<Entry>>,
+}
+
+impl<W: Write> StatementWriter for CsvWriter<W> {
+    fn begin(&mut self, st: &Statement) -> Result<()> {
+        self.header = stream::header_of(st);
+        self.deferred = st.closing_balance.is_none().then(Vec::new);
         Ok(())
     }
+
+    fn entry(&mut self, e: &Entry) -> Result<()> {
+        match &mut self.deferred {
+            Some(es) => es.push(e.clone()),
+            None => self.wrt.serialize(out_row(&self.header, self.header.closing_balance.as_ref(), e)?)?,
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, t: &StatementTrailer) -> Result<()> {
+        if let Some(es) = self.deferred.take() {
+            self.header.balances.extend(t.balances.iter().cloned());
+            for e in &es {
+                self.wrt.serialize(out_row(&self.header, t.closing_balance.as_ref(), e)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.wrt.flush()?;
+        Ok(())
+    }
+}
+
+/// Суммы — в знаках валюты (`10.50 EUR`, `100 JPY`).
+fn out_row<'a>(st: &'a Statement, closing: Option<&'a Balance>, e: &'a Entry) -> Result<CsvOutRow<'a>> {
+    let cp = e.counterparty.as_ref();
+    let tx = e.bank_tx_code.as_ref();
+    let prtry = tx.and_then(|c| c.proprietary.as_ref());
+    let balance_amount = |b: &Balance| b.currency.scale(b.amount).map(|a| a.to_string());
+    Ok(CsvOutRow {
+        booking_date: e.booking_date.format("%Y-%m-%d").to_string(),
+        value_date: e.value_date.map(|d| d.format("%Y-%m-%d").to_string()),
+        amount: e.currency.scale(e.amount)?.to_string(),
+        currency: e.currency.code(),
+        dc: match e.dc { DebitCredit::Debit => "D", DebitCredit::Credit => "C" },
+        description: &e.description,
+        reference: &e.reference,
+        account_id: st.account_id.to_string(),
+        opening_amount: st.opening_balance.as_ref().map(balance_amount).transpose()?,
+        opening_currency: st.opening_balance.as_ref().map(|b| b.currency.code()),
+        opening_date: st.opening_balance.as_ref().map(|b| b.date.format("%Y-%m-%d").to_string()),
+        closing_amount: closing.map(balance_amount).transpose()?,
+        closing_currency: closing.map(|b| b.currency.code()),
+        closing_date: closing.map(|b| b.date.format("%Y-%m-%d").to_string()),
+        statement_id: &st.statement_id,
+        counterparty_name: cp.and_then(|c| c.name.as_deref()),
+        counterparty_account: cp.and_then(|c| c.account.as_deref()),
+        counterparty_bic: cp.and_then(|c| c.bic.as_deref()),
+        counterparty_address: cp.and_then(|c| c.address.as_deref()),
+        bank_tx_iso: tx.and_then(|c| c.iso.as_ref()).map(ToString::to_string),
+        bank_tx_swift: tx.and_then(|c| c.swift.as_deref()),
+        bank_tx_proprietary: prtry.map(|p| p.code.as_str()),
+        bank_tx_issuer: prtry.and_then(|p| p.issuer.as_deref()),
+        balances: format_balances(&st.balances)?,
+        status: e.status.to_string(),
+        created: st.created.map(|c| c.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        electronic_seq: st.electronic_seq,
+        legal_seq: st.legal_seq,
+        page: st.page,
+        last_page: st.last_page,
+        period_from: st.period.map(|p| p.from.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        period_to: st.period.map(|p| p.to.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        account_currency: st.account_currency.map(|c| c.code()),
+        account_owner: st.account_owner.as_deref(),
+        servicer_bic: st.servicer_bic.as_deref(),
+    })
 }
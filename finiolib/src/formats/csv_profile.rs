@@ -0,0 +1,295 @@
+//! Профиль разбора банковской выгрузки CSV: разделитель, заголовок, форматы
+//! дат и чисел, знак суммы. Профиль описывается в TOML или JSON:
+//!
+//! ```toml
+//! name = "sberbank"
+//! delimiter = ";"
+//! skip_rows = 2               # шапка выгрузки до строки заголовка
+//! date_format = "%d.%m.%Y"
+//! decimal_separator = ","
+//! thousands_separator = " "
+//! sign = "split"              # signed | indicator | split
+//! currency = "RUB"            # если в выгрузке нет колонки валюты
+//! account_id = "40702810938000000001"
+//!
+//! [columns]
+//! booking_date = "Дата проводки"
+//! debit = "Сумма по дебету"
+//! credit = "Сумма по кредиту"
+//! description = "Назначение платежа"
+//! counterparty_name = 4       # номер колонки, с 0
+//! ```
+//!
+//! Колонка задаётся именем из заголовка или номером. Запись по профилю
+//! приводится к колонкам `Csv` (ISO-даты, суммы с точкой, `D`/`C`), дальше
+//! разбор общий. Строки без даты проводки (итоги, подвал) пропускаются.
+
+use crate::{
+    currency::Currency,
+    error::{Diagnostic, ErrorCode, FinioError, Result},
+    model::DebitCredit,
+};
+use chrono::NaiveDate;
+use csv::StringRecord;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Описание выгрузки одного банка.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CsvProfile {
+    pub name: Option<String>,
+    pub delimiter: char,
+    pub quote: char,
+    /// записи перед заголовком (пустые строки не считаются)
+    pub skip_rows: usize,
+    /// без заголовка колонки задаются только номерами
+    pub has_header: bool,
+    /// формат chrono, напр. `%d.%m.%Y`
+    pub date_format: String,
+    pub decimal_separator: char,
+    /// пробелы (и неразрывные) в суммах отбрасываются всегда
+    pub thousands_separator: Option<char>,
+    pub sign: AmountSign,
+    /// значения колонки `dc` (без учёта регистра)
+    pub debit_values: Vec<String>,
+    pub credit_values: Vec<String>,
+    /// валюта, если колонки `currency` нет
+    pub currency: Option<Currency>,
+    /// счёт выписки, если колонки `account_id` нет
+    pub account_id: Option<String>,
+    pub columns: ProfileColumns<ColumnRef>,
+}
+
+/// Как в выгрузке записано направление суммы.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AmountSign {
+    /// одна колонка `amount`, минус — дебет
+    #[default]
+    Signed,
+    /// `amount` без знака, направление — в колонке `dc`
+    Indicator,
+    /// суммы списания и зачисления в колонках `debit` и `credit`
+    Split,
+}
+
+/// Колонка по имени из заголовка или по номеру (с 0).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ColumnRef {
+    Index(usize),
+    Name(String),
+}
+
+/// Колонки выгрузки по полям `Csv`; `booking_date` обязательна.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileColumns<C> {
+    pub booking_date: Option<C>,
+    pub value_date: Option<C>,
+    pub amount: Option<C>,
+    pub debit: Option<C>,
+    pub credit: Option<C>,
+    pub dc: Option<C>,
+    pub currency: Option<C>,
+    pub description: Option<C>,
+    pub reference: Option<C>,
+    pub account_id: Option<C>,
+    pub statement_id: Option<C>,
+    pub counterparty_name: Option<C>,
+    pub counterparty_account: Option<C>,
+    pub counterparty_bic: Option<C>,
+}
+
+/// Колонка, найденная в записи: номер и имя для диагностик.
+#[derive(Debug, Clone)]
+pub(crate) struct Column {
+    pub index: usize,
+    pub name: String,
+}
+
+impl Default for CsvProfile {
+    fn default() -> Self {
+        CsvProfile {
+            name: None,
+            delimiter: ',',
+            quote: '"',
+            skip_rows: 0,
+            has_header: true,
+            date_format: "%Y-%m-%d".into(),
+            decimal_separator: '.',
+            thousands_separator: None,
+            sign: AmountSign::Signed,
+            debit_values: vec!["D".into(), "debit".into()],
+            credit_values: vec!["C".into(), "credit".into()],
+            currency: None,
+            account_id: None,
+            columns: ProfileColumns::default(),
+        }
+    }
+}
+
+impl<C> Default for ProfileColumns<C> {
+    fn default() -> Self {
+        ProfileColumns {
+            booking_date: None,
+            value_date: None,
+            amount: None,
+            debit: None,
+            credit: None,
+            dc: None,
+            currency: None,
+            description: None,
+            reference: None,
+            account_id: None,
+            statement_id: None,
+            counterparty_name: None,
+            counterparty_account: None,
+            counterparty_bic: None,
+        }
+    }
+}
+
+impl<C> ProfileColumns<C> {
+    /// Применяет `f(колонка, имя поля)` к каждой заданной колонке.
+    fn try_map<D>(&self, mut f: impl FnMut(&C, &str) -> Result<D>) -> Result<ProfileColumns<D>> {
+        let mut map = |c: &Option<C>, field: &str| c.as_ref().map(|c| f(c, field)).transpose();
+        Ok(ProfileColumns {
+            booking_date: map(&self.booking_date, "booking_date")?,
+            value_date: map(&self.value_date, "value_date")?,
+            amount: map(&self.amount, "amount")?,
+            debit: map(&self.debit, "debit")?,
+            credit: map(&self.credit, "credit")?,
+            dc: map(&self.dc, "dc")?,
+            currency: map(&self.currency, "currency")?,
+            description: map(&self.description, "description")?,
+            reference: map(&self.reference, "reference")?,
+            account_id: map(&self.account_id, "account_id")?,
+            statement_id: map(&self.statement_id, "statement_id")?,
+            counterparty_name: map(&self.counterparty_name, "counterparty_name")?,
+            counterparty_account: map(&self.counterparty_account, "counterparty_account")?,
+            counterparty_bic: map(&self.counterparty_bic, "counterparty_bic")?,
+        })
+    }
+}
+
+impl CsvProfile {
+    /// Профиль из файла: `.json` — JSON, иначе TOML.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::from_json(&text),
+            _ => Self::from_toml(&text),
+        }
+    }
+
+    pub fn from_toml(s: &str) -> Result<Self> {
+        let p: CsvProfile = toml::from_str(s).map_err(|e| FinioError::Parse(format!("CSV profile: {e}")))?;
+        p.check()?;
+        Ok(p)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        let p: CsvProfile = serde_json::from_str(s).map_err(|e| FinioError::Parse(format!("CSV profile: {e}")))?;
+        p.check()?;
+        Ok(p)
+    }
+
+    /// Колонки, без которых записи не разобрать, и однобайтовые разделители.
+    pub fn check(&self) -> Result<()> {
+        let invalid = |field: &str, msg: &str, value: String| -> FinioError {
+            Diagnostic::new(ErrorCode::InvalidValue, msg).field(field).value(value).into()
+        };
+        for (field, c) in [("delimiter", self.delimiter), ("quote", self.quote)] {
+            if !c.is_ascii() {
+                return Err(invalid(field, "expected a single ASCII character", c.to_string()));
+            }
+        }
+        let cols = &self.columns;
+        let required: &[(&str, bool)] = match self.sign {
+            AmountSign::Signed => &[("amount", cols.amount.is_some())],
+            AmountSign::Indicator => &[("amount", cols.amount.is_some()), ("dc", cols.dc.is_some())],
+            AmountSign::Split => &[("debit", cols.debit.is_some()), ("credit", cols.credit.is_some())],
+        };
+        let missing = [("booking_date", cols.booking_date.is_some())]
+            .iter()
+            .chain(required)
+            .find(|(_, set)| !set)
+            .map(|(field, _)| format!("columns.{field}"));
+        if let Some(field) = missing {
+            let msg = format!("column required for sign = {:?}", self.sign);
+            return Err(Diagnostic::new(ErrorCode::MissingField, msg).field(field).into());
+        }
+        if cols.currency.is_none() && self.currency.is_none() {
+            let msg = "set `currency` or `columns.currency`";
+            return Err(Diagnostic::new(ErrorCode::MissingField, msg).field("currency").into());
+        }
+        if !self.has_header {
+            cols.try_map(|c, field| match c {
+                ColumnRef::Index(_) => Ok(()),
+                ColumnRef::Name(name) => {
+                    Err(invalid(&format!("columns.{field}"), "column names need has_header = true", name.clone()))
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Номера колонок по заголовку выгрузки.
+    pub(crate) fn resolve(&self, header: Option<&StringRecord>) -> Result<ProfileColumns<Column>> {
+        self.columns.try_map(|c, _| match c {
+            ColumnRef::Index(index) => {
+                let name = header.and_then(|h| h.get(*index)).map_or_else(|| format!("#{index}"), str::to_string);
+                Ok(Column { index: *index, name })
+            }
+            ColumnRef::Name(name) => header
+                .and_then(|h| h.iter().position(|h| h.trim() == name))
+                .map(|index| Column { index, name: name.clone() })
+                .ok_or_else(|| Diagnostic::new(ErrorCode::MissingField, "column not found in header").field(name).into()),
+        })
+    }
+
+    pub(crate) fn date(&self, s: &str, column: &str) -> Result<NaiveDate> {
+        NaiveDate::parse_from_str(s, &self.date_format).map_err(|e| {
+            Diagnostic::new(ErrorCode::InvalidDate, format!("expected {} ({e})", self.date_format))
+                .field(column)
+                .value(s)
+                .into()
+        })
+    }
+
+    /// Сумма со знаком: без пробелов и разделителя тысяч, `−` — минус.
+    pub(crate) fn amount(&self, s: &str, column: &str) -> Result<Decimal> {
+        let plain: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && Some(*c) != self.thousands_separator)
+            .map(|c| match c {
+                c if c == self.decimal_separator => '.',
+                '−' => '-',
+                c => c,
+            })
+            .collect();
+        plain.trim_start_matches('+').parse().map_err(|e| {
+            Diagnostic::new(ErrorCode::InvalidAmount, format!("invalid amount ({e})"))
+                .field(column)
+                .value(s)
+                .into()
+        })
+    }
+
+    pub(crate) fn dc(&self, s: &str, column: &str) -> Result<DebitCredit> {
+        let s_lower = s.trim().to_lowercase();
+        let is = |values: &[String]| values.iter().any(|v| v.trim().to_lowercase() == s_lower);
+        if is(&self.debit_values) {
+            Ok(DebitCredit::Debit)
+        } else if is(&self.credit_values) {
+            Ok(DebitCredit::Credit)
+        } else {
+            let msg = format!("expected one of {:?} or {:?}", self.debit_values, self.credit_values);
+            Err(Diagnostic::new(ErrorCode::InvalidDebitCredit, msg).field(column).value(s).into())
+        }
+    }
+}
@@ -0,0 +1,361 @@
+//! Поле `:86:` MT940/MT942 — информация владельцу счёта.
+//!
+//! Банки раскладывают его по-разному: немецкие — подполями `?00`…`?63`
+//! с кодом операции GVC впереди, нидерландские и польские — тегами
+//! `/NAME/`, `/REMI/`, `/IBAN/`…, остальные пишут свободный текст.
+//! Раскладка выбирается профилем банка (`Layout86`) или своей реализацией
+//! `Field86`, которую принимают `Mt940Reader::layout86`/`Mt940Writer::layout86`.
+
+use crate::account::Bic;
+use crate::model::{Counterparty, CreditorReference, Entry, ProprietaryTxCode};
+use crate::remittance::find_rf;
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+/// Разбор и запись `:86:` одной раскладки.
+pub trait Field86: Send + Sync {
+    /// Заполняет поля проводки по строкам `:86:` (без тега, включая продолжения).
+    fn parse(&self, lines: &[&str], e: &mut Entry);
+
+    /// Строки `:86:` для проводки; пустой список — поле не пишется.
+    fn format(&self, e: &Entry) -> Vec<String>;
+}
+
+/// Длина подполя `?NN` и строки `:86:` по SWIFT.
+const SUBFIELD_LEN: usize = 27;
+const LINE_LEN: usize = 65;
+
+/// Свободный текст: строки склеиваются через пробел в `description`;
+/// при записи текст переносится по словам в строки до 65 символов.
+pub struct FreeText;
+
+impl Field86 for FreeText {
+    fn parse(&self, lines: &[&str], e: &mut Entry) {
+        e.description = lines.join(" ");
+    }
+
+    fn format(&self, e: &Entry) -> Vec<String> {
+        wrap(&e.description, LINE_LEN)
+    }
+}
+
+/// Немецкая раскладка (DK): `GVC?00текст?10…?20…?29 назначение?30 BLZ/BIC
+/// ?31 счёт?32…?33 имя?34 код возврата?60…?63 назначение`.
+/// Подполя `?10`, `?34` и прочие служебные не сохраняются; RF-референс
+/// из назначения (`SVWZ+RF18…`) копируется в `remittance`. При записи имя
+/// длиннее двух подполей обрезается, а назначение длиннее 14 подполей
+/// пишется свободным текстом.
+pub struct Gvc;
+
+impl Field86 for Gvc {
+    fn parse(&self, lines: &[&str], e: &mut Entry) {
+        // подполе может переноситься на следующую строку — склеиваем без пробела
+        let text = lines.concat();
+        let Some((gvc, fields)) = subfields(&text) else {
+            return FreeText.parse(lines, e);
+        };
+        let mut description = String::new();
+        let mut cp = Counterparty::default();
+        for (code, v) in fields {
+            match code {
+                0 => e.posting_text = value(v),
+                20..=29 | 60..=63 => description.push_str(v),
+                30 => cp.bic = value(v).map(Bic::from),
+                31 => cp.account = value(v),
+                32 | 33 => {
+                    if let Some(v) = value(v) {
+                        cp.name.get_or_insert_with(String::new).push_str(&v);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(gvc) = gvc {
+            e.bank_tx_code.get_or_insert_default().proprietary = Some(ProprietaryTxCode {
+                code: gvc.to_string(),
+                issuer: Some(GVC_ISSUER.into()),
+            });
+        }
+        e.description = description.trim().to_string();
+        e.counterparty = (!cp.is_empty()).then_some(cp);
+        e.remittance.creditor_reference = find_rf(&e.description).map(CreditorReference::rf);
+    }
+
+    fn format(&self, e: &Entry) -> Vec<String> {
+        // по подполю на строку: так строки :86: не выходят за 65 символов
+        let description = chunks(&e.description, SUBFIELD_LEN);
+        let codes: Vec<u8> = (20..=29).chain(60..=63).collect();
+        if description.len() > codes.len() {
+            // назначение не влезает в 14 подполей — свободный текст без потерь
+            return FreeText.format(e);
+        }
+        let mut out = Vec::new();
+        if let Some(text) = &e.posting_text {
+            out.push(format!("?00{text}"));
+        }
+        for (code, chunk) in codes.iter().zip(&description) {
+            out.push(format!("?{code}{chunk}"));
+        }
+        if let Some(cp) = &e.counterparty {
+            if let Some(bic) = &cp.bic {
+                out.push(format!("?30{bic}"));
+            }
+            if let Some(account) = &cp.account {
+                out.push(format!("?31{account}"));
+            }
+            // имя — в ?32 и ?33 по 27 символов, дальше обрезается
+            if let Some(name) = cp.name.as_ref().filter(|n| !n.is_empty()) {
+                for (code, part) in [32, 33].iter().zip(chunks(name, SUBFIELD_LEN)) {
+                    out.push(format!("?{code}{part}"));
+                }
+            }
+        }
+        if let Some(gvc) = gvc(e) {
+            match out.first_mut() {
+                Some(first) => first.insert_str(0, gvc),
+                None => out.push(gvc.to_string()),
+            }
+        }
+        out
+    }
+}
+
+/// Эмитент кодов GVC — Deutsche Kreditwirtschaft.
+const GVC_ISSUER: &str = "DK";
+
+/// Проприетарный код проводки, если это GVC (три цифры).
+fn gvc(e: &Entry) -> Option<&str> {
+    let p = e.bank_tx_code.as_ref()?.proprietary.as_ref()?;
+    let is_dk = p.issuer.as_deref().is_none_or(|i| i == GVC_ISSUER);
+    (is_dk && p.code.len() == 3 && p.code.bytes().all(|b| b.is_ascii_digit())).then_some(&p.code)
+}
+
+/// Подполе `?NN`: номер и значение.
+type Subfield<'a> = (u8, &'a str);
+
+/// Код GVC и подполя `?NN`; `None` — текст не в немецкой раскладке.
+/// Одни три цифры — код GVC без подполей.
+fn subfields(s: &str) -> Option<(Option<&str>, Vec<Subfield<'_>>)> {
+    let (gvc, body) = match s.as_bytes() {
+        [a, b, c] if [a, b, c].iter().all(|d| d.is_ascii_digit()) => (Some(s), ""),
+        [a, b, c, b'?', ..] if [a, b, c].iter().all(|d| d.is_ascii_digit()) => {
+            (Some(&s[..3]), &s[3..])
+        }
+        [b'?', ..] => (None, s),
+        _ => return None,
+    };
+    let fields = body
+        .split('?')
+        .skip(1)
+        .map(|f| {
+            let code = f.get(..2)?.parse().ok()?;
+            Some((code, &f[2..]))
+        })
+        .collect::<Option<_>>()?;
+    Some((gvc, fields))
+}
+
+/// Теги `/TAG/значение` (NL, PL): `/TRTP/` текст операции, `/NAME/`,
+/// `/ADDR/`, `/IBAN/`, `/BIC/` контрагента, `/REMI/` назначение,
+/// `/CDTRREFTP//CD/SCOR/ISSR/ISO/CDTRREF/` или `/RF/` референс получателя.
+/// Прочие теги (`/EREF/`, `/ORDP/`, `/ULTD/`…) распознаются, но не сохраняются.
+pub struct Tagged;
+
+static RE_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"/(EREF|KREF|PREF|MARF|CSID|TRTP|ORDP|BENM|ULTC|ULTD|NAME|ADDR|IBAN|BIC|ID|REMI|RTRN|PURP|CDTRREFTP|CDTRREF|CD|ISSR|RF|SVCL|COAM|OAMT)/",
+    )
+    .expect("valid :86: tag regex")
+});
+
+impl Field86 for Tagged {
+    fn parse(&self, lines: &[&str], e: &mut Entry) {
+        // строки режутся по 65 символов посреди значения — склеиваем без пробела
+        let text = lines.concat();
+        let tags: Vec<_> = RE_TAG.captures_iter(&text).collect();
+        if tags.is_empty() {
+            return FreeText.parse(lines, e);
+        }
+        let mut cp = Counterparty::default();
+        let mut description = None;
+        let (mut kind, mut issuer, mut cref) = (None, None, None);
+        for (i, caps) in tags.iter().enumerate() {
+            let whole = caps.get(0).expect("match");
+            let end = tags.get(i + 1).map_or(text.len(), |n| n.get(0).expect("match").start());
+            let v = text[whole.end()..end].trim_end_matches('/');
+            // первое вхождение побеждает: NAME после ULTD — конечный участник
+            match &caps[1] {
+                "TRTP" => e.posting_text = e.posting_text.take().or_else(|| value(v)),
+                "NAME" => cp.name = cp.name.take().or_else(|| value(v)),
+                "ADDR" => cp.address = cp.address.take().or_else(|| value(v)),
+                "IBAN" => cp.account = cp.account.take().or_else(|| value(v)),
+                "BIC" => cp.bic = cp.bic.take().or_else(|| value(v).map(Bic::from)),
+                "REMI" => {
+                    let v = v.strip_prefix("USTD//").unwrap_or(v);
+                    description = description.or_else(|| value(v));
+                }
+                "CD" => kind = kind.or_else(|| value(v)),
+                "ISSR" => issuer = issuer.or_else(|| value(v)),
+                "CDTRREF" | "RF" => cref = cref.or_else(|| value(v)),
+                _ => {}
+            }
+        }
+        e.description = description.unwrap_or_default();
+        e.counterparty = (!cp.is_empty()).then_some(cp);
+        e.remittance.creditor_reference = cref.map(|reference| match kind {
+            None if reference.starts_with("RF") => CreditorReference::rf(&reference),
+            _ => CreditorReference { kind, issuer, reference },
+        });
+    }
+
+    fn format(&self, e: &Entry) -> Vec<String> {
+        let mut s = String::new();
+        let tag = |s: &mut String, t: &str, v: Option<&str>| {
+            if let Some(v) = v.filter(|v| !v.is_empty()) {
+                s.push_str(&format!("/{t}/{v}"));
+            }
+        };
+        let cp = e.counterparty.as_ref();
+        tag(&mut s, "TRTP", e.posting_text.as_deref());
+        tag(&mut s, "NAME", cp.and_then(|c| c.name.as_deref()));
+        tag(&mut s, "ADDR", cp.and_then(|c| c.address.as_deref()));
+        tag(&mut s, "IBAN", cp.and_then(|c| c.account.as_deref()));
+        tag(&mut s, "BIC", cp.and_then(|c| c.bic.as_deref()));
+        tag(&mut s, "REMI", Some(&e.description));
+        if let Some(r) = &e.remittance.creditor_reference {
+            // тип — вложенными тегами: /CDTRREFTP//CD/SCOR/ISSR/ISO
+            if r.kind.is_some() || r.issuer.is_some() {
+                s.push_str("/CDTRREFTP/");
+                tag(&mut s, "CD", r.kind.as_deref());
+                tag(&mut s, "ISSR", r.issuer.as_deref());
+            }
+            tag(&mut s, "CDTRREF", Some(&r.reference));
+        }
+        chunks(&s, LINE_LEN)
+    }
+}
+
+/// Раскладка по содержимому: подполя `?NN` — немецкая, теги `/…/` —
+/// тегированная, иначе свободный текст. Пишет немецкую только при коде GVC,
+/// тегированную — если есть контрагент, текст операции или референс
+/// получателя, иначе — свободный текст.
+pub struct Auto;
+
+impl Field86 for Auto {
+    fn parse(&self, lines: &[&str], e: &mut Entry) {
+        let first = lines.first().copied().unwrap_or("");
+        if subfields(first).is_some() {
+            Gvc.parse(lines, e)
+        } else if RE_TAG.find(first).is_some_and(|m| m.start() == 0) {
+            Tagged.parse(lines, e)
+        } else {
+            FreeText.parse(lines, e)
+        }
+    }
+
+    fn format(&self, e: &Entry) -> Vec<String> {
+        let tagged = e.counterparty.as_ref().is_some_and(|c| !c.is_empty())
+            || e.posting_text.is_some()
+            || e.remittance.creditor_reference.is_some();
+        if gvc(e).is_some() {
+            Gvc.format(e)
+        } else if tagged {
+            Tagged.format(e)
+        } else {
+            FreeText.format(e)
+        }
+    }
+}
+
+/// Встроенные раскладки — профили банков для `finio --mt86-layout`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Layout86 {
+    #[default]
+    Auto,
+    FreeText,
+    Gvc,
+    Tagged,
+}
+
+impl Field86 for Layout86 {
+    fn parse(&self, lines: &[&str], e: &mut Entry) {
+        match self {
+            Layout86::Auto => Auto.parse(lines, e),
+            Layout86::FreeText => FreeText.parse(lines, e),
+            Layout86::Gvc => Gvc.parse(lines, e),
+            Layout86::Tagged => Tagged.parse(lines, e),
+        }
+    }
+
+    fn format(&self, e: &Entry) -> Vec<String> {
+        match self {
+            Layout86::Auto => Auto.format(e),
+            Layout86::FreeText => FreeText.format(e),
+            Layout86::Gvc => Gvc.format(e),
+            Layout86::Tagged => Tagged.format(e),
+        }
+    }
+}
+
+impl fmt::Display for Layout86 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Layout86::Auto => "auto",
+            Layout86::FreeText => "text",
+            Layout86::Gvc => "gvc",
+            Layout86::Tagged => "tags",
+        })
+    }
+}
+
+/// "auto", "text", "gvc" (или "de"), "tags" (или "nl", "pl").
+impl FromStr for Layout86 {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(Layout86::Auto),
+            "text" | "free" => Ok(Layout86::FreeText),
+            "gvc" | "de" => Ok(Layout86::Gvc),
+            "tags" | "nl" | "pl" => Ok(Layout86::Tagged),
+            _ => Err(format!("unknown :86: layout {s:?} (auto, text, gvc, tags)")),
+        }
+    }
+}
+
+fn value(v: &str) -> Option<String> {
+    Some(v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Переносит текст по пробелам в строки до `n` символов; слово длиннее
+/// строки режется. Пробел на месте переноса уходит, `FreeText::parse`
+/// вернёт его при склейке.
+fn wrap(s: &str, n: usize) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in s.split(' ') {
+        let len = line.chars().count();
+        if len > 0 && len + 1 + word.chars().count() > n {
+            out.push(std::mem::take(&mut line));
+        } else if len > 0 {
+            line.push(' ');
+        }
+        line.push_str(word);
+        while line.chars().count() > n {
+            let rest = line.split_off(line.char_indices().nth(n).map_or(line.len(), |(i, _)| i));
+            out.push(std::mem::replace(&mut line, rest));
+        }
+    }
+    if !line.is_empty() {
+        out.push(line);
+    }
+    out
+}
+
+/// Режет строку на куски по `n` символов.
+fn chunks(s: &str, n: usize) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    chars.chunks(n).map(|c| c.iter().collect()).collect()
+}
@@ -1,133 +1,383 @@
 use crate::{
-    error::{FinioError, Result},
-    model::{Balance, DebitCredit, Entry, Statement},
+    account::AccountId,
+    currency::{parse_currency, Currency},
+    error::{Diagnostic, ErrorCode, FinioError, Location, Result},
+    formats::field86::{Auto, Field86},
+    model::{
+        Balance, BalanceKind, BankTxCode, DebitCredit, Entry, EntryStatus, Remittance, Statement,
+        TypedBalance,
+    },
+    stream::{self, StatementEvent, StatementTrailer},
+    traits::{ParseMode, StatementWriter, StreamRead, StreamWrite},
+    txcode::SWIFT_MISC,
 };
 use chrono::{Datelike, NaiveDate};
 use regex::Regex;
 use rust_decimal::Decimal;
-use std::io::BufRead;
+use std::collections::VecDeque;
+use std::io::{BufRead, Lines, Write};
+use std::sync::LazyLock;
 
 /// Минимальный набор тегов: :20:, :25:, :60F:, :61:, :86:, :62F:
 pub struct Mt940;
 
+// :60F:/:62F:/:64:… D|C YYMMDD CCY amount (сумма проверяется отдельно)
+static RE_BALANCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<dc>[CD])(?P<date>\d{6})(?P<ccy>[A-Za-z]{3})(?P<amt>.+)$").expect("valid balance regex")
+});
+
+// :61: YYMMDD [MMDD] C|D|RC|RD [funds|CCY] amount[,~~] [Nxxx|Fxxx|Sxxx] REF [//BANKREF]
+// и необязательная вторая строка — дополнительные сведения.
+// Валюта из трёх букв вместо кода средств — расширение некоторых банков.
+static RE_61: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^:61:(?P<val>\d{6})(?P<book>\d{4})?(?P<dc>R?[CD])(?:(?P<ccy>[A-Z]{3})|(?P<funds>[A-Z]))?(?P<amt>\d+,\d*)(?P<tx>[NF][A-Z0-9]{3}|S\d{3})?(?P<ref>.*?)(?://(?P<bref>.*))?$",
+    )
+    .expect("valid :61: regex")
+});
+
 impl crate::traits::ReadFormat for Mt940 {
-    fn read<R: BufRead>(r: R) -> Result<Statement> {
-        let mut account_id = String::new();
-        let mut statement_id: Option<String> = None;
-        let mut opening: Option<Balance> = None;
-        let mut closing: Option<Balance> = None;
-        let mut entries: Vec<Entry> = Vec::new();
-
-        // :61: YYMMDD [MMDD] [C|D] [CCY]? amount[,~~] [TX]? [REF]...
-        let re_61 = Regex::new(
-            r"^:61:(?P<val>\d{6})(?P<book>\d{4})?(?P<dc>[CD])(?P<ccy>[A-Z]{3})?(?P<amt>\d+,\d{0,2})(?P<tx>[A-Z]{3,4})?(?P<ref>[^\r\n]*)?.*$",
-        )
-            .map_err(|e| FinioError::Parse(e.to_string()))?;
-
-        let mut last_entry_has_86 = false;
-
-        for line in r.lines() {
-            let line = line?;
-            if line.starts_with(":20:") {
-                statement_id = Some(line[4..].trim().to_string());
-            } else if line.starts_with(":25:") {
-                account_id = line[4..].trim().to_string();
-            } else if line.starts_with(":60F:") {
-                opening = parse_balance(&line[5..])?;
-            } else if line.starts_with(":62F:") {
-                closing = parse_balance(&line[5..])?;
-            } else if line.starts_with(":61:") {
-                let caps = re_61
-                    .captures(&line)
-                    .ok_or_else(|| FinioError::Parse("bad :61:".into()))?;
-
-                // маленький хелпер для обязательных групп
-                let req = |name: &str| {
-                    caps.name(name)
-                        .map(|m| m.as_str())
-                        .ok_or_else(|| FinioError::Parse(format!(":61: missing {name}")))
-                };
-
-                let val = req("val")?;
-                let book_opt = caps.name("book").map(|m| m.as_str());
-
-                let dc = match req("dc")? {
-                    "C" => DebitCredit::Credit,
-                    "D" => DebitCredit::Debit,
-                    other => return Err(FinioError::Parse(format!(":61: dc {other}"))),
-                };
-
-                // сумма (в :61: — без валюты)
-                let amt = req("amt")?.replace(',', ".");
-                let amount: Decimal = amt
-                    .parse()
-                    .map_err(|e| FinioError::Parse(format!("amount: {e}")))?;
-
-                // даты
-                let value_date = parse_mt_date(val)?;
-                let booking_date = match book_opt {
-                    Some(b) => parse_mt_book_date(value_date.year(), b)?,
-                    None => value_date,
-                };
-
-                // валюта: если указана прямо в :61:, берём её; иначе — из opening_balance; иначе XXX
-                let currency = if let Some(m) = caps.name("ccy") {
-                    m.as_str().to_string()
-                } else {
-                    opening
-                        .as_ref()
-                        .map(|b| b.currency.clone())
-                        .unwrap_or_else(|| "XXX".into())
-                };
-
-                // reference: хвост после кода операции, игнорируем пустой и NONREF
-                let reference = caps
-                    .name("ref")
-                    .map(|m| m.as_str().trim().to_string())
-                    .filter(|s| !s.is_empty() && s != "NONREF");
-
-                entries.push(Entry {
-                    booking_date,
-                    value_date: Some(value_date),
-                    amount,
-                    currency,
-                    dc,
-                    description: String::new(),
-                    reference,
-                });
-                last_entry_has_86 = false;
-            } else if line.starts_with(":86:") {
-                if let Some(last) = entries.last_mut() {
-                    let text = line[4..].to_string();
-                    if last.description.is_empty() {
-                        last.description = text;
-                    } else {
-                        last.description.push(' ');
-                        last.description.push_str(&text);
+    fn read_statements_with<R: BufRead>(
+        r: R,
+        mode: ParseMode,
+    ) -> Result<(Vec<Statement>, Vec<Diagnostic>)> {
+        stream::collect_with(Self::reader_with(r, mode), mode)
+    }
+}
+
+impl StreamRead for Mt940 {
+    type Reader<R: BufRead> = Mt940Reader<R>;
+
+    fn reader_with<R: BufRead>(r: R, mode: ParseMode) -> Mt940Reader<R> {
+        Mt940Reader {
+            lines: r.lines(),
+            mode,
+            line_no: 0,
+            open: false,
+            header: None,
+            currency: None,
+            pending: None,
+            layout: Box::new(Auto),
+            closing: None,
+            late_balances: Vec::new(),
+            last_entry_has_86: false,
+            after_61: false,
+            out: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+/// Построчный pull-парсер: в памяти только текущая проводка.
+/// Проводка отдаётся, когда встречен следующий тег после её `:86:`.
+pub struct Mt940Reader<R> {
+    lines: Lines<R>,
+    /// в мягком режиме ошибка строки не прерывает разбор: испорченная
+    /// :61: пропускается вместе со своей :86:, испорченный баланс опускается
+    mode: ParseMode,
+    /// номер последней прочитанной строки (с 1)
+    line_no: u64,
+    /// блок :20:…:62F: открыт
+    open: bool,
+    /// реквизиты открытого блока, ещё не отданные наружу
+    header: Option<Statement>,
+    /// валюта из :60F: для :61: без собственной валюты
+    currency: Option<Currency>,
+    pending: Option<Entry>,
+    /// раскладка `:86:`
+    layout: Box<dyn Field86>,
+    closing: Option<Balance>,
+    /// :62M:, :64:, :65: — уходят в trailer
+    late_balances: Vec<TypedBalance>,
+    last_entry_has_86: bool,
+    /// предыдущая строка — `:61:`; следующая без тега — её дополнительные сведения
+    after_61: bool,
+    out: VecDeque<Result<StatementEvent>>,
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for Mt940Reader<R> {
+    type Item = Result<StatementEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(ev) = self.out.pop_front() {
+                return Some(ev);
+            }
+            if self.done {
+                return None;
+            }
+            match self.lines.next() {
+                Some(Ok(line)) => {
+                    self.line_no += 1;
+                    if let Err(e) = self.line(&line) {
+                        let at = Location::Line { line: self.line_no, column: None };
+                        let e = e.located(at, &line);
+                        self.done = self.mode == ParseMode::Strict || e.diagnostic().is_none();
+                        self.out.push_back(Err(e));
                     }
-                    last_entry_has_86 = true;
                 }
-            } else if last_entry_has_86 && !line.starts_with(':') {
-                // продолжение описания без нового тега
-                if let Some(last) = entries.last_mut() {
-                    last.description.push(' ');
-                    last.description.push_str(line.trim());
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e.into()));
+                }
+                None => {
+                    self.done = true;
+                    self.close();
                 }
             }
         }
+    }
+}
 
-        Ok(Statement {
-            statement_id,
-            account_id,
-            opening_balance: opening,
-            closing_balance: closing,
-            entries,
-        })
+impl<R> Mt940Reader<R> {
+    /// Раскладка `:86:` банка; по умолчанию — `Auto`.
+    pub fn layout86(mut self, layout: impl Field86 + 'static) -> Self {
+        self.layout = Box::new(layout);
+        self
     }
+
+    fn line(&mut self, line: &str) -> Result<()> {
+        let after_61 = std::mem::take(&mut self.after_61);
+        if let Some(rest) = line.strip_prefix(":20:") {
+            // новый :20: открывает следующую выписку
+            self.close();
+            self.open = true;
+            self.header = Some(Statement {
+                statement_id: Some(rest.trim().to_string()),
+                ..Statement::default()
+            });
+        } else if let Some(rest) = line.strip_prefix(":25:") {
+            if let Some(h) = self.header_mut() {
+                h.account_id = AccountId::parse(rest);
+            }
+        } else if let Some(rest) = line.strip_prefix(":28C:") {
+            if let Some(h) = self.header_mut() {
+                parse_28c(rest, h)?;
+            }
+        } else if let Some(rest) = line.strip_prefix(":60F:") {
+            let opening = parse_balance(rest, ":60F:")?;
+            self.currency = Some(opening.currency);
+            if let Some(h) = self.header_mut() {
+                h.opening_balance = Some(opening);
+            }
+        } else if let Some(rest) = line.strip_prefix(":60M:") {
+            // продолжение выписки: входящий остаток — промежуточный
+            let b = parse_balance(rest, ":60M:")?;
+            self.currency = Some(b.currency);
+            let b = TypedBalance::new(BalanceKind::InterimOpening, &b);
+            match self.header_mut() {
+                Some(h) => h.balances.push(b),
+                None => self.late_balances.push(b),
+            }
+        } else if let Some((tag, kind, rest)) = late_balance_tag(line) {
+            self.emit_header();
+            self.flush_entry();
+            let b = parse_balance(rest, tag)?;
+            self.late_balances.push(TypedBalance::new(kind, &b));
+            self.last_entry_has_86 = false;
+        } else if let Some(rest) = line.strip_prefix(":62F:") {
+            self.emit_header();
+            self.flush_entry();
+            self.closing = Some(parse_balance(rest, ":62F:")?);
+            self.last_entry_has_86 = false;
+        } else if line.starts_with(":61:") {
+            self.emit_header();
+            self.flush_entry();
+            // валюта: если указана прямо в :61:, берём её; иначе — из opening_balance; иначе XXX
+            let currency = self.currency.unwrap_or(Currency::XXX);
+            self.pending = Some(parse_61(line, currency)?);
+            self.last_entry_has_86 = false;
+            self.after_61 = true;
+        } else if let Some(text) = line.strip_prefix(":86:") {
+            if let Some(e) = self.pending.as_mut() {
+                append_86(e, text);
+                self.last_entry_has_86 = true;
+            }
+        } else if is_message_delimiter(line) {
+            // "-" / "-}" / "{1:…" — границы SWIFT-сообщений, не часть описания
+            self.last_entry_has_86 = false;
+        } else if line.starts_with(':') {
+            // прочие теги не поддерживаются, но обрывают продолжение :86:
+            self.last_entry_has_86 = false;
+        } else if after_61 {
+            // вторая строка :61: — дополнительные сведения
+            if let Some(e) = self.pending.as_mut() {
+                append_supplementary(e, line);
+            }
+        } else if self.last_entry_has_86 {
+            // продолжение описания без нового тега
+            if let Some(e) = self.pending.as_mut() {
+                append_86(e, line.trim());
+            }
+        }
+        Ok(())
+    }
+
+    /// Реквизиты открытого блока (открывает его, если :20: не было).
+    /// `None` — Header уже отдан, поздние реквизиты некуда записать.
+    fn header_mut(&mut self) -> Option<&mut Statement> {
+        if !self.open {
+            self.open = true;
+            self.header = Some(Statement::default());
+        }
+        self.header.as_mut()
+    }
+
+    fn emit_header(&mut self) {
+        self.header_mut();
+        if let Some(h) = self.header.take() {
+            self.out.push_back(Ok(StatementEvent::Header(Box::new(h))));
+        }
+    }
+
+    fn flush_entry(&mut self) {
+        if let Some(mut e) = self.pending.take() {
+            finish_86(&mut e, self.layout.as_ref());
+            self.out.push_back(Ok(StatementEvent::Entry(Box::new(e))));
+        }
+    }
+
+    fn close(&mut self) {
+        if !self.open {
+            return;
+        }
+        self.emit_header();
+        self.flush_entry();
+        let closing_balance = self.closing.take();
+        let balances = std::mem::take(&mut self.late_balances);
+        self.out.push_back(Ok(StatementEvent::Trailer(StatementTrailer { closing_balance, balances })));
+        self.open = false;
+        self.currency = None;
+        self.last_entry_has_86 = false;
+    }
+}
+
+/// Остатки после проводок: тег, вид и значение.
+fn late_balance_tag(line: &str) -> Option<(&'static str, BalanceKind, &str)> {
+    [
+        (":62M:", BalanceKind::InterimClosing),
+        (":64:", BalanceKind::ClosingAvailable),
+        (":65:", BalanceKind::ForwardAvailable),
+    ]
+    .into_iter()
+    .find_map(|(tag, kind)| line.strip_prefix(tag).map(|rest| (tag, kind, rest)))
+}
+
+/// Разбор строки `:61:`; `currency` — валюта выписки для строк без своей валюты.
+pub(crate) fn parse_61(line: &str, currency: Currency) -> Result<Entry> {
+    let caps = RE_61.captures(line).ok_or_else(|| {
+        Diagnostic::new(ErrorCode::Syntax, "malformed statement line")
+            .field(":61:")
+            .value(line.strip_prefix(":61:").unwrap_or(line))
+    })?;
+
+    // маленький хелпер для обязательных групп
+    let req = |name: &str| {
+        caps.name(name).map(|m| m.as_str()).ok_or_else(|| {
+            let d = Diagnostic::new(ErrorCode::MissingField, "missing subfield");
+            FinioError::from(d.field(format!(":61: {name}")))
+        })
+    };
+
+    let val = req("val")?;
+    let book_opt = caps.name("book").map(|m| m.as_str());
+
+    // RC — сторно кредита, это дебетовая проводка; RD — наоборот
+    let (dc, reversal) = match req("dc")? {
+        "C" => (DebitCredit::Credit, false),
+        "D" => (DebitCredit::Debit, false),
+        "RC" => (DebitCredit::Debit, true),
+        "RD" => (DebitCredit::Credit, true),
+        other => {
+            return Err(Diagnostic::new(ErrorCode::InvalidDebitCredit, "expected C, D, RC or RD")
+                .field(":61: debit/credit mark")
+                .value(other)
+                .into())
+        }
+    };
+
+    // сумма (в :61: — без валюты)
+    let amount = parse_mt_amount(req("amt")?, ":61: amount")?;
+
+    // даты
+    let value_date = parse_mt_date(val, ":61: value date")?;
+    let booking_date = match book_opt {
+        Some(b) => parse_mt_book_date(value_date, b, ":61: entry date")?,
+        None => value_date,
+    };
+
+    let currency = match caps.name("ccy") {
+        Some(m) => parse_currency(m.as_str(), ":61: currency")?,
+        None => currency,
+    };
+
+    // reference: хвост после кода операции, игнорируем пустой и NONREF
+    let reference = caps
+        .name("ref")
+        .map(|m| m.as_str().trim().to_string())
+        .filter(|s| !s.is_empty() && s != "NONREF");
+    let bank_reference = caps
+        .name("bref")
+        .map(|m| m.as_str().trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    Ok(Entry {
+        booking_date,
+        value_date: Some(value_date),
+        amount,
+        currency,
+        dc,
+        description: String::new(),
+        reference,
+        reversal,
+        status: EntryStatus::Booked,
+        funds_code: caps.name("funds").and_then(|m| m.as_str().chars().next()),
+        bank_reference,
+        supplementary: None,
+        counterparty: None,
+        posting_text: None,
+        bank_tx_code: caps.name("tx").map(|m| BankTxCode::from_swift(m.as_str())),
+        remittance: Remittance::default(),
+        details: Vec::new(),
+    })
 }
 
 impl crate::traits::WriteFormat for Mt940 {
-    fn write<W: std::io::Write>(mut w: W, st: &Statement) -> Result<()> {
+    fn write_statements<W: Write>(w: W, sts: &[Statement]) -> Result<()> {
+        // блоки идут подряд: каждый начинается с :20:, по которому их и делит reader
+        stream::write_all(&mut Self::writer(w), sts)
+    }
+}
+
+impl StreamWrite for Mt940 {
+    type Writer<W: Write> = Mt940Writer<W>;
+
+    fn writer<W: Write>(w: W) -> Mt940Writer<W> {
+        Mt940Writer { w, closing: None, late_balances: Vec::new(), layout: Box::new(Auto) }
+    }
+}
+
+/// Пишет блок :20:…:62F: по мере поступления проводок.
+pub struct Mt940Writer<W> {
+    w: W,
+    /// :62F: из заголовка, если trailer его не уточнит
+    closing: Option<Balance>,
+    /// :62M:/:64:/:65: из заголовка — пишутся после проводок
+    late_balances: Vec<TypedBalance>,
+    layout: Box<dyn Field86>,
+}
+
+impl<W> Mt940Writer<W> {
+    /// Раскладка `:86:` на выходе; по умолчанию — `Auto`.
+    pub fn layout86(mut self, layout: impl Field86 + 'static) -> Self {
+        self.layout = Box::new(layout);
+        self
+    }
+}
+
+impl<W: Write> StatementWriter for Mt940Writer<W> {
+    fn begin(&mut self, st: &Statement) -> Result<()> {
         use std::fmt::Write as FmtWrite;
         let mut s = String::new();
         if let Some(id) = &st.statement_id {
@@ -136,107 +386,236 @@ impl crate::traits::WriteFormat for Mt940 {
             let _ = writeln!(s, ":20:NOTPROVIDED");
         }
         let _ = writeln!(s, ":25:{}", st.account_id);
+        let _ = writeln!(s, ":28C:{}", statement_number(st));
 
         if let Some(b) = &st.opening_balance {
-            let _ = writeln!(
-                s,
-                ":60F:{}{}{}",
-                if b.amount.is_sign_negative() { "D" } else { "C" },
-                b.date.format("%y%m%d"),
-                format_amount(&b.amount, &b.currency) // тут с валютой
-            );
+            s.push_str(&balance_line(":60F:", b)?);
         }
-        for e in &st.entries {
-            let dc = match e.dc {
-                DebitCredit::Debit => "D",
-                DebitCredit::Credit => "C",
-            };
-            let val = e.value_date.unwrap_or(e.booking_date);
-
-            // reference (если нет — пишем NONREF)
-            let ref_str = e
-                .reference
-                .as_deref()
-                .filter(|v| !v.is_empty())
-                .unwrap_or("NONREF");
-
-            // :61: YYMMDD MMDD D/C amount NTRF[REF]   — amount БЕЗ валюты
-            let _ = writeln!(
-                s,
-                ":61:{}{}{}{}NTRF{}",
-                val.format("%y%m%d"),
-                e.booking_date.format("%m%d"),
-                dc,
-                format_amount_plain(&e.amount),
-                ref_str
-            );
-
-            if !e.description.is_empty() {
-                let _ = writeln!(s, ":86:{}", e.description);
-            }
+        let (interim, late): (Vec<_>, Vec<_>) =
+            st.balances.iter().cloned().partition(|b| b.kind == BalanceKind::InterimOpening);
+        for b in &interim {
+            s.push_str(&balance_line(":60M:", &b.balance())?);
         }
-        if let Some(b) = &st.closing_balance {
-            let _ = writeln!(
-                s,
-                ":62F:{}{}{}",
-                if b.amount.is_sign_negative() { "D" } else { "C" },
-                b.date.format("%y%m%d"),
-                format_amount(&b.amount, &b.currency) // тут с валютой
-            );
+        self.closing = st.closing_balance.clone();
+        self.late_balances = late;
+        self.w.write_all(s.as_bytes())?;
+        Ok(())
+    }
+
+    fn entry(&mut self, e: &Entry) -> Result<()> {
+        self.w.write_all(format_61_86(e, self.layout.as_ref())?.as_bytes())?;
+        Ok(())
+    }
+
+    fn end(&mut self, t: &StatementTrailer) -> Result<()> {
+        let closing = t.closing_balance.as_ref().or(self.closing.as_ref());
+        let mut late = std::mem::take(&mut self.late_balances);
+        late.extend(t.balances.iter().cloned());
+        // порядок SWIFT: :62M:/:62F:, затем :64:, затем :65:
+        let mut s = String::new();
+        let tagged = |kind: BalanceKind, tag: &str| {
+            late.iter()
+                .filter(|b| b.kind == kind)
+                .map(|b| balance_line(tag, &b.balance()))
+                .collect::<Result<String>>()
+        };
+        s.push_str(&tagged(BalanceKind::InterimClosing, ":62M:")?);
+        if let Some(b) = closing {
+            s.push_str(&balance_line(":62F:", b)?);
         }
-        w.write_all(s.as_bytes())?;
+        s.push_str(&tagged(BalanceKind::ClosingAvailable, ":64:")?);
+        s.push_str(&tagged(BalanceKind::ForwardAvailable, ":65:")?);
+        self.w.write_all(s.as_bytes())?;
+        self.closing = None;
         Ok(())
     }
-}
 
-/// Парс баланса формата D/C + YYMMDD + CCY + amount
-fn parse_balance(s: &str) -> Result<Option<Balance>> {
-    if s.len() < 7 {
-        return Ok(None);
+    fn finish(&mut self) -> Result<()> {
+        self.w.flush()?;
+        Ok(())
     }
-    let dc = &s[0..1];
-    let date = &s[1..7];
-    let rest = &s[7..];
+}
+
+/// Строки `:61:` и `:86:` одной проводки; `:86:` — в раскладке `layout`.
+pub(crate) fn format_61_86(e: &Entry, layout: &dyn Field86) -> Result<String> {
+    use std::fmt::Write as FmtWrite;
+    let mut s = String::new();
+    let dc = match (e.dc, e.reversal) {
+        (DebitCredit::Debit, false) => "D",
+        (DebitCredit::Credit, false) => "C",
+        (DebitCredit::Debit, true) => "RC",
+        (DebitCredit::Credit, true) => "RD",
+    };
+    let funds = e.funds_code.map(String::from).unwrap_or_default();
+    let val = e.value_date.unwrap_or(e.booking_date);
 
-    if rest.len() < 3 {
-        return Ok(None);
+    // reference (если нет — пишем NONREF)
+    let ref_str = e
+        .reference
+        .as_deref()
+        .filter(|v| !v.is_empty())
+        .unwrap_or("NONREF");
+
+    // тип операции: свой или выведенный из ISO-кода; ISO-код без пары —
+    // NMSC, без кода вовсе — NTRF
+    let tx = match &e.bank_tx_code {
+        Some(code) => code.swift_code().unwrap_or_else(|| SWIFT_MISC.into()),
+        None => "NTRF".into(),
+    };
+
+    // :61: YYMMDD MMDD D/C[funds] amount TYPE REF[//BANKREF] — amount БЕЗ валюты
+    let _ = write!(
+        s,
+        ":61:{}{}{}{}{}{}{}",
+        val.format("%y%m%d"),
+        e.booking_date.format("%m%d"),
+        dc,
+        funds,
+        format_amount_plain(&e.amount, e.currency)?,
+        tx,
+        ref_str
+    );
+    if let Some(bref) = e.bank_reference.as_deref().filter(|r| !r.is_empty()) {
+        let _ = write!(s, "//{bref}");
+    }
+    s.push('\n');
+    if let Some(extra) = e.supplementary.as_deref().filter(|x| !x.is_empty()) {
+        let _ = writeln!(s, "{extra}");
     }
-    let currency = &rest[0..3];
-    let amt = &rest[3..].replace(',', ".");
 
-    let amount: Decimal =
-        amt.parse()
-            .map_err(|e| FinioError::Parse(format!("balance amt: {e}")))?;
-    let d = parse_mt_date(date)?;
-    let signed = if dc == "D" { -amount } else { amount };
-    Ok(Some(Balance {
-        date: d,
-        amount: signed,
-        currency: currency.to_string(),
-    }))
+    let lines = layout.format(e);
+    if !lines.is_empty() {
+        let _ = writeln!(s, ":86:{}", lines.join("\n"));
+    }
+    Ok(s)
 }
 
-fn parse_mt_date(yy_mmdd: &str) -> Result<NaiveDate> {
-    NaiveDate::parse_from_str(yy_mmdd, "%y%m%d").map_err(|e| FinioError::Parse(e.to_string()))
+/// Вторая строка `:61:` — дополнительные сведения (до 34 символов).
+pub(crate) fn append_supplementary(e: &mut Entry, line: &str) {
+    let line = line.trim();
+    if !line.is_empty() {
+        e.supplementary = Some(line.to_string());
+    }
 }
 
-fn parse_mt_book_date(year: i32, mmdd: &str) -> Result<NaiveDate> {
-    NaiveDate::parse_from_str(&format!("{year}{mmdd}"), "%Y%m%d")
-        .map_err(|e| FinioError::Parse(e.to_string()))
+/// Дописывает строку `:86:` (или её продолжение) к сырому тексту поля;
+/// до `finish_86` строки хранятся в `description` через перевод строки.
+pub(crate) fn append_86(e: &mut Entry, text: &str) {
+    if !e.description.is_empty() {
+        e.description.push('\n');
+    }
+    e.description.push_str(text);
 }
 
-fn format_amount(a: &rust_decimal::Decimal, ccy: &str) -> String {
-    let mut s = a.abs().to_string();
-    if let Some(dot) = s.find('.') {
-        s.replace_range(dot..=dot, ",");
+/// Раскладывает накопленный `append_86` текст по полям проводки.
+pub(crate) fn finish_86(e: &mut Entry, layout: &dyn Field86) {
+    let raw = std::mem::take(&mut e.description);
+    if !raw.is_empty() {
+        layout.parse(&raw.split('\n').collect::<Vec<_>>(), e);
     }
-    format!("{ccy}{s}")
 }
 
-fn format_amount_plain(a: &rust_decimal::Decimal) -> String {
-    let mut s = a.abs().to_string();
-    if let Some(dot) = s.find('.') {
-        s.replace_range(dot..=dot, ",");
+pub(crate) fn is_message_delimiter(line: &str) -> bool {
+    let t = line.trim();
+    t == "-" || t.starts_with("-}") || t.starts_with('{')
+}
+
+/// `:28C:` — номер выписки и необязательный номер страницы: `5n[/5n]`.
+pub(crate) fn parse_28c(s: &str, st: &mut Statement) -> Result<()> {
+    let s = s.trim();
+    let invalid = || -> FinioError {
+        Diagnostic::new(ErrorCode::InvalidValue, "expected statement number[/page]").field(":28C:").value(s).into()
+    };
+    let (number, page) = match s.split_once('/') {
+        Some((n, p)) => (n, Some(p)),
+        None => (s, None),
+    };
+    st.legal_seq = Some(number.parse().map_err(|_| invalid())?);
+    st.page = page.map(|p| p.parse().map_err(|_| invalid())).transpose()?;
+    Ok(())
+}
+
+/// Значение `:28C:`; без номера — `1`, как принято у банков без нумерации.
+pub(crate) fn statement_number(st: &Statement) -> String {
+    let number = st.legal_seq.or(st.electronic_seq).unwrap_or(1);
+    match st.page {
+        Some(page) => format!("{number}/{page}"),
+        None => number.to_string(),
     }
-    s
+}
+
+/// Парс баланса формата D/C + YYMMDD + CCY + amount; `tag` — для диагностики.
+fn parse_balance(s: &str, tag: &str) -> Result<Balance> {
+    let Some(c) = RE_BALANCE.captures(s.trim_end()) else {
+        return Err(Diagnostic::new(ErrorCode::Syntax, format!("malformed {tag} balance, expected D|C YYMMDD CCY amount"))
+            .field(tag)
+            .value(s)
+            .into());
+    };
+    let currency = parse_currency(&c["ccy"], &format!("{tag} currency"))?;
+    let amount = parse_mt_amount(&c["amt"], &format!("{tag} amount"))?;
+    let date = parse_mt_date(&c["date"], &format!("{tag} date"))?;
+    Ok(Balance {
+        date,
+        amount: if &c["dc"] == "D" { -amount } else { amount },
+        currency,
+    })
+}
+
+/// Сумма SWIFT с запятой; `field` — для диагностики.
+pub(crate) fn parse_mt_amount(s: &str, field: &str) -> Result<Decimal> {
+    s.replace(',', ".").parse().map_err(|e| {
+        Diagnostic::new(ErrorCode::InvalidAmount, format!("invalid amount ({e})"))
+            .field(field)
+            .value(s)
+            .into()
+    })
+}
+
+pub(crate) fn parse_mt_date(yy_mmdd: &str, field: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(yy_mmdd, "%y%m%d").map_err(|e| invalid_date(e, field, yy_mmdd))
+}
+
+/// Дата проводки `MMDD` без года — ближайшая к дате валютирования:
+/// при валютировании 2025-12-31 проводка `0102` — это 2026-01-02.
+fn parse_mt_book_date(value_date: NaiveDate, mmdd: &str, field: &str) -> Result<NaiveDate> {
+    let year = value_date.year();
+    let parse = |y: i32| NaiveDate::parse_from_str(&format!("{y}{mmdd}"), "%Y%m%d");
+    [year - 1, year, year + 1]
+        .into_iter()
+        .filter_map(|y| parse(y).ok())
+        .min_by_key(|d| (*d - value_date).num_days().abs())
+        .map_or_else(|| parse(year).map_err(|e| invalid_date(e, field, mmdd)), Ok)
+}
+
+fn invalid_date(e: chrono::ParseError, field: &str, value: &str) -> FinioError {
+    Diagnostic::new(ErrorCode::InvalidDate, format!("invalid date ({e})"))
+        .field(field)
+        .value(value)
+        .into()
+}
+
+/// Строка остатка `:60F:`/`:62F:`/`:64:`…: D/C, YYMMDD, валюта, сумма.
+fn balance_line(tag: &str, b: &Balance) -> Result<String> {
+    Ok(format!(
+        "{tag}{}{}{}\n",
+        if b.amount.is_sign_negative() { "D" } else { "C" },
+        b.date.format("%y%m%d"),
+        format_amount(&b.amount, b.currency)?
+    ))
+}
+
+/// Валюта и сумма SWIFT: `EUR100,00`.
+pub(crate) fn format_amount(a: &Decimal, ccy: Currency) -> Result<String> {
+    Ok(format!("{ccy}{}", format_amount_plain(a, ccy)?))
+}
+
+/// Сумма SWIFT (15d) в знаках валюты; запятая обязательна и без дробной
+/// части: `100,` для JPY.
+pub(crate) fn format_amount_plain(a: &Decimal, ccy: Currency) -> Result<String> {
+    let s = ccy.scale(a.abs())?.to_string();
+    Ok(match s.split_once('.') {
+        Some((int, frac)) => format!("{int},{frac}"),
+        None => format!("{s},"),
+    })
 }
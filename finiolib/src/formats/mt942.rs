@@ -0,0 +1,415 @@
+//! MT942 — промежуточная (внутридневная) выписка.
+//!
+//! Теги: :20:, :21:, :25:, :28C:, :34F:, :13D:, :61:/:86: (как в MT940),
+//! :90D:, :90C:, завершающий :86:. Остатков :60F:/:62F: в MT942 нет.
+//!
+//! Отчёт целиком (с лимитами, временем и итогами) доступен через
+//! `Mt942::read_reports`/`write_reports`; `ReadFormat`/`WriteFormat` отдают
+//! только нормализованную `Statement`. Внутридневные сообщения невелики,
+//! поэтому потоковые reader/writer — адаптеры поверх готовых выписок.
+
+use crate::{
+    account::AccountId,
+    currency::{parse_currency, Currency},
+    error::{Diagnostic, ErrorCode, FinioError, Location, Result},
+    formats::mt940::{
+        append_86, finish_86, format_61_86, format_amount, format_amount_plain,
+        append_supplementary, is_message_delimiter, parse_28c, parse_61, parse_mt_amount,
+        statement_number,
+    },
+    formats::field86::Auto,
+    model::{DebitCredit, EntryStatus, Statement},
+    stream::{self, Buffered, StatementEvent},
+    traits::{ParseMode, ReadFormat, StreamRead, StreamWrite, WriteFormat},
+};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
+use regex::Regex;
+use rust_decimal::Decimal;
+use std::io::{BufRead, Write};
+use std::sync::LazyLock;
+
+pub struct Mt942;
+
+/// :34F: — минимальная сумма проводок, попадающих в отчёт.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloorLimit {
+    pub currency: Currency,
+    pub amount: Decimal,
+}
+
+/// :90D:/:90C: — количество и сумма дебетовых/кредитовых проводок.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntrySummary {
+    pub count: u32,
+    pub currency: Currency,
+    pub amount: Decimal,
+}
+
+/// Один отчёт MT942 (блок :20:…).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mt942Report {
+    /// :20:, :25:, проводки :61:/:86:
+    pub statement: Statement,
+    /// :21:
+    pub related_reference: Option<String>,
+    /// :28C:
+    pub statement_number: Option<String>,
+    /// первый :34F: (D или без признака — общий лимит)
+    pub floor_limit_debit: Option<FloorLimit>,
+    /// второй :34F: с признаком C; если его нет — совпадает с дебетовым
+    pub floor_limit_credit: Option<FloorLimit>,
+    /// :13D:
+    pub date_time: Option<DateTime<FixedOffset>>,
+    /// :90D:
+    pub debits: Option<EntrySummary>,
+    /// :90C:
+    pub credits: Option<EntrySummary>,
+    /// :86: после :90D:/:90C: — информация владельцу счёта
+    pub info: Option<String>,
+}
+
+impl Mt942Report {
+    /// Отчёт из нормализованной выписки: итоги :90D:/:90C: считаются по
+    /// проводкам в валюте отчёта (проводки в других валютах в них не входят),
+    /// лимит — нулевой (в отчёт попадают все проводки), :13D: — время
+    /// формирования выписки или конец дня последней проводки; без известного
+    /// смещения время считается UTC, чтобы вывод не зависел от машины.
+    pub fn from_statement(st: &Statement) -> Self {
+        let currency = statement_currency(st);
+        let summary = |dc: DebitCredit| {
+            let (count, amount) = st
+                .entries
+                .iter()
+                .filter(|e| e.dc == dc && e.currency == currency)
+                .fold((0u32, Decimal::ZERO), |(n, sum), e| (n + 1, sum + e.amount));
+            EntrySummary { count, currency, amount }
+        };
+        let limit = FloorLimit { currency, amount: Decimal::ZERO };
+        let date_time = st.created_at().or_else(|| {
+            let local = st
+                .created
+                .or_else(|| st.entries.iter().map(|e| e.booking_date).max()?.and_hms_opt(23, 59, 0))?;
+            Some(local.and_utc().fixed_offset())
+        });
+
+        Mt942Report {
+            statement: st.clone(),
+            floor_limit_debit: Some(limit.clone()),
+            floor_limit_credit: Some(limit),
+            date_time,
+            debits: Some(summary(DebitCredit::Debit)),
+            credits: Some(summary(DebitCredit::Credit)),
+            ..Mt942Report::default()
+        }
+    }
+}
+
+fn statement_currency(st: &Statement) -> Currency {
+    st.entries
+        .first()
+        .map(|e| e.currency)
+        .or_else(|| st.opening_balance.as_ref().map(|b| b.currency))
+        .unwrap_or(Currency::XXX)
+}
+
+// :34F: CCY [D|C] amount
+static RE_34F: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<ccy>[A-Z]{3})(?P<dc>[DC])?(?P<amt>\d+(?:,\d*)?)$").expect("valid :34F: regex")
+});
+
+// :90D:/:90C: count CCY amount
+static RE_90: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<n>\d{1,5})(?P<ccy>[A-Z]{3})(?P<amt>\d+(?:,\d*)?)$").expect("valid :90: regex")
+});
+
+/* ------------------------------- READ ----------------------------------- */
+
+impl Mt942 {
+    pub fn read_reports<R: BufRead>(r: R) -> Result<Vec<Mt942Report>> {
+        Ok(Self::read_reports_with(r, ParseMode::Strict)?.0)
+    }
+
+    /// В мягком режиме испорченная строка пропускается (`:61:` — вместе со
+    /// своей `:86:`), а её диагностика попадает во второй элемент.
+    pub fn read_reports_with<R: BufRead>(
+        r: R,
+        mode: ParseMode,
+    ) -> Result<(Vec<Mt942Report>, Vec<Diagnostic>)> {
+        let mut reports: Vec<Mt942Report> = Vec::new();
+        let mut cur: Option<Mt942Report> = None;
+        // куда относится очередной :86: — к последней проводке или к отчёту
+        let mut after_totals = false;
+        let mut last_has_86 = false;
+        // :86: пропущенной :61: некуда отнести
+        let mut skip_86 = false;
+        // строка без тега сразу после :61: — её дополнительные сведения
+        let mut after_61 = false;
+
+        let mut step = |line: &str| -> Result<()> {
+            let prev_61 = std::mem::take(&mut after_61);
+            if line.starts_with(':') && !line.starts_with(":86:") {
+                skip_86 = false;
+            }
+            if let Some(rest) = line.strip_prefix(":20:") {
+                if let Some(rep) = cur.take() {
+                    reports.push(rep);
+                }
+                let rep = cur.insert(Mt942Report::default());
+                rep.statement.statement_id = Some(rest.trim().to_string());
+                after_totals = false;
+                last_has_86 = false;
+                return Ok(());
+            }
+
+            let rep = cur.get_or_insert_default();
+            if let Some(rest) = line.strip_prefix(":21:") {
+                rep.related_reference = Some(rest.trim().to_string());
+            } else if let Some(rest) = line.strip_prefix(":25:") {
+                rep.statement.account_id = AccountId::parse(rest);
+            } else if let Some(rest) = line.strip_prefix(":28C:") {
+                parse_28c(rest, &mut rep.statement)?;
+                rep.statement_number = Some(rest.trim().to_string());
+            } else if let Some(rest) = line.strip_prefix(":34F:") {
+                let (dc, limit) = parse_34f(rest.trim())?;
+                match (dc, &rep.floor_limit_debit) {
+                    (Some(DebitCredit::Credit), _) | (None, Some(_)) => {
+                        rep.floor_limit_credit = Some(limit)
+                    }
+                    (Some(DebitCredit::Debit), _) => rep.floor_limit_debit = Some(limit),
+                    (None, None) => {
+                        // без признака — один лимит на оба направления
+                        rep.floor_limit_debit = Some(limit.clone());
+                        rep.floor_limit_credit = Some(limit);
+                    }
+                }
+            } else if let Some(rest) = line.strip_prefix(":13D:") {
+                let dt = parse_13d(rest.trim())?;
+                rep.statement.created = Some(dt.naive_local());
+                rep.statement.created_offset = Some(*dt.offset());
+                rep.date_time = Some(dt);
+            } else if line.starts_with(":61:") {
+                let currency = rep
+                    .floor_limit_debit
+                    .as_ref()
+                    .map_or(Currency::XXX, |l| l.currency);
+                last_has_86 = false;
+                skip_86 = true;
+                let mut e = parse_61(line, currency)?;
+                // MT942 — промежуточный отчёт: проводки ещё не в выписке
+                e.status = EntryStatus::Pending;
+                skip_86 = false;
+                rep.statement.entries.push(e);
+                last_has_86 = false;
+                after_61 = true;
+            } else if let Some(rest) = line.strip_prefix(":90D:") {
+                rep.debits = Some(parse_90(rest.trim())?);
+                after_totals = true;
+                last_has_86 = false;
+            } else if let Some(rest) = line.strip_prefix(":90C:") {
+                rep.credits = Some(parse_90(rest.trim())?);
+                after_totals = true;
+                last_has_86 = false;
+            } else if let Some(text) = line.strip_prefix(":86:") {
+                if skip_86 {
+                    last_has_86 = false;
+                } else if after_totals {
+                    rep.info = Some(text.to_string());
+                    last_has_86 = true;
+                } else if let Some(e) = rep.statement.entries.last_mut() {
+                    append_86(e, text);
+                    last_has_86 = true;
+                }
+            } else if is_message_delimiter(line) || line.starts_with(':') {
+                last_has_86 = false;
+            } else if prev_61 {
+                if let Some(e) = rep.statement.entries.last_mut() {
+                    append_supplementary(e, line);
+                }
+            } else if last_has_86 {
+                // продолжение :86: без нового тега
+                if after_totals {
+                    if let Some(info) = rep.info.as_mut() {
+                        info.push(' ');
+                        info.push_str(line.trim());
+                    }
+                } else if let Some(e) = rep.statement.entries.last_mut() {
+                    append_86(e, line.trim());
+                }
+            }
+            Ok(())
+        };
+
+        let mut skipped = Vec::new();
+        for (i, line) in r.lines().enumerate() {
+            let line = line?;
+            let at = Location::Line { line: i as u64 + 1, column: None };
+            if let Err(e) = step(&line) {
+                let e = e.located(at, &line);
+                match mode {
+                    ParseMode::Strict => return Err(e),
+                    ParseMode::Lenient => skipped.push(e.into_diagnostic()?),
+                }
+            }
+        }
+
+        if let Some(rep) = cur {
+            reports.push(rep);
+        }
+        for e in reports.iter_mut().flat_map(|rep| &mut rep.statement.entries) {
+            finish_86(e, &Auto);
+        }
+        Ok((reports, skipped))
+    }
+}
+
+fn parse_34f(s: &str) -> Result<(Option<DebitCredit>, FloorLimit)> {
+    let caps = RE_34F.captures(s).ok_or_else(|| malformed(":34F:", s))?;
+    let dc = caps.name("dc").map(|m| match m.as_str() {
+        "D" => DebitCredit::Debit,
+        _ => DebitCredit::Credit,
+    });
+    let amount = parse_mt_amount(&caps["amt"], ":34F: amount")?;
+    let currency = parse_currency(&caps["ccy"], ":34F: currency")?;
+    Ok((dc, FloorLimit { currency, amount }))
+}
+
+fn parse_90(s: &str) -> Result<EntrySummary> {
+    let caps = RE_90.captures(s).ok_or_else(|| malformed(":90D:/:90C:", s))?;
+    Ok(EntrySummary {
+        count: caps["n"].parse().map_err(|e| {
+            Diagnostic::new(ErrorCode::InvalidValue, format!("invalid count ({e})"))
+                .field(":90D:/:90C: count")
+                .value(&caps["n"])
+        })?,
+        currency: parse_currency(&caps["ccy"], ":90D:/:90C: currency")?,
+        amount: parse_mt_amount(&caps["amt"], ":90D:/:90C: amount")?,
+    })
+}
+
+/// :13D: YYMMDDHHMM±HHMM
+fn parse_13d(s: &str) -> Result<DateTime<FixedOffset>> {
+    let bad = || {
+        FinioError::from(
+            Diagnostic::new(ErrorCode::InvalidDate, "expected YYMMDDHHMM±HHMM")
+                .field(":13D:")
+                .value(s),
+        )
+    };
+    if s.len() != 15 || !s.is_ascii() {
+        return Err(bad());
+    }
+    let local = NaiveDateTime::parse_from_str(&s[..10], "%y%m%d%H%M").map_err(|_| bad())?;
+    let sign = match &s[10..11] {
+        "+" => 1,
+        "-" => -1,
+        _ => return Err(bad()),
+    };
+    let hh: i32 = s[11..13].parse().map_err(|_| bad())?;
+    let mm: i32 = s[13..15].parse().map_err(|_| bad())?;
+    let offset = FixedOffset::east_opt(sign * (hh * 3600 + mm * 60)).ok_or_else(bad)?;
+    offset.from_local_datetime(&local).single().ok_or_else(bad)
+}
+
+fn malformed(tag: &str, value: &str) -> FinioError {
+    Diagnostic::new(ErrorCode::Syntax, format!("malformed {tag} field"))
+        .field(tag)
+        .value(value)
+        .into()
+}
+
+impl ReadFormat for Mt942 {
+    fn read_statements_with<R: BufRead>(
+        r: R,
+        mode: ParseMode,
+    ) -> Result<(Vec<Statement>, Vec<Diagnostic>)> {
+        let (reports, skipped) = Self::read_reports_with(r, mode)?;
+        Ok((reports.into_iter().map(|rep| rep.statement).collect(), skipped))
+    }
+}
+
+impl StreamRead for Mt942 {
+    type Reader<R: BufRead> = Box<dyn Iterator<Item = Result<StatementEvent>>>;
+
+    fn reader_with<R: BufRead>(r: R, mode: ParseMode) -> Self::Reader<R> {
+        match Self::read_statements_with(r, mode) {
+            Ok((sts, skipped)) => Box::new(
+                skipped
+                    .into_iter()
+                    .map(|d| Err(d.into()))
+                    .chain(stream::events(sts)),
+            ),
+            Err(e) => Box::new(std::iter::once(Err(e))),
+        }
+    }
+}
+
+/* ------------------------------- WRITE ---------------------------------- */
+
+impl Mt942 {
+    pub fn write_reports<W: Write>(mut w: W, reports: &[Mt942Report]) -> Result<()> {
+        use std::fmt::Write as FmtWrite;
+        for rep in reports {
+            let st = &rep.statement;
+            let mut s = String::new();
+            let _ = writeln!(s, ":20:{}", st.statement_id.as_deref().unwrap_or("NOTPROVIDED"));
+            if let Some(r) = &rep.related_reference {
+                let _ = writeln!(s, ":21:{r}");
+            }
+            let _ = writeln!(s, ":25:{}", st.account_id);
+            let number = rep.statement_number.clone().unwrap_or_else(|| statement_number(st));
+            let _ = writeln!(s, ":28C:{number}");
+
+            match (&rep.floor_limit_debit, &rep.floor_limit_credit) {
+                (Some(d), Some(c)) if d != c => {
+                    let _ = writeln!(s, ":34F:{}D{}", d.currency, format_amount_plain(&d.amount, d.currency)?);
+                    let _ = writeln!(s, ":34F:{}C{}", c.currency, format_amount_plain(&c.amount, c.currency)?);
+                }
+                (Some(l), _) | (None, Some(l)) => {
+                    let _ = writeln!(s, ":34F:{}", format_amount(&l.amount, l.currency)?);
+                }
+                (None, None) => {
+                    let _ = writeln!(s, ":34F:{}", format_amount(&Decimal::ZERO, statement_currency(st))?);
+                }
+            }
+
+            if let Some(dt) = rep.date_time {
+                let _ = writeln!(s, ":13D:{}", dt.format("%y%m%d%H%M%z"));
+            }
+
+            for e in &st.entries {
+                s.push_str(&format_61_86(e, &Auto)?);
+            }
+
+            for (tag, sum) in [("90D", &rep.debits), ("90C", &rep.credits)] {
+                if let Some(sum) = sum {
+                    let _ = writeln!(
+                        s,
+                        ":{tag}:{}{}",
+                        sum.count,
+                        format_amount(&sum.amount, sum.currency)?
+                    );
+                }
+            }
+            if let Some(info) = &rep.info {
+                let _ = writeln!(s, ":86:{info}");
+            }
+            w.write_all(s.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteFormat for Mt942 {
+    fn write_statements<W: Write>(w: W, sts: &[Statement]) -> Result<()> {
+        let reports: Vec<Mt942Report> = sts.iter().map(Mt942Report::from_statement).collect();
+        Self::write_reports(w, &reports)
+    }
+}
+
+impl StreamWrite for Mt942 {
+    type Writer<W: Write> = Buffered<Self, W>;
+
+    fn writer<W: Write>(w: W) -> Self::Writer<W> {
+        Buffered::new(w)
+    }
+}
@@ -0,0 +1,702 @@
+//! 1CClientBankExchange — текстовый формат обмена «Клиент банка» ↔ 1С.
+//!
+//! Файл состоит из строк `Ключ=Значение`: заголовок, секции `СекцияРасчСчет`
+//! … `КонецРасчСчет` (остатки по счёту за период) и документы
+//! `СекцияДокумент=<вид>` … `КонецДокумента`. Кодировка — Windows-1251
+//! (`Кодировка=Windows`) или CP866 (`Кодировка=DOS`), суммы в рублях.
+//!
+//! Каждая секция счёта становится `Statement`; документ попадает в выписку
+//! счёта, совпадающего со счётом плательщика (дебет) или получателя (кредит).
+//! ИНН, КПП, банк и корсчёт другой стороны — в `Counterparty`, своей — в
+//! реквизитах владельца и банка `Statement`; остальное (`ВидОплаты`,
+//! `Очередность`…) доступно только через `OneC::read_exchange`.
+//!
+//! Выписка 1С — это остатки по счёту и документы в любом порядке, поэтому
+//! файл разбирается и пишется целиком; потоковые reader/writer — адаптеры.
+
+use crate::{
+    account::{AccountId, Bic},
+    currency::Currency,
+    encoding::{Encoding, EncodingWriter},
+    error::{Diagnostic, ErrorCode, FinioError, Location, Result},
+    model::{Balance, Counterparty, DebitCredit, Entry, EntryStatus, Period, Remittance, Statement},
+    stream::{self, Buffered, StatementEvent},
+    traits::{ParseMode, ReadFormat, StreamRead, StreamWrite, WriteFormat},
+};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use encoding_rs::{IBM866, WINDOWS_1251};
+use rust_decimal::Decimal;
+use std::io::{BufRead, Write};
+
+pub struct OneC;
+
+const MAGIC: &str = "1CClientBankExchange";
+const FORMAT_VERSION: &str = "1.03";
+const DATE_FMT: &str = "%d.%m.%Y";
+
+/// Плательщик или получатель документа.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OneCParty {
+    /// `…Счет`
+    pub account: Option<String>,
+    /// `Плательщик`/`Получатель` (или `…1`)
+    pub name: Option<String>,
+    pub inn: Option<String>,
+    pub kpp: Option<String>,
+    /// `…Банк1`
+    pub bank: Option<String>,
+    pub bik: Option<String>,
+    /// `…Корсчет`
+    pub corr_account: Option<String>,
+}
+
+/// `СекцияДокумент` … `КонецДокумента`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OneCDocument {
+    /// вид документа: «Платежное поручение», «Банковский ордер» и т.п.
+    pub kind: String,
+    pub number: Option<String>,
+    pub date: NaiveDate,
+    pub amount: Decimal,
+    pub payer: OneCParty,
+    pub payee: OneCParty,
+    /// `ДатаСписано` — дата списания со счёта плательщика
+    pub debited: Option<NaiveDate>,
+    /// `ДатаПоступило` — дата зачисления на счёт получателя
+    pub credited: Option<NaiveDate>,
+    /// `НазначениеПлатежа`
+    pub purpose: String,
+    /// прочие реквизиты (`ВидОплаты`, `Очередность`, …) в исходном порядке
+    pub extra: Vec<(String, String)>,
+}
+
+/// `СекцияРасчСчет` … `КонецРасчСчет`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OneCAccount {
+    pub account: String,
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
+    pub opening: Option<Decimal>,
+    /// `ВсегоПоступило`
+    pub total_in: Option<Decimal>,
+    /// `ВсегоСписано`
+    pub total_out: Option<Decimal>,
+    pub closing: Option<Decimal>,
+}
+
+/// Файл обмена целиком.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OneCExchange {
+    pub sender: Option<String>,
+    pub receiver: Option<String>,
+    pub created: Option<NaiveDateTime>,
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
+    pub accounts: Vec<OneCAccount>,
+    pub documents: Vec<OneCDocument>,
+}
+
+impl OneCParty {
+    fn to_counterparty(&self) -> Option<Counterparty> {
+        let c = Counterparty {
+            name: self.name.clone(),
+            account: self.account.clone(),
+            bic: self.bik.as_deref().map(Bic::from),
+            address: None,
+            tax_id: self.inn.clone(),
+            tax_reason_code: self.kpp.clone(),
+            bank_name: self.bank.clone(),
+            bank_corr_account: self.corr_account.clone(),
+        };
+        (!c.is_empty()).then_some(c)
+    }
+
+    fn from_counterparty(c: &Counterparty) -> Self {
+        OneCParty {
+            account: c.account.clone(),
+            name: c.name.clone(),
+            inn: c.tax_id.clone(),
+            kpp: c.tax_reason_code.clone(),
+            bank: c.bank_name.clone(),
+            bik: c.bic.as_ref().map(ToString::to_string),
+            corr_account: c.bank_corr_account.clone(),
+        }
+    }
+
+    /// Реквизиты своей стороны — владельца счёта выписки.
+    fn from_owner(st: &Statement) -> Self {
+        let bik = match &st.account_id {
+            AccountId::Ru { bik, .. } => bik.clone(),
+            _ => None,
+        };
+        OneCParty {
+            account: Some(st.account_id.number().to_string()),
+            name: st.account_owner.clone(),
+            inn: st.owner_tax_id.clone(),
+            kpp: st.owner_tax_reason_code.clone(),
+            bank: st.servicer_name.clone(),
+            bik: bik.or_else(|| st.servicer_bic.as_ref().filter(|b| b.is_bik()).map(ToString::to_string)),
+            corr_account: st.servicer_corr_account.clone(),
+        }
+    }
+}
+
+impl OneCDocument {
+    /// Проводка с точки зрения счёта `account`; `None`, если документ
+    /// не касается этого счёта.
+    pub fn to_entry(&self, account: &str) -> Option<Entry> {
+        let dc = if self.payer.account.as_deref() == Some(account) {
+            DebitCredit::Debit
+        } else if self.payee.account.as_deref() == Some(account) {
+            DebitCredit::Credit
+        } else {
+            return None;
+        };
+        let (booked, other) = match dc {
+            DebitCredit::Debit => (self.debited, &self.payee),
+            DebitCredit::Credit => (self.credited, &self.payer),
+        };
+        Some(Entry {
+            booking_date: booked.unwrap_or(self.date),
+            value_date: None,
+            amount: self.amount,
+            currency: Currency::RUB,
+            dc,
+            description: self.purpose.clone(),
+            reference: self.number.clone(),
+            reversal: false,
+            status: EntryStatus::Booked,
+            funds_code: None,
+            bank_reference: None,
+            supplementary: None,
+            counterparty: other.to_counterparty(),
+            posting_text: None,
+            bank_tx_code: None,
+            remittance: Remittance::default(),
+            details: Vec::new(),
+        })
+    }
+
+    /// Документ по проводке выписки `st`: владелец счёта — плательщик при
+    /// дебете и получатель при кредите; другая сторона — из `counterparty`.
+    pub fn from_entry(e: &Entry, st: &Statement) -> Self {
+        let ours = OneCParty::from_owner(st);
+        let other = e.counterparty.as_ref().map(OneCParty::from_counterparty).unwrap_or_default();
+        let (payer, payee, debited, credited) = match e.dc {
+            DebitCredit::Debit => (ours, other, Some(e.booking_date), None),
+            DebitCredit::Credit => (other, ours, None, Some(e.booking_date)),
+        };
+        OneCDocument {
+            kind: "Платежное поручение".into(),
+            number: e.reference.clone(),
+            date: e.value_date.unwrap_or(e.booking_date),
+            amount: e.amount,
+            payer,
+            payee,
+            debited,
+            credited,
+            purpose: e.description.clone(),
+            extra: Vec::new(),
+        }
+    }
+}
+
+impl OneCExchange {
+    /// Выписка на каждую секцию счёта. В файле без секций (поручения
+    /// из 1С в банк) выписок нет.
+    pub fn statements(&self) -> Vec<Statement> {
+        self.accounts
+            .iter()
+            .map(|acc| {
+                let from = acc.date_from.or(self.date_from);
+                let to = acc.date_to.or(self.date_to);
+                let balance = |amount: Option<Decimal>, date: Option<NaiveDate>| {
+                    Some(Balance { date: date?, amount: amount?, currency: Currency::RUB })
+                };
+                // реквизиты владельца и его банка — из первого документа по счёту
+                let ours = self
+                    .documents
+                    .iter()
+                    .flat_map(|d| [&d.payer, &d.payee])
+                    .find(|p| p.account.as_deref() == Some(acc.account.as_str()))
+                    .cloned()
+                    .unwrap_or_default();
+                let account_id = match AccountId::parse(&acc.account) {
+                    AccountId::Ru { account, bik: None } => AccountId::Ru { account, bik: ours.bik },
+                    id => id,
+                };
+                Statement {
+                    created: self.created,
+                    period: from.zip(to).map(|(from, to)| Period::days(from, to)),
+                    account_id,
+                    account_currency: Some(Currency::RUB),
+                    account_owner: ours.name,
+                    owner_tax_id: ours.inn,
+                    owner_tax_reason_code: ours.kpp,
+                    servicer_name: ours.bank,
+                    servicer_corr_account: ours.corr_account,
+                    opening_balance: balance(acc.opening, from),
+                    closing_balance: balance(acc.closing, to),
+                    entries: self
+                        .documents
+                        .iter()
+                        .filter_map(|d| d.to_entry(&acc.account))
+                        .collect(),
+                    ..Statement::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Файл обмена из выписок в рублях; итоги оборотов считаются по проводкам.
+    pub fn from_statements(sts: &[Statement]) -> Result<Self> {
+        let mut ex = OneCExchange::default();
+        for st in sts {
+            let mut currencies = st.entries.iter().map(|e| e.currency).chain(
+                [&st.opening_balance, &st.closing_balance]
+                    .into_iter()
+                    .flatten()
+                    .map(|b| b.currency),
+            );
+            if currencies.any(|c| c != Currency::RUB) {
+                return Err(FinioError::Unsupported("1CClientBankExchange supports RUB only"));
+            }
+
+            let sum = |dc: DebitCredit| {
+                st.entries.iter().filter(|e| e.dc == dc).map(|e| e.amount).sum::<Decimal>()
+            };
+            let dates = st.entries.iter().map(|e| e.booking_date);
+            let period = st.period.map(|p| (p.from.date(), p.to.date()));
+            let date_from = period
+                .map(|p| p.0)
+                .or(st.opening_balance.as_ref().map(|b| b.date))
+                .or(dates.clone().min());
+            let date_to = period
+                .map(|p| p.1)
+                .or(st.closing_balance.as_ref().map(|b| b.date))
+                .or(dates.max());
+
+            ex.accounts.push(OneCAccount {
+                account: st.account_id.number().to_string(),
+                date_from,
+                date_to,
+                opening: st.opening_balance.as_ref().map(|b| b.amount),
+                total_in: Some(sum(DebitCredit::Credit)),
+                total_out: Some(sum(DebitCredit::Debit)),
+                closing: st.closing_balance.as_ref().map(|b| b.amount),
+            });
+            ex.documents
+                .extend(st.entries.iter().map(|e| OneCDocument::from_entry(e, st)));
+        }
+        ex.created = sts.iter().find_map(|st| st.created);
+        ex.date_from = ex.accounts.iter().filter_map(|a| a.date_from).min();
+        ex.date_to = ex.accounts.iter().filter_map(|a| a.date_to).max();
+        Ok(ex)
+    }
+}
+
+/* ------------------------------- READ ----------------------------------- */
+
+enum Section {
+    Header,
+    Account(OneCAccount),
+    Document(Box<DocumentBuilder>),
+    /// испорченный документ в мягком режиме — пропускается до `КонецДокумента`
+    Skipped,
+}
+
+/// Документ до `КонецДокумента`: дата и сумма обязательны.
+struct DocumentBuilder {
+    kind: String,
+    number: Option<String>,
+    date: Option<NaiveDate>,
+    amount: Option<Decimal>,
+    payer: OneCParty,
+    payee: OneCParty,
+    debited: Option<NaiveDate>,
+    credited: Option<NaiveDate>,
+    purpose: String,
+    extra: Vec<(String, String)>,
+}
+
+impl DocumentBuilder {
+    fn new(kind: &str) -> Self {
+        DocumentBuilder {
+            kind: kind.to_string(),
+            number: None,
+            date: None,
+            amount: None,
+            payer: OneCParty::default(),
+            payee: OneCParty::default(),
+            debited: None,
+            credited: None,
+            purpose: String::new(),
+            extra: Vec::new(),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let opt = || (!value.is_empty()).then(|| value.to_string());
+        match key {
+            "Номер" => self.number = opt(),
+            "Дата" => self.date = Some(parse_date(value, key)?),
+            "Сумма" => self.amount = Some(parse_amount(value, key)?),
+            "ДатаСписано" => self.debited = parse_opt_date(value, key)?,
+            "ДатаПоступило" => self.credited = parse_opt_date(value, key)?,
+            "НазначениеПлатежа" => {
+                // бывает разбито на НазначениеПлатежа1..6 — склеиваем
+                if !self.purpose.is_empty() && !value.is_empty() {
+                    self.purpose.push(' ');
+                }
+                self.purpose.push_str(value);
+            }
+            _ => {
+                if let Some(rest) = key.strip_prefix("Плательщик") {
+                    if set_party(&mut self.payer, rest, value) {
+                        return Ok(());
+                    }
+                } else if let Some(rest) = key.strip_prefix("Получатель") {
+                    if set_party(&mut self.payee, rest, value) {
+                        return Ok(());
+                    }
+                } else if key.starts_with("НазначениеПлатежа") {
+                    return self.set("НазначениеПлатежа", value);
+                }
+                self.extra.push((key.to_string(), value.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    fn build(self) -> Result<OneCDocument> {
+        let missing = |key: &str| {
+            let d = Diagnostic::new(ErrorCode::MissingField, "required document field missing");
+            FinioError::from(d.field(key))
+        };
+        Ok(OneCDocument {
+            date: self.date.ok_or_else(|| missing("Дата"))?,
+            amount: self.amount.ok_or_else(|| missing("Сумма"))?,
+            kind: self.kind,
+            number: self.number,
+            payer: self.payer,
+            payee: self.payee,
+            debited: self.debited,
+            credited: self.credited,
+            purpose: self.purpose,
+            extra: self.extra,
+        })
+    }
+}
+
+/// `rest` — ключ без префикса `Плательщик`/`Получатель`; false — не реквизит стороны.
+fn set_party(p: &mut OneCParty, rest: &str, value: &str) -> bool {
+    let slot = match rest {
+        "Счет" | "РасчСчет" => &mut p.account,
+        "" | "1" => &mut p.name,
+        "ИНН" => &mut p.inn,
+        "КПП" => &mut p.kpp,
+        "Банк1" => &mut p.bank,
+        "БИК" => &mut p.bik,
+        "Корсчет" => &mut p.corr_account,
+        _ => return false,
+    };
+    // «Плательщик» и «Плательщик1» — одно и то же; первое непустое побеждает
+    if slot.is_none() && !value.is_empty() {
+        *slot = Some(value.to_string());
+    }
+    true
+}
+
+impl OneC {
+    pub fn read_exchange<R: BufRead>(r: R) -> Result<OneCExchange> {
+        Ok(Self::read_exchange_with(r, ParseMode::Strict)?.0)
+    }
+
+    /// В мягком режиме документ с ошибкой пропускается целиком, а испорченный
+    /// реквизит заголовка или счёта остаётся пустым; диагностики — вторым элементом.
+    pub fn read_exchange_with<R: BufRead>(
+        mut r: R,
+        mode: ParseMode,
+    ) -> Result<(OneCExchange, Vec<Diagnostic>)> {
+        let mut bytes = Vec::new();
+        r.read_to_end(&mut bytes)?;
+        let text = decode(&bytes);
+
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i as u64 + 1, l.trim()))
+            .filter(|(_, l)| !l.is_empty());
+        match lines.next() {
+            Some((_, MAGIC)) => {}
+            first => {
+                let (line, snippet) = first.unwrap_or((1, ""));
+                let d = Diagnostic::new(ErrorCode::Structure, format!("file must start with {MAGIC}"));
+                return Err(FinioError::from(d).located(Location::Line { line, column: None }, snippet));
+            }
+        }
+
+        let mut ex = OneCExchange::default();
+        let mut section = Section::Header;
+        let (mut created_date, mut created_time) = (None, None);
+        let mut last_line = 1;
+        // true — встречен КонецФайла
+        let mut step = |key: &str, value: &str| -> Result<bool> {
+            match (key, &mut section) {
+                ("КонецФайла", _) => return Ok(true),
+                ("СекцияРасчСчет", Section::Header) => {
+                    section = Section::Account(OneCAccount::default())
+                }
+                ("КонецРасчСчет", Section::Account(acc)) => {
+                    ex.accounts.push(std::mem::take(acc));
+                    section = Section::Header;
+                }
+                ("СекцияДокумент", Section::Header) => {
+                    section = Section::Document(Box::new(DocumentBuilder::new(value)))
+                }
+                ("КонецДокумента", Section::Document(_) | Section::Skipped) => {
+                    if let Section::Document(doc) = std::mem::replace(&mut section, Section::Header) {
+                        ex.documents.push(doc.build()?);
+                    }
+                }
+                ("СекцияРасчСчет" | "КонецРасчСчет" | "СекцияДокумент" | "КонецДокумента", _) => {
+                    return Err(Diagnostic::new(ErrorCode::Structure, "unexpected section marker")
+                        .field(key)
+                        .into());
+                }
+                (_, Section::Header) => match key {
+                    "Отправитель" => ex.sender = Some(value.to_string()),
+                    "Получатель" => ex.receiver = Some(value.to_string()),
+                    "ДатаНачала" => ex.date_from = parse_opt_date(value, key)?,
+                    "ДатаКонца" => ex.date_to = parse_opt_date(value, key)?,
+                    "ДатаСоздания" => created_date = parse_opt_date(value, key)?,
+                    "ВремяСоздания" => created_time = NaiveTime::parse_from_str(value, "%H:%M:%S").ok(),
+                    // ВерсияФормата, Кодировка, РасчСчет, Документ=… — не нужны
+                    _ => {}
+                },
+                (_, Section::Account(acc)) => match key {
+                    "РасчСчет" => acc.account = value.to_string(),
+                    "ДатаНачала" => acc.date_from = parse_opt_date(value, key)?,
+                    "ДатаКонца" => acc.date_to = parse_opt_date(value, key)?,
+                    "НачальныйОстаток" => acc.opening = parse_opt_amount(value, key)?,
+                    "ВсегоПоступило" => acc.total_in = parse_opt_amount(value, key)?,
+                    "ВсегоСписано" => acc.total_out = parse_opt_amount(value, key)?,
+                    "КонечныйОстаток" => acc.closing = parse_opt_amount(value, key)?,
+                    _ => {}
+                },
+                (_, Section::Document(doc)) => {
+                    if let Err(e) = doc.set(key, value) {
+                        if mode == ParseMode::Lenient {
+                            section = Section::Skipped;
+                        }
+                        return Err(e);
+                    }
+                }
+                (_, Section::Skipped) => {}
+            }
+            Ok(false)
+        };
+
+        let mut skipped = Vec::new();
+        for (n, line) in lines {
+            last_line = n;
+            let (key, value) = line.split_once('=').unwrap_or((line, ""));
+            let at = Location::Line { line: n, column: None };
+            match step(key, value.trim()) {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(e) if mode == ParseMode::Lenient => {
+                    skipped.push(e.located(at, line).into_diagnostic()?)
+                }
+                Err(e) => return Err(e.located(at, line)),
+            }
+        }
+
+        if !matches!(section, Section::Header) {
+            let d = Diagnostic::new(ErrorCode::Structure, "unterminated section");
+            return Err(FinioError::from(d).located(Location::Line { line: last_line, column: None }, ""));
+        }
+        ex.created = created_date.map(|d| d.and_time(created_time.unwrap_or_default()));
+        Ok((ex, skipped))
+    }
+}
+
+/// UTF-8 (в т.ч. уже перекодированный вход) принимается как есть,
+/// иначе — кодировка из заголовка `Кодировка=`.
+fn decode(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        return s.to_string();
+    }
+    // «Кодировка» в заголовке сама закодирована, поэтому смотрим на значение
+    let dos = bytes
+        .split(|b| *b == b'\n')
+        .take(16)
+        .any(|l| l.trim_ascii_end().ends_with(b"=DOS"));
+    let enc = if dos { IBM866 } else { WINDOWS_1251 };
+    enc.decode_without_bom_handling(bytes).0.into_owned()
+}
+
+fn parse_date(s: &str, key: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, DATE_FMT).map_err(|e| {
+        Diagnostic::new(ErrorCode::InvalidDate, format!("expected DD.MM.YYYY ({e})"))
+            .field(key)
+            .value(s)
+            .into()
+    })
+}
+
+fn parse_opt_date(s: &str, key: &str) -> Result<Option<NaiveDate>> {
+    if s.is_empty() {
+        Ok(None)
+    } else {
+        parse_date(s, key).map(Some)
+    }
+}
+
+fn parse_amount(s: &str, key: &str) -> Result<Decimal> {
+    s.replace(',', ".").parse().map_err(|e| {
+        Diagnostic::new(ErrorCode::InvalidAmount, format!("invalid amount ({e})"))
+            .field(key)
+            .value(s)
+            .into()
+    })
+}
+
+fn parse_opt_amount(s: &str, key: &str) -> Result<Option<Decimal>> {
+    if s.is_empty() {
+        Ok(None)
+    } else {
+        parse_amount(s, key).map(Some)
+    }
+}
+
+impl ReadFormat for OneC {
+    fn read_statements_with<R: BufRead>(
+        r: R,
+        mode: ParseMode,
+    ) -> Result<(Vec<Statement>, Vec<Diagnostic>)> {
+        let (ex, skipped) = Self::read_exchange_with(r, mode)?;
+        Ok((ex.statements(), skipped))
+    }
+}
+
+impl StreamRead for OneC {
+    type Reader<R: BufRead> = Box<dyn Iterator<Item = Result<StatementEvent>>>;
+
+    fn reader_with<R: BufRead>(r: R, mode: ParseMode) -> Self::Reader<R> {
+        match Self::read_statements_with(r, mode) {
+            Ok((sts, skipped)) => Box::new(
+                skipped
+                    .into_iter()
+                    .map(|d| Err(d.into()))
+                    .chain(stream::events(sts)),
+            ),
+            Err(e) => Box::new(std::iter::once(Err(e))),
+        }
+    }
+}
+
+/* ------------------------------- WRITE ---------------------------------- */
+
+impl OneC {
+    /// Пишет файл в Windows-1251 с переводами строк CRLF, как ждёт 1С;
+    /// символ, которого нет в Windows-1251, — ошибка.
+    pub fn write_exchange<W: Write>(w: W, ex: &OneCExchange) -> Result<()> {
+        let mut out = Lines::default();
+        out.line(MAGIC);
+        out.kv("ВерсияФормата", FORMAT_VERSION);
+        out.kv("Кодировка", "Windows");
+        out.kv("Отправитель", ex.sender.as_deref().unwrap_or("finio"));
+        out.kv("Получатель", ex.receiver.as_deref().unwrap_or(""));
+        if let Some(c) = ex.created {
+            out.kv("ДатаСоздания", &c.format(DATE_FMT).to_string());
+            out.kv("ВремяСоздания", &c.format("%H:%M:%S").to_string());
+        }
+        out.date("ДатаНачала", ex.date_from);
+        out.date("ДатаКонца", ex.date_to);
+        for acc in &ex.accounts {
+            out.kv("РасчСчет", &acc.account);
+        }
+
+        for acc in &ex.accounts {
+            out.line("СекцияРасчСчет");
+            out.date("ДатаНачала", acc.date_from);
+            out.date("ДатаКонца", acc.date_to);
+            out.kv("РасчСчет", &acc.account);
+            out.amount("НачальныйОстаток", acc.opening);
+            out.amount("ВсегоПоступило", acc.total_in);
+            out.amount("ВсегоСписано", acc.total_out);
+            out.amount("КонечныйОстаток", acc.closing);
+            out.line("КонецРасчСчет");
+        }
+
+        for doc in &ex.documents {
+            out.kv("СекцияДокумент", &doc.kind);
+            out.kv("Номер", doc.number.as_deref().unwrap_or(""));
+            out.date("Дата", Some(doc.date));
+            out.amount("Сумма", Some(doc.amount));
+            out.party("Плательщик", &doc.payer);
+            out.party("Получатель", &doc.payee);
+            out.date("ДатаСписано", doc.debited);
+            out.date("ДатаПоступило", doc.credited);
+            for (k, v) in &doc.extra {
+                out.kv(k, v);
+            }
+            out.kv("НазначениеПлатежа", &doc.purpose);
+            out.line("КонецДокумента");
+        }
+        out.line("КонецФайла");
+
+        // символ вне cp1251 — ошибка, а не &#NNNN; в назначении платежа
+        EncodingWriter::new(w, Encoding::Windows1251)?.write_all(out.0.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Lines(String);
+
+impl Lines {
+    fn line(&mut self, s: &str) {
+        self.0.push_str(s);
+        self.0.push_str("\r\n");
+    }
+
+    fn kv(&mut self, key: &str, value: &str) {
+        self.line(&format!("{key}={value}"));
+    }
+
+    fn date(&mut self, key: &str, d: Option<NaiveDate>) {
+        self.kv(key, &d.map(|d| d.format(DATE_FMT).to_string()).unwrap_or_default());
+    }
+
+    fn amount(&mut self, key: &str, a: Option<Decimal>) {
+        self.kv(key, &a.map(|a| format!("{:.2}", a)).unwrap_or_default());
+    }
+
+    fn party(&mut self, prefix: &str, p: &OneCParty) {
+        let v = |o: &Option<String>| o.clone().unwrap_or_default();
+        self.kv(&format!("{prefix}Счет"), &v(&p.account));
+        self.kv(prefix, &v(&p.name));
+        self.kv(&format!("{prefix}ИНН"), &v(&p.inn));
+        self.kv(&format!("{prefix}КПП"), &v(&p.kpp));
+        self.kv(&format!("{prefix}Банк1"), &v(&p.bank));
+        self.kv(&format!("{prefix}БИК"), &v(&p.bik));
+        self.kv(&format!("{prefix}Корсчет"), &v(&p.corr_account));
+    }
+}
+
+impl WriteFormat for OneC {
+    fn write_statements<W: Write>(w: W, sts: &[Statement]) -> Result<()> {
+        Self::write_exchange(w, &OneCExchange::from_statements(sts)?)
+    }
+}
+
+impl StreamWrite for OneC {
+    type Writer<W: Write> = Buffered<Self, W>;
+
+    fn writer<W: Write>(w: W) -> Self::Writer<W> {
+        Buffered::new(w)
+    }
+}
@@ -1,8 +1,25 @@
 //! Упрощённый XML (не CAMT!): <Statement><AccountId/><Entries>...</Entries></Statement>
+//!
+//! Одна выписка пишется как корневой `<XmlStatement>`, несколько — как
+//! `<XmlStatements>` с дочерними `<XmlStatement>`. Reader понимает оба варианта.
+//!
+//! Формат разбирается через serde целиком, поэтому потоковые reader/writer
+//! здесь — адаптеры поверх готовых выписок, без выигрыша по памяти.
 
-use crate::{error::{FinioError, Result}, model::{Statement, Entry, Balance, DebitCredit}};
-use chrono::NaiveDate;
-use quick_xml::{de::from_reader, se::to_string};
+use crate::{
+    account::{AccountId, Bic},
+    currency::Currency,
+    error::{Diagnostic, ErrorCode, FinioError, Location, Result},
+    model::{
+        Statement, Entry, EntryStatus, Balance, BankTxCode, Counterparty, DebitCredit,
+        Period, ProprietaryTxCode,
+        Remittance, TypedBalance,
+    },
+    stream::{self, Buffered, StatementEvent},
+    traits::{ParseMode, ReadFormat, StreamRead, StreamWrite},
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use quick_xml::{de::from_str, events::Event, se::to_string, Reader};
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, Write};
 
@@ -20,6 +37,46 @@ struct XmlEntry {
     description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     reference: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    reversal: bool,
+    /// pending/info; проведённые — без элемента
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    funds_code: Option<char>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    bank_reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    supplementary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    counterparty: Option<XmlCounterparty>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    bank_tx_code: Option<XmlBankTxCode>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct XmlCounterparty {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bic: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+}
+
+/// `iso` — "PMNT/RCDT/ESCT".
+#[derive(Serialize, Deserialize, Debug)]
+struct XmlBankTxCode {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iso: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    swift: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proprietary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issuer: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -30,126 +87,403 @@ struct XmlBalance {
     currency: String,
 }
 
+/// `kind` — `interim_closing`, `forward_available`… или код CAMT.
+#[derive(Serialize, Deserialize, Debug)]
+struct XmlTypedBalance {
+    kind: String,
+    dc: String,
+    date: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    amount: Decimal,
+    currency: String,
+}
+
+/// Границы — `YYYY-MM-DDTHH:MM:SS`.
+#[derive(Serialize, Deserialize, Debug)]
+struct XmlPeriod {
+    from: String,
+    to: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct XmlStatement {
     #[serde(skip_serializing_if = "Option::is_none")]
     statement_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    electronic_seq: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    legal_seq: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    last_page: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    created: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    period: Option<XmlPeriod>,
     account_id: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    account_currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    account_owner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    servicer_bic: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     opening_balance: Option<XmlBalance>,
     #[serde(skip_serializing_if = "Option::is_none")]
     closing_balance: Option<XmlBalance>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    balances: Vec<XmlTypedBalance>,
     entries: Vec<XmlEntry>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+struct XmlStatements {
+    #[serde(rename = "XmlStatement", default)]
+    statements: Vec<XmlStatement>,
+}
 
-fn parse_dc(s: &str) -> Result<DebitCredit> {
+
+fn parse_dc(s: &str, path: &str) -> Result<DebitCredit> {
     match s {
         "D" => Ok(DebitCredit::Debit),
         "C" => Ok(DebitCredit::Credit),
-        _ => Err(FinioError::Parse(format!("dc {s}"))),
+        _ => Err(invalid(ErrorCode::InvalidDebitCredit, "expected D or C", path, s)),
     }
 }
 
+fn parse_date(s: &str, path: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|e| invalid(ErrorCode::InvalidDate, &format!("expected YYYY-MM-DD ({e})"), path, s))
+}
+
+fn parse_datetime(s: &str, path: &str) -> Result<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
+        .map_err(|e| invalid(ErrorCode::InvalidDate, &format!("expected YYYY-MM-DDTHH:MM:SS ({e})"), path, s))
+}
+
+fn parse_ccy(s: &str, path: &str) -> Result<Currency> {
+    s.parse().map_err(|msg: String| invalid(ErrorCode::InvalidValue, &msg, path, s))
+}
+
+/// Ошибка значения элемента `path`; serde места в исходнике не сообщает,
+/// поэтому путь собирается по индексам выписки и проводки.
+fn invalid(code: ErrorCode, msg: &str, path: &str, value: &str) -> FinioError {
+    let field = path.rsplit('/').next().unwrap_or(path);
+    Diagnostic::new(code, msg)
+        .field(field)
+        .value(value)
+        .at(Location::Xml { path: path.to_string(), offset: None })
+        .into()
+}
+
 pub struct SimpleXml;
 
 // parse_dc оставить как было
 
-impl crate::traits::ReadFormat for SimpleXml {
-    fn read<R: BufRead>(r: R) -> Result<Statement> {
-        use crate::error::FinioError;
-        let x: XmlStatement = from_reader(r).map_err(|e| FinioError::Xml(format!("{e}")))?;
+impl ReadFormat for SimpleXml {
+    fn read_statements_with<R: BufRead>(
+        mut r: R,
+        mode: ParseMode,
+    ) -> Result<(Vec<Statement>, Vec<Diagnostic>)> {
+        let mut src = String::new();
+        r.read_to_string(&mut src)?;
 
-        // helper, чтобы не дублировать код
-        fn parse_xml_balance(b: XmlBalance) -> Result<Balance> {
-            Ok(Balance {
-                date: chrono::NaiveDate::parse_from_str(&b.date, "%Y-%m-%d")
-                    .map_err(|e| FinioError::Parse(format!("{e}")))?,
-                amount: b.amount,
-                currency: b.currency,
-            })
+        // по корню понимаем, одна выписка или пакет;
+        // ошибка serde (битая разметка, нет обязательного элемента) — всегда фатальна
+        let mut skipped = Vec::new();
+        let sts = if root_name(&src)?.as_deref() == Some("XmlStatements") {
+            from_str::<XmlStatements>(&src)
+                .map_err(|e| FinioError::Xml(format!("{e}")))?
+                .statements
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| {
+                    let base = format!("/XmlStatements/XmlStatement[{}]", i + 1);
+                    from_xml(x, &base, mode, &mut skipped)
+                })
+                .collect::<Result<_>>()?
+        } else {
+            let x = from_str(&src).map_err(|e| FinioError::Xml(format!("{e}")))?;
+            vec![from_xml(x, "/XmlStatement", mode, &mut skipped)?]
+        };
+        Ok((sts, skipped))
+    }
+}
+
+impl StreamRead for SimpleXml {
+    type Reader<R: BufRead> = Box<dyn Iterator<Item = Result<StatementEvent>>>;
+
+    fn reader_with<R: BufRead>(r: R, mode: ParseMode) -> Self::Reader<R> {
+        match Self::read_statements_with(r, mode) {
+            // диагностики известны до первого события — их и отдаём первыми
+            Ok((sts, skipped)) => Box::new(
+                skipped
+                    .into_iter()
+                    .map(|d| Err(d.into()))
+                    .chain(stream::events(sts)),
+            ),
+            Err(e) => Box::new(std::iter::once(Err(e))),
         }
+    }
+}
 
-        // Option<Result<Balance>> -> Result<Option<Balance>>
-        let opening: Option<Balance> = x.opening_balance
-            .map(parse_xml_balance)
-            .transpose()?;
+impl StreamWrite for SimpleXml {
+    type Writer<W: Write> = Buffered<Self, W>;
+
+    fn writer<W: Write>(w: W) -> Self::Writer<W> {
+        Buffered::new(w)
+    }
+}
 
-        let closing: Option<Balance> = x.closing_balance
-            .map(parse_xml_balance)
-            .transpose()?;
+fn root_name(src: &str) -> Result<Option<String>> {
+    let mut reader = Reader::from_str(src);
+    loop {
+        match reader.read_event().map_err(|e| FinioError::Xml(e.to_string()))? {
+            Event::Start(e) | Event::Empty(e) => {
+                return Ok(Some(String::from_utf8_lossy(e.local_name().as_ref()).into_owned()))
+            }
+            Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// В мягком режиме ошибка становится диагностикой в `skipped`, а значение — `None`.
+fn recover<T>(r: Result<T>, mode: ParseMode, skipped: &mut Vec<Diagnostic>) -> Result<Option<T>> {
+    match r {
+        Ok(v) => Ok(Some(v)),
+        Err(e) if mode == ParseMode::Lenient => {
+            skipped.push(e.into_diagnostic()?);
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// `base` — путь выписки для диагностики. В мягком режиме испорченная
+/// проводка пропускается, испорченный баланс опускается.
+fn from_xml(
+    x: XmlStatement,
+    base: &str,
+    mode: ParseMode,
+    skipped: &mut Vec<Diagnostic>,
+) -> Result<Statement> {
+    // helper, чтобы не дублировать код
+    let parse_xml_balance = |b: XmlBalance, name: &str| -> Result<Balance> {
+        Ok(Balance {
+            date: parse_date(&b.date, &format!("{base}/{name}/date"))?,
+            amount: b.amount,
+            currency: parse_ccy(&b.currency, &format!("{base}/{name}/currency"))?,
+        })
+    };
+
+    // Option<Result<Balance>> -> Result<Option<Balance>>
+    let opening: Option<Balance> = x.opening_balance
+        .map(|b| parse_xml_balance(b, "opening_balance"))
+        .and_then(|r| recover(r, mode, skipped).transpose())
+        .transpose()?;
+
+    let closing: Option<Balance> = x.closing_balance
+        .map(|b| parse_xml_balance(b, "closing_balance"))
+        .and_then(|r| recover(r, mode, skipped).transpose())
+        .transpose()?;
+
+    let mut balances = Vec::with_capacity(x.balances.len());
+    for (j, b) in x.balances.into_iter().enumerate() {
+        let at = format!("{base}/balances[{}]", j + 1);
+        let balance = (|| {
+            Ok(TypedBalance {
+                kind: b.kind.parse().map_err(|msg: String| {
+                    invalid(ErrorCode::InvalidValue, &msg, &format!("{at}/kind"), &b.kind)
+                })?,
+                dc: parse_dc(&b.dc, &format!("{at}/dc"))?,
+                date: parse_date(&b.date, &format!("{at}/date"))?,
+                amount: b.amount,
+                currency: parse_ccy(&b.currency, &format!("{at}/currency"))?,
+            })
+        })();
+        balances.extend(recover(balance, mode, skipped)?);
+    }
 
-        let mut entries = Vec::with_capacity(x.entries.len());
-        for e in x.entries {
-            let booking_date = NaiveDate::parse_from_str(&e.booking_date, "%Y-%m-%d")
-                .map_err(|e| FinioError::Parse(format!("{e}")))?;
+    let mut entries = Vec::with_capacity(x.entries.len());
+    for (j, e) in x.entries.into_iter().enumerate() {
+        let at = format!("{base}/entries[{}]", j + 1);
+        let entry = (|| {
+            let booking_date = parse_date(&e.booking_date, &format!("{at}/booking_date"))?;
             let value_date = match e.value_date {
-                Some(v) => Some(NaiveDate::parse_from_str(&v, "%Y-%m-%d")
-                    .map_err(|e| FinioError::Parse(format!("{e}")))?),
+                Some(v) => Some(parse_date(&v, &format!("{at}/value_date"))?),
                 None => None,
             };
             let amount = e.amount;
-            let dc = parse_dc(&e.dc)?;
+            let currency = parse_ccy(&e.currency, &format!("{at}/currency"))?;
+            let dc = parse_dc(&e.dc, &format!("{at}/dc"))?;
+            let status = match &e.status {
+                Some(v) => v.parse().map_err(|msg: String| {
+                    invalid(ErrorCode::InvalidValue, &msg, &format!("{at}/status"), v)
+                })?,
+                None => EntryStatus::Booked,
+            };
+            let bank_tx_code = match e.bank_tx_code {
+                Some(c) => Some(BankTxCode {
+                    iso: match c.iso {
+                        Some(v) => Some(v.parse().map_err(|msg: String| {
+                            invalid(ErrorCode::InvalidValue, &msg, &format!("{at}/bank_tx_code/iso"), &v)
+                        })?),
+                        None => None,
+                    },
+                    swift: c.swift,
+                    proprietary: c.proprietary.map(|code| ProprietaryTxCode { code, issuer: c.issuer }),
+                }),
+                None => None,
+            };
 
-            entries.push(Entry {
+            Ok(Entry {
                 booking_date,
                 value_date,
                 amount,
-                currency: e.currency,
+                currency,
                 dc,
                 description: e.description,
                 reference: e.reference,
-            });
-        }
-
-        Ok(Statement {
-            statement_id: x.statement_id,
-            account_id: x.account_id,
-            opening_balance: opening,
-            closing_balance: closing,
-            entries,
-        })
+                reversal: e.reversal,
+                status,
+                funds_code: e.funds_code,
+                bank_reference: e.bank_reference,
+                supplementary: e.supplementary,
+                counterparty: e.counterparty.map(|c| Counterparty {
+                    name: c.name,
+                    account: c.account,
+                    bic: c.bic.map(Bic::from),
+                    address: c.address,
+                    ..Counterparty::default()
+                }),
+                posting_text: None,
+                bank_tx_code,
+                remittance: Remittance::default(),
+                details: Vec::new(),
+            })
+        })();
+        entries.extend(recover(entry, mode, skipped)?);
     }
+
+    let period = match &x.period {
+        Some(p) => Some(Period {
+            from: parse_datetime(&p.from, &format!("{base}/period/from"))?,
+            to: parse_datetime(&p.to, &format!("{base}/period/to"))?,
+        }),
+        None => None,
+    };
+    let account_currency = match &x.account_currency {
+        Some(c) => Some(parse_ccy(c, &format!("{base}/account_currency"))?),
+        None => None,
+    };
+
+    Ok(Statement {
+        statement_id: x.statement_id,
+        electronic_seq: x.electronic_seq,
+        legal_seq: x.legal_seq,
+        page: x.page,
+        last_page: x.last_page,
+        created: match &x.created {
+            Some(c) => Some(parse_datetime(c, &format!("{base}/created"))?),
+            None => None,
+        },
+        created_offset: None,
+        period,
+        account_id: AccountId::parse(&x.account_id),
+        account_currency,
+        account_owner: x.account_owner,
+        servicer_bic: x.servicer_bic.map(Bic::from),
+        opening_balance: opening,
+        closing_balance: closing,
+        balances,
+        entries,
+        ..Statement::default()
+    })
 }
 
 
 impl crate::traits::WriteFormat for SimpleXml {
-    fn write<W: Write>(mut w: W, st: &Statement) -> Result<()> {
-        let opening = st.opening_balance.as_ref().map(|b| XmlBalance {
-            date: b.date.format("%Y-%m-%d").to_string(),
-            amount: b.amount,                         // ⬅️ Decimal
-            currency: b.currency.clone(),
-        });
-
-        let closing = st.closing_balance.as_ref().map(|b| XmlBalance {
-            date: b.date.format("%Y-%m-%d").to_string(),
-            amount: b.amount,                         // ⬅️ Decimal
-            currency: b.currency.clone(),
-        });
-
-        let entries = st.entries.iter().map(|e| XmlEntry {
-            booking_date: e.booking_date.format("%Y-%m-%d").to_string(),
-            value_date: e.value_date.map(|d| d.format("%Y-%m-%d").to_string()),
-            amount: e.amount,                         // ⬅️ Decimal
-            currency: e.currency.clone(),
-            dc: match e.dc { DebitCredit::Debit=>"D".into(), DebitCredit::Credit=>"C".into() },
-            description: e.description.clone(),
-            reference: e.reference.clone(),
-        }).collect();
-
-        let x = XmlStatement {
-            statement_id: st.statement_id.clone(),
-            account_id: st.account_id.clone(),
-            opening_balance: opening,
-            closing_balance: closing,
-            entries,
-        };
+    fn write_statements<W: Write>(mut w: W, sts: &[Statement]) -> Result<()> {
+        // одиночную выписку пишем в прежнем виде, чтобы не ломать потребителей
+        let s = match sts {
+            [st] => to_string(&to_xml(st)?),
+            _ => to_string(&XmlStatements { statements: sts.iter().map(to_xml).collect::<Result<_>>()? }),
+        }
+        .map_err(|e| FinioError::Xml(format!("{e}")))?;
 
         // 🔧 сериализуем в String и пишем в io::Write
-        let s = to_string(&x).map_err(|e| FinioError::Xml(format!("{e}")))?;
         w.write_all(s.as_bytes())?;
         Ok(())
     }
-
 }
 
+/// Суммы приводятся к числу знаков валюты, как в CSV; лишние знаки — ошибка.
+fn to_xml(st: &Statement) -> Result<XmlStatement> {
+    let balance = |b: &Balance| -> Result<XmlBalance> {
+        Ok(XmlBalance {
+            date: b.date.format("%Y-%m-%d").to_string(),
+            amount: b.currency.scale(b.amount)?,
+            currency: b.currency.to_string(),
+        })
+    };
+    let opening = st.opening_balance.as_ref().map(balance).transpose()?;
+    let closing = st.closing_balance.as_ref().map(balance).transpose()?;
+
+    let entries = st.entries.iter().map(|e| Ok(XmlEntry {
+        booking_date: e.booking_date.format("%Y-%m-%d").to_string(),
+        value_date: e.value_date.map(|d| d.format("%Y-%m-%d").to_string()),
+        amount: e.currency.scale(e.amount)?,
+        currency: e.currency.to_string(),
+        dc: match e.dc { DebitCredit::Debit=>"D".into(), DebitCredit::Credit=>"C".into() },
+        description: e.description.clone(),
+        reference: e.reference.clone(),
+        reversal: e.reversal,
+        status: (e.status != EntryStatus::Booked).then(|| e.status.to_string()),
+        funds_code: e.funds_code,
+        bank_reference: e.bank_reference.clone(),
+        supplementary: e.supplementary.clone(),
+        counterparty: e.counterparty.as_ref().map(|c| XmlCounterparty {
+            name: c.name.clone(),
+            account: c.account.clone(),
+            bic: c.bic.as_ref().map(ToString::to_string),
+            address: c.address.clone(),
+        }),
+        bank_tx_code: e.bank_tx_code.as_ref().map(|c| XmlBankTxCode {
+            iso: c.iso.as_ref().map(ToString::to_string),
+            swift: c.swift.clone(),
+            proprietary: c.proprietary.as_ref().map(|p| p.code.clone()),
+            issuer: c.proprietary.as_ref().and_then(|p| p.issuer.clone()),
+        }),
+    })).collect::<Result<_>>()?;
+
+    let balances = st.balances.iter().map(|b| Ok(XmlTypedBalance {
+        kind: b.kind.to_string(),
+        dc: match b.dc { DebitCredit::Debit => "D".into(), DebitCredit::Credit => "C".into() },
+        date: b.date.format("%Y-%m-%d").to_string(),
+        amount: b.currency.scale(b.amount)?,
+        currency: b.currency.to_string(),
+    })).collect::<Result<_>>()?;
+
+    Ok(XmlStatement {
+        statement_id: st.statement_id.clone(),
+        electronic_seq: st.electronic_seq,
+        legal_seq: st.legal_seq,
+        page: st.page,
+        last_page: st.last_page,
+        created: st.created.map(|c| c.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        period: st.period.map(|p| XmlPeriod {
+            from: p.from.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            to: p.to.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }),
+        account_id: st.account_id.to_string(),
+        account_currency: st.account_currency.map(|c| c.to_string()),
+        account_owner: st.account_owner.clone(),
+        servicer_bic: st.servicer_bic.as_ref().map(ToString::to_string),
+        opening_balance: opening,
+        closing_balance: closing,
+        balances,
+        entries,
+    })
+}
@@ -2,12 +2,26 @@
 
 pub mod error;
 pub mod model;
+pub mod account;
 pub mod traits;
 pub mod convert;
+pub mod currency;
+pub mod detect;
+pub mod encoding;
+pub mod stream;
+pub mod remittance;
+pub mod txcode;
+pub mod validate;
 
 pub mod formats {
     pub mod csv;
+    pub mod csv_profile;
     pub mod xml;
     pub mod mt940;
+    pub mod field86;
+    pub mod mt942;
+    pub mod camt052;
     pub mod camt053;
+    pub mod camt054;
+    pub mod onec;
 }
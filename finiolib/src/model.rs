@@ -1,8 +1,14 @@
 //! Доменные модели — единый «нормализованный» слой между форматами.
 
-use chrono::NaiveDate;
+use crate::{
+    account::{AccountId, Bic},
+    currency::Currency,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum DebitCredit {
@@ -10,29 +16,361 @@ pub enum DebitCredit {
     Credit,
 }
 
+/// Состояние проводки (CAMT `Ntry/Sts`).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum EntryStatus {
+    /// `BOOK` — проведена
+    #[default]
+    Booked,
+    /// `PDNG` — ожидает проведения; все проводки MT942
+    Pending,
+    /// `INFO` — только для сведения, на остаток не влияет
+    Information,
+}
+
+impl EntryStatus {
+    /// Код ISO 20022: `BOOK`, `PDNG`, `INFO`.
+    pub fn code(self) -> &'static str {
+        match self {
+            EntryStatus::Booked => "BOOK",
+            EntryStatus::Pending => "PDNG",
+            EntryStatus::Information => "INFO",
+        }
+    }
+}
+
+impl fmt::Display for EntryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EntryStatus::Booked => "booked",
+            EntryStatus::Pending => "pending",
+            EntryStatus::Information => "info",
+        })
+    }
+}
+
+/// Имя из `Display` или код ISO 20022.
+impl FromStr for EntryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "booked" | "BOOK" => Ok(EntryStatus::Booked),
+            "pending" | "PDNG" => Ok(EntryStatus::Pending),
+            "info" | "INFO" => Ok(EntryStatus::Information),
+            _ => Err(format!("unknown entry status {s:?} (booked, pending, info)")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Entry {
     pub booking_date: NaiveDate,
     pub value_date: Option<NaiveDate>,
     pub amount: Decimal,
-    pub currency: String,
+    pub currency: Currency,
     pub dc: DebitCredit,
     pub description: String,
     pub reference: Option<String>,
+    /// сторно (`RC`/`RD` в `:61:`, `RvslInd` в CAMT); `dc` — направление самой проводки
+    pub reversal: bool,
+    pub status: EntryStatus,
+    /// код средств `:61:` — третья буква кода валюты
+    pub funds_code: Option<char>,
+    /// референс банка (`//…` в `:61:`, `AcctSvcrRef` в CAMT)
+    pub bank_reference: Option<String>,
+    /// дополнительные сведения — вторая строка `:61:`
+    pub supplementary: Option<String>,
+    pub counterparty: Option<Counterparty>,
+    /// текст операции от банка (`?00` или `/TRTP/` в `:86:`)
+    pub posting_text: Option<String>,
+    /// код операции: ISO 20022 `BkTxCd`, SWIFT-тип из `:61:`, GVC
+    pub bank_tx_code: Option<BankTxCode>,
+    /// назначение платежа единственной операции; у пакетной — в `details`
+    pub remittance: Remittance,
+    /// операции внутри проводки (CAMT `NtryDtls/TxDtls`); у пакетной — несколько
+    pub details: Vec<TxDetails>,
+}
+
+/// Одна операция проводки. Контрагент и назначение единственной операции
+/// хранятся в `Entry`, а не здесь.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TxDetails {
+    /// сумма операции (`AmtDtls/TxAmt/Amt`); `None` — не указана
+    pub amount: Option<Decimal>,
+    pub currency: Option<Currency>,
+    pub refs: TxRefs,
+    pub remittance: Remittance,
+    pub counterparty: Option<Counterparty>,
+    pub dates: TxDates,
+}
+
+impl TxDetails {
+    pub fn is_empty(&self) -> bool {
+        self == &TxDetails::default()
+    }
+}
+
+/// Даты операции (`TxDtls/RltdDts`); проприетарные `Prtry` не поддерживаются.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TxDates {
+    /// `AccptncDtTm`
+    pub acceptance: Option<NaiveDateTime>,
+    /// `TradActvtyCtrctlSttlmDt`
+    pub trade_settlement: Option<NaiveDate>,
+    /// `TradDt`
+    pub trade: Option<NaiveDate>,
+    /// `IntrBkSttlmDt`
+    pub interbank_settlement: Option<NaiveDate>,
+    /// `StartDt`
+    pub start: Option<NaiveDate>,
+    /// `EndDt`
+    pub end: Option<NaiveDate>,
+    /// `TxDtTm`
+    pub transaction: Option<NaiveDateTime>,
+}
+
+impl TxDates {
+    pub fn is_empty(&self) -> bool {
+        self == &TxDates::default()
+    }
+}
+
+/// Референсы операции (`TxDtls/Refs`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TxRefs {
+    pub msg_id: Option<String>,
+    pub instr_id: Option<String>,
+    pub end_to_end_id: Option<String>,
+    pub tx_id: Option<String>,
+    /// идентификатор мандата прямого дебета
+    pub mandate_id: Option<String>,
+}
+
+/// Назначение платежа (`RmtInf`). Из MT940 текст назначения остаётся
+/// в `description`, сюда попадает только референс получателя.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Remittance {
+    /// строки `Ustrd`
+    pub unstructured: Vec<String>,
+    /// `Strd/CdtrRefInf`
+    pub creditor_reference: Option<CreditorReference>,
+}
+
+impl Remittance {
+    pub fn is_empty(&self) -> bool {
+        self.unstructured.is_empty() && self.creditor_reference.is_none()
+    }
+}
+
+/// Референс получателя: `SCOR` с эмитентом `ISO` — ISO 11649 (`RF18539007547034`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CreditorReference {
+    /// тип (`Tp/CdOrPrtry`): `SCOR`, `RPIN`… или проприетарный
+    pub kind: Option<String>,
+    pub issuer: Option<String>,
+    pub reference: String,
+}
+
+/// Код операции банка. Заполняется тем, что есть в источнике; недостающее
+/// представление выводится по таблице `txcode` при записи.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BankTxCode {
+    /// ISO 20022 Domain/Family/SubFamily
+    pub iso: Option<IsoTxCode>,
+    /// SWIFT-тип из `:61:`: `N`/`F` + код (`NTRF`, `FCHK`) или `S` + тип сообщения (`S103`)
+    pub swift: Option<String>,
+    /// `BkTxCd/Prtry`, GVC из немецкого `:86:` (эмитент `DK`)
+    pub proprietary: Option<ProprietaryTxCode>,
+}
+
+/// `PMNT/RCDT/ESCT`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct IsoTxCode {
+    pub domain: String,
+    pub family: String,
+    pub sub_family: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProprietaryTxCode {
+    pub code: String,
+    pub issuer: Option<String>,
+}
+
+/// Вторая сторона проводки: плательщик для кредита, получатель для дебета.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Counterparty {
+    pub name: Option<String>,
+    /// IBAN или номер счёта
+    pub account: Option<String>,
+    /// BIC банка контрагента (у российских банков — БИК)
+    pub bic: Option<Bic>,
+    /// адрес одной строкой
+    pub address: Option<String>,
+    /// ИНН (реквизиты 1С; другие форматы их не переносят)
+    pub tax_id: Option<String>,
+    /// КПП
+    pub tax_reason_code: Option<String>,
+    /// наименование банка (`Банк1` в 1С)
+    pub bank_name: Option<String>,
+    /// корреспондентский счёт банка
+    pub bank_corr_account: Option<String>,
+}
+
+impl Counterparty {
+    pub fn is_empty(&self) -> bool {
+        *self == Counterparty::default()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Balance {
     pub date: NaiveDate,
     pub amount: Decimal,
-    pub currency: String,
+    pub currency: Currency,
 }
 
+/// Вид остатка помимо входящего и исходящего (`opening_balance`/`closing_balance`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum BalanceKind {
+    /// `:60M:` — входящий остаток продолжения выписки, CAMT `PRCD`
+    InterimOpening,
+    /// `:62M:` — промежуточный исходящий, CAMT `ITBD`
+    InterimClosing,
+    /// `:64:` — доступный исходящий, CAMT `CLAV`
+    ClosingAvailable,
+    /// `:65:` — доступный на будущую дату, CAMT `FWAV`
+    ForwardAvailable,
+    /// прочие коды CAMT (`OPAV`, `ITAV`, `INFO`…); в MT940 не пишутся
+    Other(String),
+}
+
+impl fmt::Display for BalanceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BalanceKind::InterimOpening => "interim_opening",
+            BalanceKind::InterimClosing => "interim_closing",
+            BalanceKind::ClosingAvailable => "closing_available",
+            BalanceKind::ForwardAvailable => "forward_available",
+            BalanceKind::Other(code) => code,
+        })
+    }
+}
+
+/// Имя из `Display` или код CAMT. `ITBD` — всегда промежуточный исходящий:
+/// банки, пишущие им и входящий остаток продолжения, читаются с потерей вида.
+impl FromStr for BalanceKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "" => return Err("empty balance kind".into()),
+            "interim_opening" | "PRCD" => BalanceKind::InterimOpening,
+            "interim_closing" | "ITBD" => BalanceKind::InterimClosing,
+            "closing_available" | "CLAV" => BalanceKind::ClosingAvailable,
+            "forward_available" | "FWAV" => BalanceKind::ForwardAvailable,
+            code => BalanceKind::Other(code.to_string()),
+        })
+    }
+}
+
+/// Остаток из списка `Statement::balances`; сумма без знака, направление — в `dc`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TypedBalance {
+    pub kind: BalanceKind,
+    pub dc: DebitCredit,
+    pub date: NaiveDate,
+    pub amount: Decimal,
+    pub currency: Currency,
+}
+
+impl TypedBalance {
+    /// Из остатка со знаком (дебетовый — отрицательный).
+    pub fn new(kind: BalanceKind, b: &Balance) -> Self {
+        TypedBalance {
+            kind,
+            dc: if b.amount.is_sign_negative() { DebitCredit::Debit } else { DebitCredit::Credit },
+            date: b.date,
+            amount: b.amount.abs(),
+            currency: b.currency,
+        }
+    }
+
+    /// Остаток со знаком.
+    pub fn balance(&self) -> Balance {
+        Balance {
+            date: self.date,
+            amount: match self.dc {
+                DebitCredit::Credit => self.amount,
+                DebitCredit::Debit => -self.amount,
+            },
+            currency: self.currency,
+        }
+    }
+}
+
+/// Период выписки (`FrToDt`), границы включительно.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Period {
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+}
+
+impl Period {
+    /// Период из целых дней: с начала `from` до конца `to`.
+    pub fn days(from: NaiveDate, to: NaiveDate) -> Self {
+        let end = NaiveTime::from_hms_opt(23, 59, 59).expect("valid time");
+        Period { from: from.and_time(NaiveTime::MIN), to: to.and_time(end) }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Statement {
     pub statement_id: Option<String>,
-    pub account_id: String,
+    /// электронный порядковый номер (`ElctrncSeqNb`)
+    pub electronic_seq: Option<u64>,
+    /// номер выписки (`LglSeqNb`; в MT940 — первая часть `:28C:`)
+    pub legal_seq: Option<u64>,
+    /// номер страницы (`StmtPgntn/PgNb`; вторая часть `:28C:`)
+    pub page: Option<u32>,
+    /// последняя ли страница (`StmtPgntn/LastPgInd`)
+    pub last_page: Option<bool>,
+    /// дата и время формирования (`CreDtTm`), местное время банка
+    pub created: Option<NaiveDateTime>,
+    /// смещение `created` от UTC, если источник его указал (`:13D:` MT942,
+    /// `CreDtTm` с зоной); в CSV и XML не переносится
+    #[serde(skip)]
+    pub created_offset: Option<FixedOffset>,
+    pub period: Option<Period>,
+    pub account_id: AccountId,
+    /// валюта счёта (`Acct/Ccy`)
+    pub account_currency: Option<Currency>,
+    /// владелец счёта (`Acct/Ownr/Nm`)
+    pub account_owner: Option<String>,
+    /// BIC обслуживающего банка (`Acct/Svcr`)
+    pub servicer_bic: Option<Bic>,
+    /// ИНН и КПП владельца счёта (1С)
+    pub owner_tax_id: Option<String>,
+    pub owner_tax_reason_code: Option<String>,
+    /// наименование и корсчёт обслуживающего банка (1С)
+    pub servicer_name: Option<String>,
+    pub servicer_corr_account: Option<String>,
     pub opening_balance: Option<Balance>,
     pub closing_balance: Option<Balance>,
+    /// промежуточные, доступные и прочие остатки в порядке источника
+    pub balances: Vec<TypedBalance>,
     pub entries: Vec<Entry>,
 }
+
+impl Statement {
+    /// `created` с известным смещением.
+    pub fn created_at(&self) -> Option<DateTime<FixedOffset>> {
+        self.created_offset?.from_local_datetime(&self.created?).single()
+    }
+
+    /// Первый остаток вида `kind`.
+    pub fn balance(&self, kind: &BalanceKind) -> Option<&TypedBalance> {
+        self.balances.iter().find(|b| &b.kind == kind)
+    }
+}
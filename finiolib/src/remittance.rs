@@ -0,0 +1,48 @@
+//! Референс получателя ISO 11649: `RF`, две контрольные цифры и до 21 буквы
+//! или цифры. Проверка — как у IBAN: первые четыре символа переносятся в
+//! конец, буквы заменяются числами (`A` = 10 … `Z` = 35), остаток от деления
+//! на 97 должен быть равен 1.
+
+use crate::{account::mod97, model::CreditorReference};
+
+/// Тип референса ISO 11649 в `CdtrRefInf/Tp/CdOrPrtry/Cd`.
+pub const RF_KIND: &str = "SCOR";
+pub const RF_ISSUER: &str = "ISO";
+
+/// Контрольная сумма RF-референса; пробелы (печатная форма `RF18 5390 …`) не учитываются.
+pub fn is_valid_rf(s: &str) -> bool {
+    let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    let b = s.as_bytes();
+    if !(5..=25).contains(&b.len())
+        || !b[..2].eq_ignore_ascii_case(b"RF")
+        || !b[2..4].iter().all(u8::is_ascii_digit)
+        || !b[4..].iter().all(u8::is_ascii_alphanumeric)
+    {
+        return false;
+    }
+    mod97(b[4..].iter().chain(&b[..4])) == 1
+}
+
+/// Первый RF-референс с верной контрольной суммой в свободном тексте
+/// (`SVWZ+RF18539007547034` в немецком `:86:`).
+pub fn find_rf(text: &str) -> Option<&str> {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter_map(|w| w.find("RF").map(|i| &w[i..]))
+        .find(|w| is_valid_rf(w))
+}
+
+impl CreditorReference {
+    /// Референс ISO 11649 (`SCOR`, эмитент `ISO`).
+    pub fn rf(reference: &str) -> Self {
+        CreditorReference {
+            kind: Some(RF_KIND.into()),
+            issuer: Some(RF_ISSUER.into()),
+            reference: reference.to_string(),
+        }
+    }
+
+    /// Референс в форме ISO 11649 (префикс `RF`); `SCOR` бывает и у национальных.
+    pub fn is_rf(&self) -> bool {
+        self.reference.starts_with("RF")
+    }
+}
@@ -0,0 +1,212 @@
+//! Потоковое (pull) чтение и запись выписок.
+//!
+//! Reader отдаёт для каждой выписки последовательность событий
+//! `Header` → `Entry`* → `Trailer`, не собирая `Vec<Entry>` в памяти.
+//! Writer принимает те же события; `pump` соединяет одно с другим.
+//!
+//! Память ограничена одной проводкой не во всех форматах:
+//!
+//! - MT940, CSV, CAMT (052/053/054) — reader потоковый;
+//! - MT940 — writer потоковый; CSV и CAMT копят проводки выписки до trailer,
+//!   если закрывающий остаток не известен в заголовке (CAMT пишет `<Bal>`
+//!   до `<Ntry>`, CSV повторяет остаток в каждой строке). После MT940, где
+//!   `:62F:` идёт в конце, в памяти оказывается вся выписка;
+//! - simple XML, MT942, 1С — reader и writer разбирают и пишут файл целиком
+//!   (`Buffered`), в памяти весь вход или выход.
+
+use crate::{
+    error::{Diagnostic, ErrorCode, Result},
+    model::{Balance, Entry, Statement, TypedBalance},
+    traits::{ParseMode, StatementWriter, WriteFormat},
+};
+use std::io::Write;
+use std::marker::PhantomData;
+
+/// Событие потокового чтения.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementEvent {
+    /// Реквизиты выписки; `entries` всегда пуст. `closing_balance`
+    /// заполнен, только если формат сообщает его до проводок (CAMT, CSV).
+    /// Заголовок и проводка — в коробке: они намного больше trailer.
+    Header(Box<Statement>),
+    Entry(Box<Entry>),
+    Trailer(StatementTrailer),
+}
+
+/// То, что становится известно только после всех проводок.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatementTrailer {
+    pub closing_balance: Option<Balance>,
+    /// остатки после проводок (`:62M:`, `:64:`, `:65:`), которых не было в `Header`
+    pub balances: Vec<TypedBalance>,
+}
+
+/// Собирает поток событий в выписки (для небольших файлов и `read_statements`).
+pub fn collect<I>(events: I) -> Result<Vec<Statement>>
+where
+    I: Iterator<Item = Result<StatementEvent>>,
+{
+    let mut out: Vec<Statement> = Vec::new();
+    for ev in events {
+        match ev? {
+            StatementEvent::Header(st) => out.push(*st),
+            StatementEvent::Entry(e) => current(&mut out)?.entries.push(*e),
+            StatementEvent::Trailer(t) => {
+                let st = current(&mut out)?;
+                if t.closing_balance.is_some() {
+                    st.closing_balance = t.closing_balance;
+                }
+                st.balances.extend(t.balances);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// `collect` с учётом режима: в мягком режиме диагностики не прерывают
+/// сбор, а возвращаются списком; прочие ошибки (ввод-вывод) — по-прежнему ошибки.
+pub fn collect_with<I>(events: I, mode: ParseMode) -> Result<(Vec<Statement>, Vec<Diagnostic>)>
+where
+    I: Iterator<Item = Result<StatementEvent>>,
+{
+    match mode {
+        ParseMode::Strict => Ok((collect(events)?, Vec::new())),
+        ParseMode::Lenient => {
+            let mut skipped = Vec::new();
+            let sts = collect(skip_diagnostics(events, |d| skipped.push(d)))?;
+            Ok((sts, skipped))
+        }
+    }
+}
+
+/// Убирает из потока диагностики, передавая их в `on_skip`.
+pub fn skip_diagnostics<I, F>(events: I, mut on_skip: F) -> impl Iterator<Item = Result<StatementEvent>>
+where
+    I: Iterator<Item = Result<StatementEvent>>,
+    F: FnMut(Diagnostic),
+{
+    events.filter_map(move |ev| match ev {
+        Err(e) => match e.into_diagnostic() {
+            Ok(d) => {
+                on_skip(d);
+                None
+            }
+            Err(e) => Some(Err(e)),
+        },
+        ok => Some(ok),
+    })
+}
+
+fn current(out: &mut [Statement]) -> Result<&mut Statement> {
+    out.last_mut().ok_or_else(|| {
+        Diagnostic::new(ErrorCode::Structure, "entry outside of statement").into()
+    })
+}
+
+/// Раскладывает готовые выписки в события (обратная операция к `collect`).
+pub fn events(sts: Vec<Statement>) -> impl Iterator<Item = Result<StatementEvent>> {
+    sts.into_iter().flat_map(|mut st| {
+        let entries = std::mem::take(&mut st.entries);
+        // все остатки уже в заголовке
+        let trailer = StatementTrailer { closing_balance: st.closing_balance.clone(), balances: Vec::new() };
+        std::iter::once(StatementEvent::Header(Box::new(st)))
+            .chain(entries.into_iter().map(|e| StatementEvent::Entry(Box::new(e))))
+            .chain(std::iter::once(StatementEvent::Trailer(trailer)))
+            .map(Ok)
+    })
+}
+
+/// Пишет готовые выписки через потоковый writer.
+pub fn write_all<S: StatementWriter + ?Sized>(wr: &mut S, sts: &[Statement]) -> Result<()> {
+    for st in sts {
+        wr.begin(&header_of(st))?;
+        for e in &st.entries {
+            wr.entry(e)?;
+        }
+        wr.end(&StatementTrailer { closing_balance: st.closing_balance.clone(), balances: Vec::new() })?;
+    }
+    wr.finish()
+}
+
+/// Перекачивает события из reader в writer, не материализуя выписки.
+pub fn pump<I, S>(events: I, wr: &mut S) -> Result<()>
+where
+    I: Iterator<Item = Result<StatementEvent>>,
+    S: StatementWriter + ?Sized,
+{
+    for ev in events {
+        match ev? {
+            StatementEvent::Header(st) => wr.begin(&st)?,
+            StatementEvent::Entry(e) => wr.entry(&e)?,
+            StatementEvent::Trailer(t) => wr.end(&t)?,
+        }
+    }
+    wr.finish()
+}
+
+/// Копия выписки без проводок — то, что уходит в `Header`.
+pub fn header_of(st: &Statement) -> Statement {
+    Statement {
+        statement_id: st.statement_id.clone(),
+        electronic_seq: st.electronic_seq,
+        legal_seq: st.legal_seq,
+        page: st.page,
+        last_page: st.last_page,
+        created: st.created,
+        created_offset: st.created_offset,
+        period: st.period,
+        account_id: st.account_id.clone(),
+        account_currency: st.account_currency,
+        account_owner: st.account_owner.clone(),
+        servicer_bic: st.servicer_bic.clone(),
+        owner_tax_id: st.owner_tax_id.clone(),
+        owner_tax_reason_code: st.owner_tax_reason_code.clone(),
+        servicer_name: st.servicer_name.clone(),
+        servicer_corr_account: st.servicer_corr_account.clone(),
+        opening_balance: st.opening_balance.clone(),
+        closing_balance: st.closing_balance.clone(),
+        balances: st.balances.clone(),
+        entries: Vec::new(),
+    }
+}
+
+/// Writer для форматов без потоковой записи: копит выписки и пишет их
+/// целиком через `WriteFormat` в `finish` — память растёт со всем выходом.
+pub struct Buffered<F, W> {
+    w: W,
+    sts: Vec<Statement>,
+    _format: PhantomData<F>,
+}
+
+impl<F, W> Buffered<F, W> {
+    pub fn new(w: W) -> Self {
+        Self { w, sts: Vec::new(), _format: PhantomData }
+    }
+}
+
+impl<F: WriteFormat, W: Write> StatementWriter for Buffered<F, W> {
+    fn begin(&mut self, header: &Statement) -> Result<()> {
+        self.sts.push(header_of(header));
+        Ok(())
+    }
+
+    fn entry(&mut self, e: &Entry) -> Result<()> {
+        current(&mut self.sts)?.entries.push(e.clone());
+        Ok(())
+    }
+
+    fn end(&mut self, t: &StatementTrailer) -> Result<()> {
+        let st = current(&mut self.sts)?;
+        if t.closing_balance.is_some() {
+            st.closing_balance = t.closing_balance.clone();
+        }
+        st.balances.extend(t.balances.iter().cloned());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        F::write_statements(&mut self.w, &std::mem::take(&mut self.sts))?;
+        self.w.flush()?;
+        Ok(())
+    }
+}
@@ -1,15 +1,102 @@
 //! Унифицированные трэйты чтения/записи на основе std::io::{BufRead, Write}.
+//!
+//! Один файл может содержать несколько выписок (несколько блоков `:20:`…`:62F:`
+//! в MT940, несколько `<Stmt>` в CAMT.053), поэтому базовые методы работают
+//! с пакетом выписок, а `read`/`write` — удобные обёртки для одной выписки.
+//!
+//! В мягком режиме (`ParseMode::Lenient`) испорченная запись (строка `:61:`,
+//! строка CSV, `<Ntry>`, документ 1С) пропускается или заполняется частично,
+//! а её диагностика возвращается вместе с выписками. Потоковый reader в этом
+//! режиме отдаёт диагностику как `Err` и продолжает разбор.
 
-use crate::{error::Result, model::Statement};
+use crate::{
+    error::{Diagnostic, FinioError, Result},
+    model::{Entry, Statement},
+    stream::{StatementEvent, StatementTrailer},
+};
 use std::io::{BufRead, Write};
 
+/// Реакция reader'а на испорченную запись.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// первая же ошибка прерывает чтение
+    #[default]
+    Strict,
+    /// запись пропускается, ошибка становится предупреждением
+    Lenient,
+}
+
 pub trait ReadFormat {
-    fn read<R: BufRead>(r: R) -> Result<Statement>;
+    /// Читает все выписки в заданном режиме; вторым элементом — диагностики
+    /// пропущенных записей (в строгом режиме всегда пусто).
+    fn read_statements_with<R: BufRead>(
+        r: R,
+        mode: ParseMode,
+    ) -> Result<(Vec<Statement>, Vec<Diagnostic>)>;
+
+    /// Читает все выписки из потока в порядке их следования.
+    fn read_statements<R: BufRead>(r: R) -> Result<Vec<Statement>> {
+        Ok(Self::read_statements_with(r, ParseMode::Strict)?.0)
+    }
+
+    /// Читает ровно одну выписку. Если в потоке их несколько — ошибка,
+    /// чтобы не терять данные молча (используйте `read_statements`);
+    /// пустой поток — пустая выписка, как и раньше.
+    fn read<R: BufRead>(r: R) -> Result<Statement> {
+        let mut sts = Self::read_statements(r)?;
+        match sts.len() {
+            0 => Ok(Statement::default()),
+            1 => Ok(sts.remove(0)),
+            n => Err(FinioError::Parse(format!(
+                "expected a single statement, found {n}; use read_statements"
+            ))),
+        }
+    }
 }
 
 pub trait WriteFormat {
-    fn write<W: Write>(w: W, st: &Statement) -> Result<()>;
+    /// Пишет пакет выписок в один поток.
+    fn write_statements<W: Write>(w: W, sts: &[Statement]) -> Result<()>;
+
+    /// Пишет одну выписку.
+    fn write<W: Write>(w: W, st: &Statement) -> Result<()> {
+        Self::write_statements(w, std::slice::from_ref(st))
+    }
 }
 
 pub trait Format: ReadFormat + WriteFormat {}
 impl<T: ReadFormat + WriteFormat> Format for T {}
+
+/// Потоковый reader: `Header`, затем `Entry`*, затем `Trailer` для каждой выписки.
+pub trait StatementReader: Iterator<Item = Result<StatementEvent>> {}
+impl<T: Iterator<Item = Result<StatementEvent>>> StatementReader for T {}
+
+/// Приёмник событий потоковой записи.
+pub trait StatementWriter {
+    /// Начало выписки; `header.entries` не используется.
+    fn begin(&mut self, header: &Statement) -> Result<()>;
+    fn entry(&mut self, e: &Entry) -> Result<()>;
+    fn end(&mut self, trailer: &StatementTrailer) -> Result<()>;
+    /// Закрывает документ и сбрасывает буферы; вызывается один раз в конце.
+    fn finish(&mut self) -> Result<()>;
+}
+
+/// Потоковое чтение. Настоящий pull-парсер — у MT940, CSV и CAMT; остальные
+/// форматы читают вход целиком и раскладывают готовые выписки в события
+/// (см. `stream`).
+pub trait StreamRead {
+    type Reader<R: BufRead>: StatementReader;
+    fn reader_with<R: BufRead>(r: R, mode: ParseMode) -> Self::Reader<R>;
+
+    fn reader<R: BufRead>(r: R) -> Self::Reader<R> {
+        Self::reader_with(r, ParseMode::Strict)
+    }
+}
+
+/// Потоковая запись. Без копирования проводок пишет только MT940; CSV и CAMT
+/// держат проводки выписки, пока не известен закрывающий остаток, simple XML,
+/// MT942 и 1С — весь выход до `finish` (см. `stream`).
+pub trait StreamWrite {
+    type Writer<W: Write>: StatementWriter;
+    fn writer<W: Write>(w: W) -> Self::Writer<W>;
+}
@@ -0,0 +1,125 @@
+//! Соответствие кодов операций SWIFT (`:61:` MT940) и ISO 20022 (`BkTxCd`).
+//!
+//! В `:61:` тип операции — `N`/`F` и три буквы (`NTRF`, `FCHK`) либо `S` и
+//! номер сообщения SWIFT (`S103`). В ISO 20022 — тройка Domain/Family/SubFamily,
+//! где семейство зависит от направления: `RCDT` — полученный перевод, `ICDT` —
+//! отправленный. Код без пары в таблице не подменяется: SWIFT-тип уходит
+//! в CAMT как `Prtry` с эмитентом `SWIFT`, а ISO-код — в `Domn` без `:61:`-пары.
+//! В самом `:61:` тип обязателен, и для такого ISO-кода пишется `NMSC`.
+
+use crate::model::{BankTxCode, DebitCredit, IsoTxCode};
+use std::fmt;
+use std::str::FromStr;
+
+/// SWIFT-код без префикса → (ISO для кредита, ISO для дебета).
+/// Обратный поиск берёт первую подходящую строку.
+const TABLE: &[(&str, &str, &str)] = &[
+    // платежи
+    ("TRF", "PMNT/RCDT/ESCT", "PMNT/ICDT/ESCT"),
+    ("TRN", "PMNT/RCDT/DMCT", "PMNT/ICDT/DMCT"),
+    ("BOO", "PMNT/RCDT/BOOK", "PMNT/ICDT/BOOK"),
+    ("SAL", "PMNT/RCDT/SALA", "PMNT/ICDT/SALA"),
+    ("STO", "PMNT/RCDT/STDO", "PMNT/ICDT/STDO"),
+    ("RTI", "PMNT/RCDT/RRTN", "PMNT/ICDT/RRTN"),
+    ("CHK", "PMNT/RCHQ/CCHQ", "PMNT/ICHQ/CCHQ"),
+    ("CLR", "PMNT/RCHQ/BCHQ", "PMNT/ICHQ/BCHQ"),
+    ("TCK", "PMNT/RCHQ/TRCQ", "PMNT/ICHQ/TRCQ"),
+    ("DDT", "PMNT/RDDT/ESDD", "PMNT/IDDT/ESDD"),
+    ("COL", "PMNT/RDDT/OTHR", "PMNT/IDDT/OTHR"),
+    ("CAS", "PMNT/CNTR/CDPT", "PMNT/CNTR/CWDL"),
+    ("LBX", "PMNT/LBOX/OTHR", "PMNT/LBOX/OTHR"),
+    ("103", "PMNT/RCDT/XBCT", "PMNT/ICDT/XBCT"),
+    ("202", "PMNT/RCDT/FICT", "PMNT/ICDT/FICT"),
+    // счёт: комиссии, проценты, налоги
+    ("CHG", "ACMT/MCOP/CHRG", "ACMT/MDOP/CHRG"),
+    ("COM", "ACMT/MCOP/COMM", "ACMT/MDOP/COMM"),
+    ("INT", "ACMT/MCOP/INTR", "ACMT/MDOP/INTR"),
+    ("TAX", "ACMT/MCOP/TAXE", "ACMT/MDOP/TAXE"),
+    ("ADJ", "ACMT/MCOP/ADJT", "ACMT/MDOP/ADJT"),
+    // управление ликвидностью
+    ("CMI", "CAMT/ACCB/OTHR", "CAMT/ACCB/OTHR"),
+    ("CMS", "CAMT/ACCB/SWEP", "CAMT/ACCB/SWEP"),
+    ("CMT", "CAMT/ACCB/TOPG", "CAMT/ACCB/TOPG"),
+    ("CMZ", "CAMT/ACCB/ZABA", "CAMT/ACCB/ZABA"),
+    // валюта и ценные бумаги
+    ("FEX", "FORX/SPOT/OTHR", "FORX/SPOT/OTHR"),
+    ("DIV", "SECU/CORP/DVCA", "SECU/CORP/DVCA"),
+    ("CPN", "SECU/CORP/INTR", "SECU/CORP/INTR"),
+    ("RED", "SECU/CORP/REDM", "SECU/CORP/REDM"),
+    ("SEC", "SECU/SETT/TRAD", "SECU/SETT/TRAD"),
+    ("MSC", "XTND/NTAV/NTAV", "XTND/NTAV/NTAV"),
+];
+
+/// Код SWIFT для прочих операций.
+pub const SWIFT_MISC: &str = "NMSC";
+
+/// ISO-код по SWIFT-типу (`NTRF`, `FTRF`, `S103`) и направлению проводки.
+pub fn swift_to_iso(swift: &str, dc: DebitCredit) -> Option<IsoTxCode> {
+    let key = match swift.as_bytes() {
+        [b'N' | b'F', ..] | [b'S', b'0'..=b'9', ..] => swift.get(1..)?,
+        _ => return None,
+    };
+    let (_, credit, debit) = TABLE.iter().find(|(k, _, _)| *k == key)?;
+    let iso = match dc {
+        DebitCredit::Credit => credit,
+        DebitCredit::Debit => debit,
+    };
+    iso.parse().ok()
+}
+
+/// SWIFT-тип по ISO-коду; направление в `:61:` задаётся отдельно, поэтому
+/// сравниваются оба столбца таблицы. `None` — пары в таблице нет.
+pub fn iso_to_swift(iso: &IsoTxCode) -> Option<String> {
+    let code = iso.to_string();
+    TABLE
+        .iter()
+        .find(|(_, credit, debit)| *credit == code || *debit == code)
+        .map(|(k, _, _)| match k.as_bytes()[0] {
+            b'0'..=b'9' => format!("S{k}"),
+            _ => format!("N{k}"),
+        })
+}
+
+impl BankTxCode {
+    pub fn from_swift(code: &str) -> Self {
+        BankTxCode { swift: Some(code.to_string()), ..BankTxCode::default() }
+    }
+
+    /// ISO-код: свой или выведенный из SWIFT-типа.
+    pub fn iso_code(&self, dc: DebitCredit) -> Option<IsoTxCode> {
+        self.iso
+            .clone()
+            .or_else(|| self.swift.as_deref().and_then(|s| swift_to_iso(s, dc)))
+    }
+
+    /// SWIFT-тип: свой или выведенный из ISO-кода; `None`, если ISO-код
+    /// в таблице не найден.
+    pub fn swift_code(&self) -> Option<String> {
+        self.swift.clone().or_else(|| self.iso.as_ref().and_then(iso_to_swift))
+    }
+}
+
+impl fmt::Display for IsoTxCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.domain, self.family, self.sub_family)
+    }
+}
+
+/// "PMNT/RCDT/ESCT"
+impl FromStr for IsoTxCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('/');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(d), Some(f), Some(sf), None) if [d, f, sf].iter().all(|p| !p.is_empty()) => {
+                Ok(IsoTxCode {
+                    domain: d.to_string(),
+                    family: f.to_string(),
+                    sub_family: sf.to_string(),
+                })
+            }
+            _ => Err(format!("expected DOMAIN/FAMILY/SUBFAMILY, got {s:?}")),
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Проверка выписок: сходимость остатков, единая валюта, порядок дат,
+//! контрольные суммы RF-референсов, IBAN, счетов с БИК и форма BIC.
+//!
+//! `Validator` реализует `StatementWriter`, поэтому проверять можно прямо
+//! поток событий reader'а (`stream::pump`), не собирая выписки в памяти.
+
+use crate::{
+    account::AccountId,
+    currency::Currency,
+    error::Result,
+    remittance::is_valid_rf,
+    model::{Balance, BalanceKind, Counterparty, DebitCredit, Entry, EntryStatus, Statement, TypedBalance},
+    stream::{self, StatementTrailer},
+    traits::StatementWriter,
+};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FindingKind {
+    /// opening + Σ кредитов − Σ дебетов ≠ closing
+    BalanceMismatch { expected: Decimal, actual: Decimal },
+    /// нет входящего или исходящего остатка — сверить нечего
+    MissingBalance,
+    /// валюта остатка или проводки отличается от валюты выписки
+    CurrencyMismatch { expected: Currency, found: Currency },
+    /// входящий остаток датирован позже исходящего
+    BalanceDatesReversed { opening: NaiveDate, closing: NaiveDate },
+    /// дата проводки вне периода [opening.date, closing.date]
+    DateOutOfRange { date: NaiveDate, from: Option<NaiveDate>, to: Option<NaiveDate> },
+    /// RF-референс получателя (ISO 11649) с неверной контрольной суммой
+    InvalidCreditorReference { reference: String },
+    /// счёт выписки или контрагента не проходит проверку (`AccountId::check`)
+    InvalidAccount { account: String, reason: &'static str },
+    /// BIC не по ISO 9362 и не БИК
+    InvalidBic { bic: String },
+}
+
+impl FindingKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            FindingKind::MissingBalance
+            | FindingKind::InvalidCreditorReference { .. }
+            | FindingKind::InvalidAccount { .. }
+            | FindingKind::InvalidBic { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+/// Замечание к выписке `statement` (с нуля) и, если применимо, к проводке `entry`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub statement: usize,
+    pub entry: Option<usize>,
+    pub kind: FindingKind,
+}
+
+impl Finding {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sev = match self.severity() {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{sev}: statement #{}", self.statement + 1)?;
+        if let Some(i) = self.entry {
+            write!(f, ", entry #{}", i + 1)?;
+        }
+        f.write_str(": ")?;
+        match &self.kind {
+            FindingKind::BalanceMismatch { expected, actual } => write!(
+                f,
+                "closing balance {actual} does not match opening + movements = {expected}"
+            ),
+            FindingKind::MissingBalance => {
+                f.write_str("opening or closing balance missing, cannot reconcile")
+            }
+            FindingKind::CurrencyMismatch { expected, found } => {
+                write!(f, "currency {found} differs from statement currency {expected}")
+            }
+            FindingKind::BalanceDatesReversed { opening, closing } => {
+                write!(f, "opening balance date {opening} is after closing date {closing}")
+            }
+            FindingKind::DateOutOfRange { date, from, to } => {
+                let show = |d: &Option<NaiveDate>| d.map_or("..".to_string(), |d| d.to_string());
+                write!(f, "booking date {date} outside statement period {}..{}", show(from), show(to))
+            }
+            FindingKind::InvalidCreditorReference { reference } => {
+                write!(f, "creditor reference {reference} fails the ISO 11649 check")
+            }
+            FindingKind::InvalidAccount { account, reason } => {
+                write!(f, "account {account} is invalid: {reason}")
+            }
+            FindingKind::InvalidBic { bic } => write!(f, "BIC {bic} is not a valid ISO 9362 code"),
+        }
+    }
+}
+
+fn interim(balances: &[TypedBalance], kind: BalanceKind) -> Option<Balance> {
+    balances.iter().find(|b| b.kind == kind).map(TypedBalance::balance)
+}
+
+pub fn has_errors(findings: &[Finding]) -> bool {
+    findings.iter().any(|f| f.severity() == Severity::Error)
+}
+
+/// Проверяет готовые выписки.
+pub fn validate(sts: &[Statement]) -> Vec<Finding> {
+    let mut v = Validator::new();
+    // Validator не возвращает ошибок ввода-вывода
+    let _ = stream::write_all(&mut v, sts);
+    v.into_findings()
+}
+
+/// Потоковый валидатор: в памяти только суммы текущей выписки
+/// (и даты проводок, если исходящий остаток придёт лишь в trailer).
+#[derive(Debug, Default)]
+pub struct Validator {
+    findings: Vec<Finding>,
+    /// индекс следующей выписки
+    next_statement: usize,
+    opening: Option<Balance>,
+    closing: Option<Balance>,
+    currency: Option<Currency>,
+    /// Σ кредитов − Σ дебетов
+    movement: Decimal,
+    entries: usize,
+    deferred_dates: Vec<(usize, NaiveDate)>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    pub fn into_findings(self) -> Vec<Finding> {
+        self.findings
+    }
+
+    fn statement(&self) -> usize {
+        self.next_statement.saturating_sub(1)
+    }
+
+    fn push(&mut self, entry: Option<usize>, kind: FindingKind) {
+        self.findings.push(Finding { statement: self.statement(), entry, kind });
+    }
+
+    fn check_currency(&mut self, entry: Option<usize>, found: Currency) {
+        match self.currency {
+            Some(expected) if expected != found => {
+                self.push(entry, FindingKind::CurrencyMismatch { expected, found });
+            }
+            Some(_) => {}
+            None => self.currency = Some(found),
+        }
+    }
+
+    fn check_account(&mut self, entry: Option<usize>, id: &AccountId) {
+        if let Err(reason) = id.check() {
+            self.push(entry, FindingKind::InvalidAccount { account: id.to_string(), reason });
+        }
+    }
+
+    fn check_counterparty(&mut self, entry: usize, cp: &Counterparty) {
+        if let Some(id) = cp.account_id() {
+            self.check_account(Some(entry), &id);
+        }
+        if let Some(bic) = cp.bic.as_ref().filter(|b| !b.is_valid() && !b.is_bik()) {
+            self.push(Some(entry), FindingKind::InvalidBic { bic: bic.to_string() });
+        }
+    }
+
+    fn check_date(&mut self, entry: usize, date: NaiveDate) {
+        let from = self.opening.as_ref().map(|b| b.date);
+        let to = self.closing.as_ref().map(|b| b.date);
+        if from.is_some_and(|f| date < f) || to.is_some_and(|t| date > t) {
+            self.push(Some(entry), FindingKind::DateOutOfRange { date, from, to });
+        }
+    }
+}
+
+impl StatementWriter for Validator {
+    fn begin(&mut self, st: &Statement) -> Result<()> {
+        self.next_statement += 1;
+        // у продолжения выписки остатки промежуточные (:60M:/:62M:)
+        self.opening = st.opening_balance.clone().or_else(|| interim(&st.balances, BalanceKind::InterimOpening));
+        self.closing = st.closing_balance.clone().or_else(|| interim(&st.balances, BalanceKind::InterimClosing));
+        self.currency = None;
+        self.movement = Decimal::ZERO;
+        self.entries = 0;
+        self.deferred_dates.clear();
+
+        self.check_account(None, &st.account_id);
+        if let Some(b) = self.opening.clone() {
+            self.check_currency(None, b.currency);
+        }
+        Ok(())
+    }
+
+    fn entry(&mut self, e: &Entry) -> Result<()> {
+        let i = self.entries;
+        self.entries += 1;
+
+        // непроведённые и информационные проводки в остаток не входят
+        match (e.status, e.dc) {
+            (EntryStatus::Booked, DebitCredit::Credit) => self.movement += e.amount,
+            (EntryStatus::Booked, DebitCredit::Debit) => self.movement -= e.amount,
+            _ => {}
+        }
+        self.check_currency(Some(i), e.currency);
+        let refs = std::iter::once(&e.remittance)
+            .chain(e.details.iter().map(|d| &d.remittance))
+            .filter_map(|r| r.creditor_reference.as_ref());
+        for r in refs.filter(|r| r.is_rf() && !is_valid_rf(&r.reference)) {
+            self.push(Some(i), FindingKind::InvalidCreditorReference { reference: r.reference.clone() });
+        }
+        let parties = e.counterparty.iter().chain(e.details.iter().filter_map(|d| d.counterparty.as_ref()));
+        for cp in parties {
+            self.check_counterparty(i, cp);
+        }
+        if self.closing.is_some() {
+            self.check_date(i, e.booking_date);
+        } else {
+            self.deferred_dates.push((i, e.booking_date));
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, t: &StatementTrailer) -> Result<()> {
+        if let Some(b) = t.closing_balance.clone().or_else(|| interim(&t.balances, BalanceKind::InterimClosing)) {
+            self.closing = Some(b);
+        }
+
+        for (i, d) in std::mem::take(&mut self.deferred_dates) {
+            self.check_date(i, d);
+        }
+
+        if let Some(closing) = self.closing.clone() {
+            self.check_currency(None, closing.currency);
+        }
+
+        match (self.opening.clone(), self.closing.clone()) {
+            (Some(o), Some(c)) => {
+                if o.date > c.date {
+                    self.push(None, FindingKind::BalanceDatesReversed { opening: o.date, closing: c.date });
+                }
+                let expected = o.amount + self.movement;
+                if expected != c.amount {
+                    self.push(None, FindingKind::BalanceMismatch { expected, actual: c.amount });
+                }
+            }
+            _ => self.push(None, FindingKind::MissingBalance),
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
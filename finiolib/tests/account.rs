@@ -0,0 +1,86 @@
+use finiolib::{
+    account::{AccountId, Bic},
+    formats::{camt053::Camt053, mt940::Mt940},
+    model::{Counterparty, Statement},
+    traits::{ReadFormat, WriteFormat},
+    validate::{validate, FindingKind},
+};
+use std::io::Cursor;
+
+#[test]
+fn identifiers_are_classified_and_checked() {
+    let iban = AccountId::parse("nl91 abna 0417 1643 00");
+    assert_eq!(iban, AccountId::Iban("NL91ABNA0417164300".into()));
+    assert_eq!(iban.check(), Ok(()));
+    assert_eq!(AccountId::parse("NL91ABNA0417164301").check(), Err("IBAN check digits do not match"));
+    assert_eq!(AccountId::parse("DE8937040044053201300").check(), Err("IBAN length does not match country"));
+
+    let ru = AccountId::parse("044525225/40702810938000000001");
+    assert_eq!(ru.number(), "40702810938000000001");
+    assert_eq!(ru.check(), Ok(()));
+    assert!(AccountId::parse("044525225/40702810900000000001").check().is_err());
+    // корсчёт проверяется по 5–6 цифрам БИК
+    assert_eq!(AccountId::parse("044525225/30101810400000000225").check(), Ok(()));
+    assert_eq!(AccountId::parse("DE0012345678"), AccountId::Other { id: "DE0012345678".into(), scheme: None });
+
+    assert!(Bic::from("cobadeffxxx").is_valid());
+    assert!(Bic::from("044525225").is_bik());
+    assert!(!Bic::from("COBA DE").is_valid());
+}
+
+#[test]
+fn camt_chooses_iban_or_othr() {
+    let mut st = Statement { account_id: "044525225/40702810938000000001".into(), ..Statement::default() };
+    let write = |st: &Statement| {
+        let mut buf = Vec::new();
+        Camt053::write(&mut buf, st).unwrap();
+        String::from_utf8(buf).unwrap()
+    };
+
+    let xml = write(&st);
+    let flat: String = xml.lines().map(str::trim).collect();
+    assert!(flat.contains("<Acct><Id><Othr><Id>40702810938000000001</Id></Othr></Id><Svcr><FinInstnId>"));
+    assert!(flat.contains("<Cd>RUCBC</Cd></ClrSysId><MmbId>044525225</MmbId>"));
+    assert!(!flat.contains("<IBAN>"));
+    assert_eq!(Camt053::read(Cursor::new(xml)).unwrap().account_id, st.account_id);
+
+    st.account_id = AccountId::Other { id: "0532013000".into(), scheme: Some("BBAN".into()) };
+    let xml = write(&st);
+    assert!(xml.lines().map(str::trim).collect::<String>().contains("<SchmeNm><Cd>BBAN</Cd></SchmeNm>"));
+    assert_eq!(Camt053::read(Cursor::new(xml)).unwrap().account_id, st.account_id);
+
+    st.account_id = "NL91ABNA0417164300".into();
+    assert!(write(&st).contains("<IBAN>NL91ABNA0417164300</IBAN>"));
+
+    // BLZ из ?30 — не BIC: в ClrSysMmbId, а не в <BIC>
+    let mt = ":20:S1\n:25:DE89370400440532013000\n:28C:1\n:60F:C251001EUR100,00\n\
+              :61:2510011001C10,00NTRFNONREF\n:86:166?20Miete?3037040044?31532013000\n:62F:C251031EUR110,00\n";
+    let st = Mt940::read(Cursor::new(mt)).unwrap();
+    let flat: String = write(&st).lines().map(str::trim).collect();
+    assert!(flat.contains("<FinInstnId><ClrSysMmbId><MmbId>37040044</MmbId></ClrSysMmbId></FinInstnId>"));
+    assert!(!flat.contains("<BIC>37040044"));
+    let back = Camt053::read(Cursor::new(flat)).unwrap();
+    assert_eq!(back.entries[0].counterparty, st.entries[0].counterparty);
+}
+
+#[test]
+fn validator_reports_bad_identifiers() {
+    let src = ":20:S1\n:25:NL91ABNA0417164301\n:60F:C251001EUR100,00\n\
+               :61:2510011001C10,00NTRFNONREF\n:86:/NAME/X/IBAN/DE89370400440532013000/BIC/COBA-DE\n\
+               :62F:C251031EUR110,00\n";
+    let st = Mt940::read(Cursor::new(src)).unwrap();
+    let cp: &Counterparty = st.entries[0].counterparty.as_ref().unwrap();
+    assert_eq!(cp.account_id().unwrap().check(), Ok(()));
+
+    let kinds: Vec<_> = validate(&[st]).into_iter().map(|f| (f.entry, f.kind)).collect();
+    assert_eq!(
+        kinds,
+        [
+            (None, FindingKind::InvalidAccount {
+                account: "NL91ABNA0417164301".into(),
+                reason: "IBAN check digits do not match",
+            }),
+            (Some(0), FindingKind::InvalidBic { bic: "COBA-DE".into() }),
+        ]
+    );
+}
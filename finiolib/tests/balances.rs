@@ -0,0 +1,110 @@
+use finiolib::{
+    formats::{camt053::Camt053, csv::Csv, mt940::Mt940, xml::SimpleXml},
+    model::{BalanceKind, DebitCredit, Statement},
+    traits::{ReadFormat, WriteFormat},
+    validate::validate,
+};
+use rust_decimal_macros::dec;
+use std::io::Cursor;
+
+// вторая страница выписки: остатки промежуточные, после них доступные
+const MT: &str = ":20:S1
+:25:DE89370400440532013000
+:28C:7/2
+:60M:C251015EUR100,00
+:61:2510161016D30,00NTRFNONREF
+:86:rent
+:62M:C251016EUR70,00
+:64:C251016EUR65,00
+:65:C251017EUR60,00
+:65:D251018EUR5,00
+";
+
+fn kinds(st: &Statement) -> Vec<BalanceKind> {
+    st.balances.iter().map(|b| b.kind.clone()).collect()
+}
+
+#[test]
+fn mt940_reads_and_writes_all_balance_tags() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+    assert!(st.opening_balance.is_none() && st.closing_balance.is_none());
+    assert_eq!(
+        kinds(&st),
+        [
+            BalanceKind::InterimOpening,
+            BalanceKind::InterimClosing,
+            BalanceKind::ClosingAvailable,
+            BalanceKind::ForwardAvailable,
+            BalanceKind::ForwardAvailable,
+        ]
+    );
+    let fwd = &st.balances[4];
+    assert_eq!((fwd.dc, fwd.amount, fwd.balance().amount), (DebitCredit::Debit, dec!(5.00), dec!(-5.00)));
+    // сверка по промежуточным остаткам
+    assert!(validate(std::slice::from_ref(&st)).is_empty());
+
+    let mut buf = Vec::new();
+    Mt940::write(&mut buf, &st).unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains(":60M:C251015EUR100,00\n:61:"));
+    assert!(out.ends_with(":62M:C251016EUR70,00\n:64:C251016EUR65,00\n:65:C251017EUR60,00\n:65:D251018EUR5,00\n"));
+    assert_eq!(Mt940::read(Cursor::new(out)).unwrap(), st);
+}
+
+#[test]
+fn balances_survive_camt_xml_and_csv() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+
+    let mut camt = Vec::new();
+    Camt053::write(&mut camt, &st).unwrap();
+    let text = String::from_utf8(camt.clone()).unwrap();
+    let codes = ["PRCD", "ITBD", "CLAV", "FWAV"];
+    assert!(codes.iter().all(|c| text.contains(&format!("<Cd>{c}</Cd>"))));
+    assert_eq!(Camt053::read(Cursor::new(camt)).unwrap().balances, st.balances);
+
+    let mut xml = Vec::new();
+    SimpleXml::write(&mut xml, &st).unwrap();
+    assert_eq!(SimpleXml::read(Cursor::new(xml)).unwrap().balances, st.balances);
+
+    let mut csv = Vec::new();
+    Csv::write(&mut csv, &st).unwrap();
+    assert_eq!(Csv::read(Cursor::new(csv)).unwrap().balances, st.balances);
+}
+
+#[test]
+fn camt_other_balance_codes_are_kept() {
+    let src = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+<BkToCstmrStmt><Stmt><Id>S1</Id>
+<Bal><Tp><CdOrPrtry><Cd>OPAV</Cd></CdOrPrtry></Tp><Amt Ccy="EUR">12.50</Amt><CdtDbtInd>DBIT</CdtDbtInd><Dt><Dt>2025-10-01</Dt></Dt></Bal>
+</Stmt></BkToCstmrStmt></Document>"#;
+    let st = Camt053::read(Cursor::new(src)).unwrap();
+    let b = &st.balances[0];
+    assert_eq!(b.kind, BalanceKind::Other("OPAV".into()));
+    assert_eq!((b.dc, b.amount, b.currency.code()), (DebitCredit::Debit, dec!(12.50), "EUR"));
+}
+
+#[test]
+fn camt_keeps_interim_kind_without_guessing() {
+    // первая страница: :60F: и :62M:, вторая — :60M: и :62F:
+    let first = MT.replace(":28C:7/2\n:60M:", ":28C:7/1\n:60F:");
+    let mut st = Mt940::read(Cursor::new(first)).unwrap();
+    st.balances.retain(|b| b.kind == BalanceKind::InterimClosing);
+    // одинокий промежуточный исходящий раньше читался как входящий
+    let mut lone = st.clone();
+    lone.opening_balance = None;
+    for st in [st, lone] {
+        let mut camt = Vec::new();
+        Camt053::write(&mut camt, &st).unwrap();
+        let back = Camt053::read(Cursor::new(camt)).unwrap();
+        assert_eq!(kinds(&back), [BalanceKind::InterimClosing]);
+        let mut mt = Vec::new();
+        Mt940::write(&mut mt, &back).unwrap();
+        assert_eq!(Mt940::read(Cursor::new(mt)).unwrap().balances, st.balances);
+    }
+
+    let src = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+<BkToCstmrStmt><Stmt><Id>S1</Id>
+<Bal><Tp><CdOrPrtry><Cd>PRCD</Cd></CdOrPrtry></Tp><Amt Ccy="EUR">1.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Dt><Dt>2025-10-01</Dt></Dt></Bal>
+</Stmt></BkToCstmrStmt></Document>"#;
+    assert_eq!(kinds(&Camt053::read(Cursor::new(src)).unwrap()), [BalanceKind::InterimOpening]);
+}
@@ -0,0 +1,44 @@
+use chrono::NaiveDate;
+use finiolib::{
+    formats::camt053::Camt053,
+    model::BalanceKind,
+    traits::{ReadFormat, WriteFormat},
+};
+use rust_decimal_macros::dec;
+use std::io::Cursor;
+
+const SRC: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.08">
+<BkToCstmrStmt><Stmt><Id>S1</Id><CreDtTm>2025-11-01T08:00:00</CreDtTm>
+<Acct><Id><IBAN>DE89370400440532013000</IBAN></Id></Acct>
+<Bal><Tp><CdOrPrtry><Cd>OPBD</Cd></CdOrPrtry></Tp><Amt Ccy="EUR">10.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Dt><Dt>2025-10-01</Dt></Dt></Bal>
+<Bal><Tp><CdOrPrtry><Cd>CLBD</Cd></CdOrPrtry></Tp><Amt Ccy="EUR">20.00</Amt><CdtDbtInd>DBIT</CdtDbtInd><Dt><DtTm>2025-10-31T23:59:59+01:00</DtTm></Dt></Bal>
+<Bal><Tp><CdOrPrtry><Cd>CLAV</Cd></CdOrPrtry></Tp><Amt Ccy="EUR">25.00</Amt><CdtDbtInd>DBIT</CdtDbtInd><Dt><Dt>2025-10-31</Dt></Dt></Bal>
+<Ntry><Amt Ccy="EUR">30.00</Amt><CdtDbtInd>DBIT</CdtDbtInd><Sts><Cd>BOOK</Cd></Sts><BookgDt><Dt>2025-10-15</Dt></BookgDt></Ntry>
+</Stmt></BkToCstmrStmt></Document>"#;
+
+#[test]
+fn bal_fills_opening_and_closing() {
+    let st = Camt053::read(Cursor::new(SRC)).unwrap();
+    let opening = st.opening_balance.as_ref().unwrap();
+    assert_eq!((opening.amount, opening.date), (dec!(10.00), NaiveDate::from_ymd_opt(2025, 10, 1).unwrap()));
+    // DBIT — отрицательный остаток; DtTm сводится к дате
+    let closing = st.closing_balance.as_ref().unwrap();
+    assert_eq!((closing.amount, closing.date), (dec!(-20.00), NaiveDate::from_ymd_opt(2025, 10, 31).unwrap()));
+    assert_eq!(st.balance(&BalanceKind::ClosingAvailable).unwrap().balance().amount, dec!(-25.00));
+    // Amt остатка не принимается за сумму проводки
+    assert_eq!(st.entries.len(), 1);
+    assert_eq!(st.entries[0].amount, dec!(30.00));
+}
+
+#[test]
+fn camt_to_camt_is_lossless() {
+    let st = Camt053::read(Cursor::new(SRC)).unwrap();
+    let mut buf = Vec::new();
+    Camt053::write(&mut buf, &st).unwrap();
+    let xml = String::from_utf8(buf).unwrap();
+    let flat: String = xml.lines().map(str::trim).collect();
+    assert!(flat.contains(r#"<Cd>CLBD</Cd></CdOrPrtry></Tp><Amt Ccy="EUR">20.00</Amt><CdtDbtInd>DBIT</CdtDbtInd>"#));
+    assert!(!xml.contains(">-"));
+    assert_eq!(Camt053::read(Cursor::new(xml.as_bytes())).unwrap(), st);
+}
@@ -0,0 +1,43 @@
+use finiolib::{
+    detect::{detect, FormatKind},
+    formats::{camt052::Camt052, camt053::Camt053, camt054::Camt054, mt940::Mt940},
+    traits::{ReadFormat, WriteFormat},
+};
+use std::io::Cursor;
+
+const MT: &str = include_str!("../../examples/sample.mt940");
+
+#[test]
+fn camt052_roundtrip() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+    let mut out = Vec::new();
+    Camt052::write(&mut out, &st).unwrap();
+
+    let text = String::from_utf8(out.clone()).unwrap();
+    assert!(text.contains("camt.052.001.02"));
+    assert!(text.contains("<BkToCstmrAcctRpt>") && text.contains("<Rpt>"));
+    assert!(text.contains("OPBD"));
+    assert_eq!(detect(&out).unwrap().format, FormatKind::Camt052);
+
+    let back = Camt052::read(Cursor::new(&out)).unwrap();
+    assert_eq!(back.account_id, st.account_id);
+    assert_eq!(back.entries, st.entries);
+
+    // чужой элемент выписки не принимается за <Stmt>
+    assert!(Camt053::read_statements(Cursor::new(&out)).is_err());
+}
+
+#[test]
+fn camt054_has_no_balances() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+    let mut out = Vec::new();
+    Camt054::write(&mut out, &st).unwrap();
+
+    let text = String::from_utf8(out.clone()).unwrap();
+    assert!(text.contains("<BkToCstmrDbtCdtNtfctn>") && text.contains("<Ntfctn>"));
+    assert!(!text.contains("<Bal>"));
+    assert_eq!(detect(&out).unwrap().format, FormatKind::Camt054);
+
+    let back = Camt054::read(Cursor::new(&out)).unwrap();
+    assert_eq!(back.entries, st.entries);
+}
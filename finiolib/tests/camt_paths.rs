@@ -0,0 +1,48 @@
+use finiolib::{
+    error::ErrorCode,
+    formats::camt053::Camt053,
+    traits::ReadFormat,
+};
+use rust_decimal_macros::dec;
+use std::io::Cursor;
+
+fn doc(stmt: &str) -> String {
+    format!(
+        r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+<BkToCstmrStmt><GrpHdr><MsgId>M1</MsgId></GrpHdr><Stmt>{stmt}</Stmt></BkToCstmrStmt></Document>"#
+    )
+}
+
+#[test]
+fn fields_are_scoped_to_their_paths() {
+    let src = doc(r#"<Id>S1</Id><Acct><Id><Othr><Id>40702810900000000001</Id></Othr></Id></Acct>
+<Bal><Tp><CdOrPrtry><Cd>OPBD</Cd></CdOrPrtry></Tp><Amt Ccy="RUB">100.00</Amt>
+<CdtDbtInd>CRDT</CdtDbtInd><Dt><Dt>2025-10-01</Dt></Dt></Bal>
+<Ntry><Amt Ccy="RUB">10.00</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+<BookgDt><Dt>2025-10-02</Dt></BookgDt>
+<NtryDtls><TxDtls><Refs><EndToEndId>E2E</EndToEndId></Refs>
+<AmtDtls><InstdAmt><Amt Ccy="USD">0.11</Amt></InstdAmt></AmtDtls>
+<Chrgs><Amt Ccy="RUB">1.00</Amt></Chrgs></TxDtls></NtryDtls>
+<AddtlNtryInf>оплата</AddtlNtryInf></Ntry>"#);
+    let st = Camt053::read(Cursor::new(src)).unwrap();
+    assert_eq!(st.statement_id.as_deref(), Some("S1"));
+    assert_eq!(st.account_id, "40702810900000000001");
+    assert_eq!(st.opening_balance.as_ref().unwrap().amount, dec!(100.00));
+    let e = &st.entries[0];
+    assert_eq!((e.amount, e.currency.code()), (dec!(10.00), "RUB"));
+    assert_eq!(e.booking_date.to_string(), "2025-10-02");
+    assert_eq!(e.description, "оплата");
+}
+
+#[test]
+fn misplaced_elements_are_structure_errors() {
+    for src in [
+        doc("<Id>S1</Id><Acct><Ntry><Amt Ccy=\"EUR\">1.00</Amt></Ntry></Acct>"),
+        doc("<Id>S1</Id><Ntry><Ntry/></Ntry>"),
+        r#"<Document><BkToCstmrAcctRpt><Rpt><Id>R1</Id></Rpt></BkToCstmrAcctRpt></Document>"#.into(),
+        r#"<BkToCstmrStmt><Stmt><Id>S1</Id></Stmt></BkToCstmrStmt>"#.into(),
+    ] {
+        let err = Camt053::read_statements(Cursor::new(&src)).unwrap_err();
+        assert_eq!(err.diagnostic().map(|d| d.code), Some(ErrorCode::Structure), "{src}");
+    }
+}
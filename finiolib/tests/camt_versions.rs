@@ -0,0 +1,68 @@
+use finiolib::{
+    formats::{
+        camt052::Camt052,
+        camt053::{Camt053, CamtMessage, CamtVersion},
+        mt940::Mt940,
+    },
+    stream,
+    traits::{ReadFormat, StreamRead, StreamWrite, WriteFormat},
+};
+use std::io::Cursor;
+
+const MT: &str = include_str!("../../examples/sample.mt940");
+
+#[test]
+fn version_parse_and_namespace() {
+    assert_eq!("001.08".parse::<CamtVersion>().unwrap(), CamtVersion::V08);
+    assert_eq!("8".parse::<CamtVersion>().unwrap(), CamtVersion::V08);
+    assert!("x".parse::<CamtVersion>().is_err());
+    assert_eq!(CamtVersion::V04.to_string(), "001.04");
+
+    let ns = CamtVersion::V08.namespace(CamtMessage::Statement);
+    assert_eq!(ns, "urn:iso:std:iso:20022:tech:xsd:camt.053.001.08");
+    assert_eq!(
+        CamtVersion::from_namespace(&ns),
+        Some((CamtMessage::Statement, CamtVersion::V08))
+    );
+    assert_eq!(CamtVersion::from_namespace("urn:foo"), None);
+}
+
+#[test]
+fn write_and_read_v08() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+    let mut out = Vec::new();
+    let mut wr = Camt053::writer(&mut out).version(CamtVersion::V08);
+    stream::write_all(&mut wr, std::slice::from_ref(&st)).unwrap();
+    drop(wr);
+
+    let text = String::from_utf8(out.clone()).unwrap();
+    assert!(text.contains("camt.053.001.08"));
+    assert!(text.contains("<Cd>BOOK</Cd>"));
+
+    let (sts, version) = Camt053::reader(Cursor::new(&out)).into_statements().unwrap();
+    assert_eq!(version, Some(CamtVersion::V08));
+    assert_eq!(sts[0].entries, st.entries);
+
+    // по умолчанию — 001.02 с кодом статуса без обёртки
+    let mut out = Vec::new();
+    Camt052::write_statements(&mut out, &[st]).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("camt.052.001.02") && text.contains("<Sts>BOOK</Sts>"));
+}
+
+#[test]
+fn reads_datetime_booking_dates() {
+    let xml = r#"<?xml version="1.0"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.06">
+<BkToCstmrStmt><Stmt><Id>S1</Id><Acct><Id><IBAN>DE00</IBAN></Id></Acct>
+<Ntry><Amt Ccy="EUR">1.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Sts>BOOK</Sts>
+<BookgDt><DtTm>2025-03-04T10:15:00+01:00</DtTm></BookgDt>
+<ValDt><Dt>2025-03-05</Dt></ValDt></Ntry>
+</Stmt></BkToCstmrStmt></Document>"#;
+
+    let (sts, version) = Camt053::reader(Cursor::new(xml)).into_statements().unwrap();
+    assert_eq!(version, Some(CamtVersion::V06));
+    let e = &sts[0].entries[0];
+    assert_eq!(e.booking_date.to_string(), "2025-03-04");
+    assert_eq!(e.value_date.unwrap().to_string(), "2025-03-05");
+}
@@ -1,4 +1,5 @@
 use finiolib::{
+    currency::Currency,
     formats::camt053::Camt053,
     model::{Statement, Entry, Balance, DebitCredit},
     traits::{ReadFormat, WriteFormat},
@@ -15,28 +16,46 @@ fn camt_write_then_read_back() {
         opening_balance: Some(Balance {
             date: NaiveDate::from_ymd_opt(2025,10,1).unwrap(),
             amount: Decimal::from_str_exact("1000.00").unwrap(),
-            currency: "EUR".into(),
+            currency: Currency::EUR,
         }),
         closing_balance: Some(Balance {
             date: NaiveDate::from_ymd_opt(2025,10,31).unwrap(),
             amount: Decimal::from_str_exact("1100.00").unwrap(),
-            currency: "EUR".into(),
+            currency: Currency::EUR,
         }),
+        balances: vec![],
         entries: vec![Entry{
             booking_date: NaiveDate::from_ymd_opt(2025,10,1).unwrap(),
             value_date: Some(NaiveDate::from_ymd_opt(2025,10,1).unwrap()),
             amount: Decimal::from_str_exact("100.00").unwrap(),
-            currency: "EUR".into(),
+            currency: Currency::EUR,
             dc: DebitCredit::Credit,
             description: "Salary October".into(),
             reference: None,
+            reversal: false,
+            status: Default::default(),
+            funds_code: None,
+            bank_reference: None,
+            supplementary: None,
+            counterparty: None,
+            posting_text: None,
+            bank_tx_code: None,
+            remittance: Default::default(),
+            details: vec![],
         }],
+        ..Statement::default()
     };
 
     // write to XML
     let mut buf = Vec::new();
     Camt053::write(&mut buf, &st).expect("write camt");
 
+    // порядок по XSD: Sts, BookgDt, ValDt
+    let xml: String = String::from_utf8(buf.clone()).unwrap().lines().map(str::trim).collect();
+    assert!(xml.contains(
+        "<Sts>BOOK</Sts><BookgDt><Dt>2025-10-01</Dt></BookgDt><ValDt><Dt>2025-10-01</Dt></ValDt>"
+    ));
+
     // read back
     let st2 = Camt053::read(Cursor::new(buf)).expect("read camt");
     assert_eq!(st2.account_id, st.account_id);
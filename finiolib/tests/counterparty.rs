@@ -0,0 +1,100 @@
+use finiolib::{
+    formats::{
+        camt053::{Camt053, CamtVersion},
+        csv::Csv,
+        mt940::Mt940,
+        onec::OneC,
+        xml::SimpleXml,
+    },
+    model::Counterparty,
+    stream,
+    traits::{ReadFormat, StreamWrite, WriteFormat},
+};
+use std::io::Cursor;
+
+const MT: &str = ":20:S1
+:25:DE89370400440532013000
+:28C:1
+:60F:C251001EUR100,00
+:61:2510011001C10,00NTRFNONREF
+:86:166?00SEPA-GUTSCHRIFT?20Rechnung 4711 vom 01.10.20
+?2125?30COBADEFFXXX?31DE02120300000000202051
+?32Muster GmbH
+:61:2510021002D5,00NTRFNONREF
+:86:plain text
+:62F:C251031EUR105,00
+";
+
+fn muster() -> Counterparty {
+    Counterparty {
+        name: Some("Muster GmbH".into()),
+        account: Some("DE02120300000000202051".into()),
+        bic: Some("COBADEFFXXX".into()),
+        address: None,
+        ..Counterparty::default()
+    }
+}
+
+#[test]
+fn mt940_structured_86() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+    let (inc, out) = (&st.entries[0], &st.entries[1]);
+    assert_eq!(inc.description, "Rechnung 4711 vom 01.10.2025");
+    assert_eq!(inc.counterparty, Some(muster()));
+    assert_eq!(out.description, "plain text");
+    assert_eq!(out.counterparty, None);
+
+    let mut buf = Vec::new();
+    Mt940::write(&mut buf, &st).unwrap();
+    assert_eq!(Mt940::read(Cursor::new(&buf)).unwrap(), st);
+}
+
+#[test]
+fn camt_roundtrip_across_versions() {
+    let mut st = Mt940::read(Cursor::new(MT)).unwrap();
+    st.entries[1].counterparty = Some(Counterparty {
+        name: Some("ООО Ромашка".into()),
+        account: Some("40702810900000000002".into()),
+        address: Some("Москва, ул. Ленина, 1".into()),
+        bic: None,
+        ..Counterparty::default()
+    });
+
+    for v in [CamtVersion::V02, CamtVersion::V08] {
+        let mut buf = Vec::new();
+        stream::write_all(&mut Camt053::writer(&mut buf).version(v), std::slice::from_ref(&st)).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        // кредит — плательщик в Dbtr, дебет — получатель в Cdtr
+        assert!(xml.contains("<DbtrAcct>") && xml.contains("<Othr>"));
+        assert_eq!(xml.contains("<Pty>"), v == CamtVersion::V08);
+        assert_eq!(xml.contains("<BICFI>"), v == CamtVersion::V08);
+        let back = Camt053::read(Cursor::new(xml.as_bytes())).unwrap();
+        assert_eq!(back.entries[0].counterparty, Some(muster()));
+        assert_eq!(back.entries[1].counterparty, st.entries[1].counterparty);
+    }
+}
+
+#[test]
+fn csv_xml_and_1c_keep_counterparty() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+    for back in [
+        {
+            let mut buf = Vec::new();
+            Csv::write(&mut buf, &st).unwrap();
+            Csv::read(Cursor::new(buf)).unwrap()
+        },
+        {
+            let mut buf = Vec::new();
+            SimpleXml::write(&mut buf, &st).unwrap();
+            SimpleXml::read(Cursor::new(buf)).unwrap()
+        },
+    ] {
+        assert_eq!(back.entries[0].counterparty, Some(muster()));
+    }
+
+    // 1С: контрагент — другая сторона документа
+    let st = OneC::read(Cursor::new(include_bytes!("../../examples/sample_1c.txt"))).unwrap();
+    let cp = st.entries[0].counterparty.as_ref().unwrap();
+    assert!(cp.account.is_some() && cp.name.is_some());
+    assert_ne!(cp.account.as_deref(), Some(st.account_id.number()));
+}
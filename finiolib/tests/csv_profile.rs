@@ -0,0 +1,88 @@
+use finiolib::{
+    error::ErrorCode,
+    formats::{
+        csv::Csv,
+        csv_profile::{AmountSign, CsvProfile},
+    },
+    model::DebitCredit,
+    stream,
+    traits::{ParseMode, StreamRead},
+};
+use rust_decimal_macros::dec;
+use std::io::Cursor;
+
+const TINKOFF: &str = "Дата операции;Сумма операции;Валюта операции;Описание\n\
+                       01.10.2025 12:30:00;-1 234,50;RUB;Кофейня\n\
+                       02.10.2025 09:00:00;+50 000,00;RUB;Зарплата\n";
+
+const TINKOFF_PROFILE: &str = r#"
+name = "tinkoff"
+delimiter = ";"
+date_format = "%d.%m.%Y %H:%M:%S"
+decimal_separator = ","
+account_id = "40817810000000000001"
+
+[columns]
+booking_date = "Дата операции"
+amount = "Сумма операции"
+currency = "Валюта операции"
+description = 3
+"#;
+
+fn read(src: &str, p: CsvProfile) -> finiolib::error::Result<Vec<finiolib::model::Statement>> {
+    stream::collect(Csv::reader_with(Cursor::new(src.to_string()), ParseMode::Strict).profile(p))
+}
+
+#[test]
+fn signed_amounts_with_local_formats() {
+    let p = CsvProfile::from_toml(TINKOFF_PROFILE).unwrap();
+    assert_eq!(p.sign, AmountSign::Signed);
+    let sts = read(TINKOFF, p).unwrap();
+    assert_eq!(sts.len(), 1);
+    assert_eq!(sts[0].account_id, "40817810000000000001");
+    let es: Vec<_> = sts[0].entries.iter().map(|e| (e.booking_date.to_string(), e.amount, e.dc)).collect();
+    assert_eq!(
+        es,
+        [
+            ("2025-10-01".into(), dec!(1234.50), DebitCredit::Debit),
+            ("2025-10-02".into(), dec!(50000.00), DebitCredit::Credit),
+        ]
+    );
+    assert_eq!(sts[0].entries[0].description, "Кофейня");
+}
+
+#[test]
+fn indicator_column_by_index_from_json() {
+    let p = CsvProfile::from_json(
+        r#"{"has_header": false, "skip_rows": 1, "sign": "indicator", "currency": "EUR",
+            "credit_values": ["Credit", "Haben"], "debit_values": ["Debit", "Soll"],
+            "columns": {"booking_date": 0, "amount": 1, "dc": 2, "account_id": 3}}"#,
+    )
+    .unwrap();
+    let src = "Kontoauszug\n2025-10-01,10.00,Haben,DE01\n2025-10-02,3.00,soll,DE01\n2025-10-02,1.00,?,DE01\n";
+    let err = read(src, p.clone()).unwrap_err();
+    let d = err.diagnostic().unwrap();
+    assert_eq!((d.code, d.field.as_deref(), d.value.as_deref()), (ErrorCode::InvalidDebitCredit, Some("#2"), Some("?")));
+
+    let (sts, skipped) = stream::collect_with(
+        Csv::reader_with(Cursor::new(src), ParseMode::Lenient).profile(p),
+        ParseMode::Lenient,
+    )
+    .unwrap();
+    assert_eq!(skipped.len(), 1);
+    let dcs: Vec<_> = sts[0].entries.iter().map(|e| e.dc).collect();
+    assert_eq!(dcs, [DebitCredit::Credit, DebitCredit::Debit]);
+}
+
+#[test]
+fn rejects_incomplete_profile_and_unknown_columns() {
+    let err = CsvProfile::from_toml("sign = \"split\"\ncurrency = \"RUB\"\n[columns]\nbooking_date = 0\ndebit = 1\n")
+        .unwrap_err();
+    assert_eq!(err.diagnostic().unwrap().field.as_deref(), Some("columns.credit"));
+    assert!(CsvProfile::from_toml("delimeter = \";\"").is_err());
+
+    let p = CsvProfile::from_toml(&TINKOFF_PROFILE.replace("Валюта операции\"", "Валюта\"")).unwrap();
+    let err = read(TINKOFF, p).unwrap_err();
+    let d = err.diagnostic().unwrap();
+    assert_eq!((d.code, d.field.as_deref()), (ErrorCode::MissingField, Some("Валюта")));
+}
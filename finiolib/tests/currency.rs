@@ -0,0 +1,68 @@
+use finiolib::{
+    currency::Currency,
+    error::ErrorCode,
+    formats::{camt053::Camt053, csv::Csv, mt940::Mt940, xml::SimpleXml},
+    traits::{ReadFormat, WriteFormat},
+};
+use rust_decimal_macros::dec;
+use std::io::Cursor;
+
+const JPY: &str = ":20:S1\n:25:JP00\n:60F:C251001JPY1000,\n\
+                   :61:2510011001C500,NTRFNONREF\n:62F:C251031JPY1500,\n";
+
+#[test]
+fn amounts_follow_currency_exponent() {
+    let bhd: Currency = "bhd".parse().unwrap();
+    assert_eq!((bhd.code(), bhd.numeric(), bhd.minor_units()), ("BHD", 48, Some(3)));
+    assert_eq!(bhd.scale(dec!(1.2)).unwrap().to_string(), "1.200");
+    assert_eq!(Currency::from_numeric(643), Some(Currency::RUB));
+    assert_eq!(Currency::EUR.scale(dec!(10.5)).unwrap().to_string(), "10.50");
+
+    let st = Mt940::read(Cursor::new(JPY)).unwrap();
+    assert_eq!(st.entries[0].currency, "JPY");
+
+    let mut mt = Vec::new();
+    Mt940::write(&mut mt, &st).unwrap();
+    let mt = String::from_utf8(mt).unwrap();
+    assert!(mt.contains(":60F:C251001JPY1000,\n"));
+    assert!(mt.contains(":61:2510011001C500,NTRF"));
+
+    let mut camt = Vec::new();
+    Camt053::write(&mut camt, &st).unwrap();
+    assert!(String::from_utf8(camt).unwrap().contains(r#"<Amt Ccy="JPY">500</Amt>"#));
+
+    let mut csv = Vec::new();
+    Csv::write(&mut csv, &st).unwrap();
+    assert!(String::from_utf8(csv).unwrap().contains("\n2025-10-01,2025-10-01,500,JPY,C,"));
+
+    let mut eur = st.clone();
+    for e in &mut eur.entries {
+        e.currency = Currency::EUR;
+        e.amount = dec!(10.5);
+    }
+    let mut mt = Vec::new();
+    Mt940::write(&mut mt, &eur).unwrap();
+    assert!(String::from_utf8(mt).unwrap().contains("C10,50NTRF"));
+
+    // SimpleXml тоже пишет знаки валюты: EUR 10 → 10.00
+    eur.entries[0].amount = dec!(10);
+    let mut xml = Vec::new();
+    SimpleXml::write(&mut xml, &eur).unwrap();
+    let xml = String::from_utf8(xml).unwrap();
+    assert!(xml.contains("<amount>10.00</amount>") && xml.contains("<amount>1000</amount>"));
+}
+
+#[test]
+fn rejects_unknown_code_and_extra_decimals() {
+    let err = Mt940::read(Cursor::new(JPY.replace("JPY1000", "ABC1000"))).unwrap_err();
+    let d = err.diagnostic().unwrap();
+    assert_eq!((d.code, d.field.as_deref()), (ErrorCode::InvalidValue, Some(":60F: currency")));
+
+    let mut st = Mt940::read(Cursor::new(JPY)).unwrap();
+    st.entries[0].amount = dec!(500.5);
+    let err = Mt940::write(Vec::new(), &st).unwrap_err();
+    assert_eq!(err.diagnostic().unwrap().code, ErrorCode::InvalidAmount);
+    assert!(Camt053::write(Vec::new(), &st).is_err());
+    let err = SimpleXml::write(Vec::new(), &st).unwrap_err();
+    assert_eq!(err.diagnostic().unwrap().code, ErrorCode::InvalidAmount);
+}
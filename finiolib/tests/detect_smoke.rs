@@ -0,0 +1,40 @@
+use finiolib::{
+    detect::{detect, from_extension, FormatKind},
+    formats::{camt053::Camt053, xml::SimpleXml},
+    model::Statement,
+    traits::WriteFormat,
+};
+use std::path::Path;
+
+fn written<F: WriteFormat>() -> Vec<u8> {
+    let st = Statement { account_id: "ACCT".into(), ..Statement::default() };
+    let mut out = Vec::new();
+    F::write(&mut out, &st).unwrap();
+    out
+}
+
+#[test]
+fn detects_by_content() {
+    let mt = b":20:STATEMENT1\n:25:DE0012345678\n:60F:C251001EUR1000,00\n";
+    assert_eq!(detect(mt).unwrap().format, FormatKind::Mt940);
+
+    let csv = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/sample.csv")).unwrap();
+    let d = detect(&csv).unwrap();
+    assert_eq!(d.format, FormatKind::Csv);
+    assert_eq!(d.confidence, 100);
+
+    assert_eq!(detect(&written::<Camt053>()).unwrap().format, FormatKind::Camt053);
+    assert_eq!(detect(&written::<SimpleXml>()).unwrap().format, FormatKind::SimpleXml);
+
+    assert!(detect(b"hello world").is_none());
+}
+
+#[test]
+fn detects_by_extension() {
+    let f = |p: &str| from_extension(Path::new(p)).map(|d| d.format);
+    assert_eq!(f("in/statement.STA"), Some(FormatKind::Mt940));
+    assert_eq!(f("out.camt.xml"), Some(FormatKind::Camt053));
+    assert_eq!(f("out.xml"), Some(FormatKind::SimpleXml));
+    assert_eq!(f("data.csv"), Some(FormatKind::Csv));
+    assert_eq!(f("README"), None);
+}
@@ -0,0 +1,74 @@
+use finiolib::{
+    error::{ErrorCode, FinioError, Location},
+    formats::{camt053::Camt053, csv::Csv, mt940::Mt940},
+    traits::ReadFormat,
+};
+use std::io::Cursor;
+
+fn diag<T: std::fmt::Debug>(r: Result<T, FinioError>) -> finiolib::error::Diagnostic {
+    r.unwrap_err().diagnostic().expect("parse diagnostic").clone()
+}
+
+#[test]
+fn mt940_reports_line_and_column() {
+    let src = ":20:S1\n:25:DE00\n:60F:C251001EUR1X00,00\n";
+    let d = diag(Mt940::read(Cursor::new(src)));
+    assert_eq!(d.code, ErrorCode::InvalidAmount);
+    assert_eq!(d.code.as_str(), "E004");
+    assert_eq!(d.field.as_deref(), Some(":60F: amount"));
+    assert_eq!(d.value.as_deref(), Some("1X00,00"));
+    assert_eq!(d.location, Some(Location::Line { line: 3, column: Some(16) }));
+    assert_eq!(d.snippet.as_deref(), Some(":60F:C251001EUR1X00,00"));
+}
+
+#[test]
+fn csv_reports_record() {
+    let src = "booking_date,value_date,amount,currency,dc,description,reference,account_id\n\
+               2025-10-01,,1.00,EUR,C,a,,DE00\n\
+               2025-10-02,,2.00,EUR,X,b,,DE00\n";
+    let d = diag(Csv::read_statements(Cursor::new(src)));
+    assert_eq!(d.code, ErrorCode::InvalidDebitCredit);
+    assert_eq!(d.field.as_deref(), Some("dc"));
+    assert_eq!(d.location, Some(Location::Record { record: 2, line: 3 }));
+}
+
+#[test]
+fn camt_reports_element_path() {
+    let src = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+<BkToCstmrStmt><Stmt><Id>S1</Id>
+<Ntry><Amt Ccy="EUR">1.00</Amt><BookgDt><Dt>2025-02-30</Dt></BookgDt></Ntry>
+</Stmt></BkToCstmrStmt></Document>"#;
+    let d = diag(Camt053::read_statements(Cursor::new(src)));
+    assert_eq!(d.code, ErrorCode::InvalidDate);
+    assert_eq!(d.value.as_deref(), Some("2025-02-30"));
+    match d.location {
+        Some(Location::Xml { path, offset }) => {
+            assert_eq!(path, "/Document/BkToCstmrStmt/Stmt/Ntry/BookgDt/Dt");
+            assert!(offset.is_some());
+        }
+        other => panic!("unexpected location {other:?}"),
+    }
+}
+
+#[test]
+fn mt940_malformed_balance_is_diagnostic() {
+    // короткий остаток и кириллица не режутся по байтам и не пропускаются
+    for bal in ["C2510", "Ж251001EUR1,00", "C25100ЖEUR1,00"] {
+        let src = format!(":20:S1\n:25:DE00\n:60F:{bal}\n");
+        let d = diag(Mt940::read(Cursor::new(src)));
+        assert_eq!(d.code, ErrorCode::Syntax, "{bal}");
+        assert_eq!(d.field.as_deref(), Some(":60F:"));
+        assert_eq!(d.value.as_deref(), Some(bal));
+    }
+}
+
+#[test]
+fn camt_entry_without_booking_date() {
+    let src = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+<BkToCstmrStmt><Stmt><Id>S1</Id>
+<Ntry><Amt Ccy="EUR">1.00</Amt><CdtDbtInd>CRDT</CdtDbtInd></Ntry>
+</Stmt></BkToCstmrStmt></Document>"#;
+    let d = diag(Camt053::read_statements(Cursor::new(src)));
+    assert_eq!(d.code, ErrorCode::MissingField);
+    assert_eq!(d.field.as_deref(), Some("Ntry/BookgDt"));
+}
@@ -0,0 +1,66 @@
+use finiolib::{
+    encoding::{detect, DecodingReader, Encoding, EncodingWriter},
+    formats::mt940::Mt940,
+    traits::{ReadFormat, WriteFormat},
+};
+use std::io::{Cursor, Write};
+
+const MT: &str = ":20:ВЫПИСКА1
+:25:40702810900000000001
+:28C:1
+:60F:C251001RUB1000,00
+:61:2510011001C100,00NTRFNONREF
+:86:Оплата по договору №7, аванс
+:62F:C251031RUB1100,00
+";
+
+fn encode(s: &str, enc: Encoding) -> Vec<u8> {
+    let mut out = Vec::new();
+    EncodingWriter::new(&mut out, enc).unwrap().write_all(s.as_bytes()).unwrap();
+    out
+}
+
+#[test]
+fn detects_encoding() {
+    assert_eq!(detect(MT.as_bytes()), Encoding::Utf8);
+    assert_eq!(detect(b"\xFF\xFE:\x002\x000\x00"), Encoding::Utf16Le);
+    assert_eq!(detect(&encode(MT, Encoding::Windows1251)), Encoding::Windows1251);
+    assert_eq!(detect(&encode(MT, Encoding::Cp866)), Encoding::Cp866);
+    assert_eq!("cp1251".parse::<Encoding>().unwrap(), Encoding::Windows1251);
+    assert_eq!("DOS".parse::<Encoding>().unwrap(), Encoding::Cp866);
+}
+
+#[test]
+fn reads_cp1251_mt940() {
+    let bytes = encode(MT, Encoding::Windows1251);
+    // без перекодировки lines() падает на не-UTF-8
+    assert!(Mt940::read(Cursor::new(&bytes)).is_err());
+
+    let r = DecodingReader::detect(Cursor::new(&bytes)).unwrap();
+    assert_eq!(r.encoding(), Encoding::Windows1251);
+    let st = Mt940::read(r).unwrap();
+    assert_eq!(st.statement_id.as_deref(), Some("ВЫПИСКА1"));
+    assert_eq!(st.entries[0].description, "Оплата по договору №7, аванс");
+
+    // и обратно — через EncodingWriter
+    let mut out = Vec::new();
+    Mt940::write(EncodingWriter::new(&mut out, Encoding::Cp866).unwrap(), &st).unwrap();
+    let back = Mt940::read(DecodingReader::new(Cursor::new(&out), Encoding::Cp866)).unwrap();
+    assert_eq!(back, st);
+}
+
+#[test]
+fn writer_handles_split_and_unmappable_chars() {
+    let mut out = Vec::new();
+    let mut w = EncodingWriter::new(&mut out, Encoding::Koi8R).unwrap();
+    let s = "Ёж".as_bytes();
+    // многобайтовый символ разрезан между вызовами write
+    w.write_all(&s[..1]).unwrap();
+    w.write_all(&s[1..]).unwrap();
+    drop(w);
+    assert_eq!(out, [0xB3, 0xD6]);
+
+    let mut w = EncodingWriter::new(Vec::new(), Encoding::Windows1251).unwrap();
+    assert!(w.write_all("€ ok, 日本 no".as_bytes()).is_err());
+    assert!(EncodingWriter::new(Vec::new(), Encoding::Utf16Le).is_err());
+}
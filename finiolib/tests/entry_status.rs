@@ -0,0 +1,50 @@
+use finiolib::{
+    convert,
+    formats::{
+        camt053::{Camt053, CamtVersion},
+        csv::Csv,
+        mt942::Mt942,
+    },
+    model::EntryStatus,
+    stream,
+    traits::{ReadFormat, StreamRead, StreamWrite, WriteFormat},
+};
+use std::io::Cursor;
+
+const CAMT: &str = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.08">
+<BkToCstmrStmt><Stmt><Id>S1</Id>
+<Ntry><Amt Ccy="EUR">1.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Sts><Cd>BOOK</Cd></Sts><BookgDt><Dt>2025-10-01</Dt></BookgDt></Ntry>
+<Ntry><Amt Ccy="EUR">2.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Sts><Cd>PDNG</Cd></Sts><BookgDt><Dt>2025-10-02</Dt></BookgDt></Ntry>
+<Ntry><Amt Ccy="EUR">3.00</Amt><CdtDbtInd>DBIT</CdtDbtInd><Sts><Cd>INFO</Cd></Sts><BookgDt><Dt>2025-10-03</Dt></BookgDt></Ntry>
+</Stmt></BkToCstmrStmt></Document>"#;
+
+#[test]
+fn camt_status_roundtrip_across_versions() {
+    let st = Camt053::read(Cursor::new(CAMT)).unwrap();
+    let statuses: Vec<_> = st.entries.iter().map(|e| e.status).collect();
+    assert_eq!(statuses, [EntryStatus::Booked, EntryStatus::Pending, EntryStatus::Information]);
+
+    for v in [CamtVersion::V02, CamtVersion::V08] {
+        let mut buf = Vec::new();
+        stream::write_all(&mut Camt053::writer(&mut buf).version(v), std::slice::from_ref(&st)).unwrap();
+        let xml = String::from_utf8(buf).unwrap();
+        let flat: String = xml.lines().map(str::trim).collect();
+        let pdng = if v == CamtVersion::V08 { "<Sts><Cd>PDNG</Cd></Sts>" } else { "<Sts>PDNG</Sts>" };
+        assert!(flat.contains(pdng));
+        assert_eq!(Camt053::read(Cursor::new(xml)).unwrap().entries, st.entries);
+    }
+
+    let mut csv = Vec::new();
+    Csv::write(&mut csv, &st).unwrap();
+    assert_eq!(Csv::read(Cursor::new(csv)).unwrap().entries, st.entries);
+}
+
+#[test]
+fn mt942_is_pending_and_filter_keeps_one_status() {
+    let st = Mt942::read(Cursor::new(include_bytes!("../../examples/sample.mt942"))).unwrap();
+    assert!(st.entries.iter().all(|e| e.status == EntryStatus::Pending));
+
+    let booked = stream::collect(convert::filter_status(Camt053::reader(Cursor::new(CAMT)), EntryStatus::Booked)).unwrap();
+    assert_eq!(booked[0].entries.len(), 1);
+    assert_eq!(booked[0].statement_id.as_deref(), Some("S1"));
+}
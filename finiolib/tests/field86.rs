@@ -0,0 +1,143 @@
+use finiolib::{
+    formats::{
+        field86::{Field86, Layout86},
+        mt940::Mt940,
+    },
+    model::Entry,
+    stream,
+    traits::{ReadFormat, StreamRead, StreamWrite},
+};
+use std::io::Cursor;
+
+fn mt(lines86: &str) -> String {
+    format!(
+        ":20:S1\n:25:NL91ABNA0417164300\n:60F:C251001EUR100,00\n\
+         :61:2510011001C10,00NTRFNONREF\n:86:{lines86}\n:62F:C251031EUR110,00\n"
+    )
+}
+
+fn roundtrip(src: &str, layout: Layout86) -> (Entry, String) {
+    let sts = stream::collect(Mt940::reader(Cursor::new(src)).layout86(layout)).unwrap();
+    let mut buf = Vec::new();
+    stream::write_all(&mut Mt940::writer(&mut buf).layout86(layout), &sts).unwrap();
+    (sts[0].entries[0].clone(), String::from_utf8(buf).unwrap())
+}
+
+#[test]
+fn german_gvc_layout() {
+    let src = mt("166?00SEPA-GUTSCHRIFT?10931?20EREF+4711\n?21SVWZ+Rechnung 4711?30COBADEFFXXX\n\
+                  ?31DE02120300000000202051?32Muster GmbH");
+    let (e, out) = roundtrip(&src, Layout86::Gvc);
+    let gvc = e.bank_tx_code.as_ref().and_then(|c| c.proprietary.as_ref()).unwrap();
+    assert_eq!((gvc.code.as_str(), gvc.issuer.as_deref()), ("166", Some("DK")));
+    assert_eq!(e.posting_text.as_deref(), Some("SEPA-GUTSCHRIFT"));
+    assert_eq!(e.description, "EREF+4711SVWZ+Rechnung 4711");
+    let cp = e.counterparty.as_ref().unwrap();
+    assert_eq!(cp.bic.as_deref(), Some("COBADEFFXXX"));
+    assert_eq!(cp.name.as_deref(), Some("Muster GmbH"));
+
+    assert!(out.contains(":86:166?00SEPA-GUTSCHRIFT\n?20"));
+    assert!(out.lines().all(|l| l.chars().count() <= 65));
+    let back = Mt940::read(Cursor::new(out)).unwrap();
+    assert_eq!(back.entries[0], e);
+}
+
+#[test]
+fn tagged_layout() {
+    let src = mt("/EREF/NOTPROVIDED/ORDP//NAME/J. Jansen/ADDR/Dorpsstraat 1 Ams\n\
+                  terdam/IBAN/NL02RABO0123456789/BIC/RABONL2U/REMI/USTD//Factuur 2025-17/\n\
+                  /TRTP/SEPA OVERBOEKING");
+    let (e, out) = roundtrip(&src, Layout86::Tagged);
+    assert_eq!(e.description, "Factuur 2025-17");
+    assert_eq!(e.posting_text.as_deref(), Some("SEPA OVERBOEKING"));
+    let cp = e.counterparty.as_ref().unwrap();
+    assert_eq!(cp.name.as_deref(), Some("J. Jansen"));
+    assert_eq!(cp.address.as_deref(), Some("Dorpsstraat 1 Amsterdam"));
+    assert_eq!(cp.account.as_deref(), Some("NL02RABO0123456789"));
+
+    assert!(out.contains(":86:/TRTP/SEPA OVERBOEKING/NAME/J. Jansen"));
+    // auto тоже узнаёт теги
+    assert_eq!(Mt940::read(Cursor::new(out)).unwrap().entries[0], e);
+}
+
+/// Своя раскладка банка: «ИМЯ|назначение».
+struct Pipe;
+
+impl Field86 for Pipe {
+    fn parse(&self, lines: &[&str], e: &mut Entry) {
+        let text = lines.join(" ");
+        let (name, purpose) = text.split_once('|').unwrap_or(("", &text));
+        e.description = purpose.to_string();
+        e.counterparty = Some(finiolib::model::Counterparty {
+            name: Some(name.to_string()),
+            ..Default::default()
+        });
+    }
+
+    fn format(&self, e: &Entry) -> Vec<String> {
+        let name = e.counterparty.as_ref().and_then(|c| c.name.as_deref()).unwrap_or("");
+        vec![format!("{name}|{}", e.description)]
+    }
+}
+
+#[test]
+fn custom_layout_plugs_in() {
+    let src = mt("ООО Ромашка|оплата по счёту 7");
+    let sts = stream::collect(Mt940::reader(Cursor::new(&src)).layout86(Pipe)).unwrap();
+    let e = &sts[0].entries[0];
+    assert_eq!(e.description, "оплата по счёту 7");
+    assert_eq!(e.counterparty.as_ref().unwrap().name.as_deref(), Some("ООО Ромашка"));
+
+    // без раскладки — свободный текст как есть
+    let plain = Mt940::read_statements(Cursor::new(&src)).unwrap();
+    assert_eq!(plain[0].entries[0].description, "ООО Ромашка|оплата по счёту 7");
+    assert_eq!("nl".parse::<Layout86>().unwrap(), Layout86::Tagged);
+}
+
+#[test]
+fn auto_picks_layout_by_content() {
+    let auto = |e: &Entry| Layout86::Auto.format(e);
+    let (mut e, _) = roundtrip(&mt("Rechnung 4711"), Layout86::FreeText);
+    e.counterparty = Some(finiolib::model::Counterparty {
+        name: Some("Muster GmbH".into()),
+        ..Default::default()
+    });
+    // без кода GVC немецкая раскладка не пишется
+    assert_eq!(auto(&e), ["/NAME/Muster GmbH/REMI/Rechnung 4711"]);
+    e.bank_tx_code = Some(finiolib::model::BankTxCode {
+        proprietary: Some(finiolib::model::ProprietaryTxCode { code: "166".into(), issuer: None }),
+        ..Default::default()
+    });
+    assert_eq!(auto(&e), ["166?20Rechnung 4711", "?32Muster GmbH"]);
+
+    // свободный текст переносится по словам в строки до 65 символов
+    let text = "оплата по договору поставки № 17 от 01.10.2025 за товары и услуги, в т.ч. НДС 20% 1666,67";
+    let (mut plain, _) = roundtrip(&mt(text), Layout86::FreeText);
+    plain.description = text.into();
+    let lines = auto(&plain);
+    assert!(lines.len() > 1 && lines.iter().all(|l| l.chars().count() <= 65));
+    let (back, _) = roundtrip(&mt(&lines.join("\n")), Layout86::Auto);
+    assert_eq!(back.description, text);
+}
+
+#[test]
+fn gvc_keeps_subfield_limits() {
+    let (mut e, _) = roundtrip(&mt("166?20x"), Layout86::Gvc);
+    e.description.clear();
+    // только код GVC — отдельной строкой, и он читается обратно
+    assert_eq!(Layout86::Gvc.format(&e), ["166"]);
+    let (back, _) = roundtrip(&mt("166"), Layout86::Auto);
+    assert_eq!(back.bank_tx_code, e.bank_tx_code);
+
+    e.counterparty = Some(finiolib::model::Counterparty {
+        name: Some("N".repeat(60)),
+        ..Default::default()
+    });
+    assert_eq!(Layout86::Gvc.format(&e), [format!("166?32{}", "N".repeat(27)), format!("?33{}", "N".repeat(27))]);
+
+    // назначение длиннее 14 подполей — свободным текстом
+    e.description = "слово ".repeat(70).trim_end().into();
+    let lines = Layout86::Gvc.format(&e);
+    assert!(lines.iter().all(|l| l.chars().count() <= 65 && !l.starts_with('?')));
+    assert_eq!(lines.join(" "), e.description);
+}
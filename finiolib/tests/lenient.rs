@@ -0,0 +1,84 @@
+use finiolib::{
+    error::{ErrorCode, Location},
+    formats::{camt053::Camt053, csv::Csv, mt940::Mt940},
+    traits::{ParseMode, ReadFormat, StreamRead},
+};
+use rust_decimal_macros::dec;
+use std::io::Cursor;
+
+const MT: &str = ":20:S1
+:25:DE00
+:60F:C251001EUR100,00
+:61:2510011001C10,00NTRFNONREF
+:86:first
+:61:2510021002X20,00NTRFNONREF
+:86:broken
+:61:2510031003D5,00NTRFNONREF
+:86:third
+:62F:C251031EUR105,00
+";
+
+#[test]
+fn mt940_skips_bad_entry_with_its_86() {
+    assert!(Mt940::read_statements(Cursor::new(MT)).is_err());
+
+    let (sts, skipped) = Mt940::read_statements_with(Cursor::new(MT), ParseMode::Lenient).unwrap();
+    assert_eq!(sts.len(), 1);
+    let descr: Vec<_> = sts[0].entries.iter().map(|e| e.description.as_str()).collect();
+    assert_eq!(descr, ["first", "third"]);
+    assert_eq!(sts[0].closing_balance.as_ref().unwrap().amount, dec!(105.00));
+
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].location, Some(Location::Line { line: 6, column: Some(5) }));
+
+    // потоковый reader отдаёт диагностику как Err и продолжает
+    let events: Vec<_> = Mt940::reader_with(Cursor::new(MT), ParseMode::Lenient).collect();
+    assert_eq!(events.iter().filter(|e| e.is_err()).count(), 1);
+    assert!(events.last().unwrap().is_ok());
+}
+
+#[test]
+fn csv_skips_bad_rows() {
+    let src = "booking_date,value_date,amount,currency,dc,description,reference,account_id\n\
+               2025-10-01,,1.00,EUR,C,a,,DE00\n\
+               2025-10-02,,2.00,EUR,X,b,,DE00\n\
+               2025-10-03\n\
+               2025-10-04,,4.00,EUR,D,d,,DE00\n";
+    let (sts, skipped) = Csv::read_statements_with(Cursor::new(src), ParseMode::Lenient).unwrap();
+    assert_eq!(sts.len(), 1);
+    let amounts: Vec<_> = sts[0].entries.iter().map(|e| e.amount).collect();
+    assert_eq!(amounts, [dec!(1.00), dec!(4.00)]);
+
+    let codes: Vec<_> = skipped.iter().map(|d| d.code).collect();
+    assert_eq!(codes, [ErrorCode::InvalidDebitCredit, ErrorCode::MissingField]);
+    assert_eq!(skipped[1].location, Some(Location::Record { record: 3, line: 4 }));
+}
+
+#[test]
+fn camt_skips_bad_ntry() {
+    let src = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+<BkToCstmrStmt><Stmt><Id>S1</Id>
+<Ntry><Amt Ccy="EUR">1.00</Amt><BookgDt><Dt>2025-02-30</Dt></BookgDt></Ntry>
+<Ntry><Amt Ccy="EUR">2.00</Amt><CdtDbtInd>DBIT</CdtDbtInd><BookgDt><Dt>2025-02-28</Dt></BookgDt></Ntry>
+</Stmt></BkToCstmrStmt></Document>"#;
+    let (sts, skipped) = Camt053::read_statements_with(Cursor::new(src), ParseMode::Lenient).unwrap();
+    assert_eq!(sts[0].statement_id.as_deref(), Some("S1"));
+    assert_eq!(sts[0].entries.len(), 1);
+    assert_eq!(sts[0].entries[0].amount, dec!(2.00));
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].code, ErrorCode::InvalidDate);
+}
+
+#[test]
+fn camt_misplaced_element_does_not_close_stmt() {
+    let src = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+<BkToCstmrStmt><Stmt><Id>S1</Id>
+<Ntry><Amt Ccy="EUR">1.00</Amt><Bal><Amt Ccy="EUR">9.00</Amt></Bal><BookgDt><Dt>2025-02-27</Dt></BookgDt></Ntry>
+<Ntry><Amt Ccy="EUR">2.00</Amt><CdtDbtInd>DBIT</CdtDbtInd><BookgDt><Dt>2025-02-28</Dt></BookgDt></Ntry>
+</Stmt></BkToCstmrStmt></Document>"#;
+    let (sts, skipped) = Camt053::read_statements_with(Cursor::new(src), ParseMode::Lenient).unwrap();
+    let amounts: Vec<_> = sts[0].entries.iter().map(|e| e.amount).collect();
+    assert_eq!(amounts, [dec!(2.00)]);
+    let codes: Vec<_> = skipped.iter().map(|d| (d.code, d.field.as_deref())).collect();
+    assert_eq!(codes, [(ErrorCode::Structure, Some("Bal"))]);
+}
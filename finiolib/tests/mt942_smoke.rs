@@ -0,0 +1,85 @@
+use finiolib::{
+    formats::{
+        camt053::Camt053,
+        mt942::{Mt942, Mt942Report},
+    },
+    model::DebitCredit,
+    traits::{ReadFormat, WriteFormat},
+};
+use rust_decimal::Decimal;
+use std::io::Cursor;
+
+const SAMPLE: &str = include_str!("../../examples/sample.mt942");
+
+#[test]
+fn mt942_read_report() {
+    let reps = Mt942::read_reports(Cursor::new(SAMPLE)).expect("mt942 read");
+    assert_eq!(reps.len(), 1);
+    let rep = &reps[0];
+
+    assert_eq!(rep.statement.account_id, "DE0012345678");
+    assert_eq!(rep.statement_number.as_deref(), Some("1/1"));
+    assert_eq!(rep.floor_limit_debit, rep.floor_limit_credit);
+    assert_eq!(rep.floor_limit_debit.as_ref().unwrap().currency, "EUR");
+    assert_eq!(rep.date_time.unwrap().to_rfc3339(), "2025-10-01T15:30:00+02:00");
+
+    let debits = rep.debits.as_ref().unwrap();
+    assert_eq!((debits.count, debits.amount), (1, Decimal::new(2550, 2)));
+    assert_eq!(rep.info.as_deref(), Some("Intraday report"));
+
+    let e = &rep.statement.entries[1];
+    assert_eq!(e.dc, DebitCredit::Debit);
+    assert_eq!(e.currency, "EUR");
+    assert_eq!(e.description, "Groceries");
+}
+
+#[test]
+fn mt942_reports_roundtrip() {
+    let reps = Mt942::read_reports(Cursor::new(SAMPLE)).unwrap();
+    let mut out = Vec::new();
+    Mt942::write_reports(&mut out, &reps).unwrap();
+    assert_eq!(Mt942::read_reports(Cursor::new(out)).unwrap(), reps);
+
+    // через нормализованную Statement итоги пересчитываются по проводкам
+    let st = Mt942::read(Cursor::new(SAMPLE)).unwrap();
+    let mut out = Vec::new();
+    Mt942::write(&mut out, &st).unwrap();
+    let back = Mt942::read_reports(Cursor::new(out)).unwrap();
+    assert_eq!(back[0].statement, st);
+    // :13D: сохраняет смещение, а не пишется как UTC
+    assert_eq!(st.created_at().unwrap().to_rfc3339(), "2025-10-01T15:30:00+02:00");
+    assert_eq!(back[0].date_time, reps[0].date_time);
+    assert_eq!(back[0].debits, reps[0].debits);
+    assert_eq!(back[0].credits, reps[0].credits);
+}
+
+#[test]
+fn mt942_time_offset_survives_camt() {
+    let st = Mt942::read(Cursor::new(SAMPLE)).unwrap();
+    let mut camt = Vec::new();
+    Camt053::write(&mut camt, &st).unwrap();
+    assert!(String::from_utf8(camt.clone()).unwrap().contains("<CreDtTm>2025-10-01T15:30:00+02:00</CreDtTm>"));
+    let back = Camt053::read(Cursor::new(camt)).unwrap();
+    assert_eq!(back.created_at(), st.created_at());
+
+    let mut out = Vec::new();
+    Mt942::write(&mut out, &back).unwrap();
+    assert!(String::from_utf8(out).unwrap().contains(":13D:2510011530+0200\n"));
+}
+
+#[test]
+fn mt942_from_statement_does_not_depend_on_host() {
+    let mut st = Mt942::read(Cursor::new(SAMPLE)).unwrap();
+    // без смещения — UTC, а не часовой пояс машины
+    st.created_offset = None;
+    let rep = Mt942Report::from_statement(&st);
+    assert_eq!(rep.date_time.unwrap().to_rfc3339(), "2025-10-01T15:30:00+00:00");
+
+    // итоги — только по проводкам в валюте отчёта
+    st.entries[1].dc = DebitCredit::Credit;
+    st.entries[1].currency = "JPY".parse().unwrap();
+    st.entries[1].amount = Decimal::new(5, 0);
+    let rep = Mt942Report::from_statement(&st);
+    let credits = rep.credits.unwrap();
+    assert_eq!((credits.count, credits.currency.code(), credits.amount), (1, "EUR", Decimal::new(10000, 2)));
+}
@@ -0,0 +1,80 @@
+use finiolib::{
+    formats::{camt053::Camt053, csv::Csv, mt940::Mt940, xml::SimpleXml},
+    model::Statement,
+    traits::{ReadFormat, WriteFormat},
+};
+use std::io::Cursor;
+
+const TWO_BLOCKS: &str = r#":20:STMT1
+:25:DE0012345678
+:28C:1
+:60F:C251001EUR1000,00
+:61:2510011001C100,00NTRFNONREF
+:86:Salary October
+:62F:C251031EUR1100,00
+-
+:20:STMT2
+:25:DE0099999999
+:28C:2
+:60F:C251001USD50,00
+:61:2510021002D10,00NTRFNONREF
+:86:Fee
+:61:2510031003D5,00NTRFNONREF
+:62F:C251031USD35,00
+-
+"#;
+
+#[test]
+fn mt940_reads_every_block() {
+    let sts = Mt940::read_statements(Cursor::new(TWO_BLOCKS)).expect("mt940 read");
+    assert_eq!(sts.len(), 2);
+    assert_eq!(sts[0].account_id, "DE0012345678");
+    assert_eq!(sts[0].entries.len(), 1);
+    assert_eq!(sts[0].entries[0].description, "Salary October");
+    assert_eq!(sts[1].account_id, "DE0099999999");
+    assert_eq!(sts[1].statement_id.as_deref(), Some("STMT2"));
+    assert_eq!(sts[1].entries.len(), 2);
+    assert_eq!(sts[1].entries[0].currency, "USD");
+
+    // одиночный read не должен молча терять второй блок
+    assert!(Mt940::read(Cursor::new(TWO_BLOCKS)).is_err());
+}
+
+#[test]
+fn empty_input_reads_as_empty_statement() {
+    assert_eq!(Mt940::read(Cursor::new("")).unwrap(), Statement::default());
+    let mut xml = Vec::new();
+    SimpleXml::write_statements(&mut xml, &[]).unwrap();
+    assert_eq!(SimpleXml::read(Cursor::new(xml)).unwrap(), Statement::default());
+    assert!(Csv::read_statements(Cursor::new("")).unwrap().is_empty());
+}
+
+#[test]
+fn batch_survives_every_format() {
+    let sts = Mt940::read_statements(Cursor::new(TWO_BLOCKS)).unwrap();
+
+    let mut camt = Vec::new();
+    Camt053::write_statements(&mut camt, &sts).unwrap();
+    let back = Camt053::read_statements(Cursor::new(camt)).unwrap();
+    assert_eq!(back.len(), 2);
+    for (a, b) in back.iter().zip(&sts) {
+        assert_eq!(a.statement_id, b.statement_id);
+        assert_eq!(a.account_id, b.account_id);
+        assert_eq!(a.entries, b.entries);
+    }
+
+    let mut xml = Vec::new();
+    SimpleXml::write_statements(&mut xml, &sts).unwrap();
+    assert_eq!(SimpleXml::read_statements(Cursor::new(xml)).unwrap(), sts);
+
+    let mut mt = Vec::new();
+    Mt940::write_statements(&mut mt, &sts).unwrap();
+    assert_eq!(Mt940::read_statements(Cursor::new(mt)).unwrap(), sts);
+
+    let mut csv = Vec::new();
+    Csv::write_statements(&mut csv, &sts).unwrap();
+    let back = Csv::read_statements(Cursor::new(csv)).unwrap();
+    assert_eq!(back.len(), 2);
+    assert_eq!(back[1].statement_id.as_deref(), Some("STMT2"));
+    assert_eq!(back[1].entries, sts[1].entries);
+}
@@ -0,0 +1,85 @@
+use finiolib::{
+    detect::{detect, FormatKind},
+    formats::onec::OneC,
+    model::DebitCredit,
+    traits::{ReadFormat, WriteFormat},
+};
+use rust_decimal_macros::dec;
+use std::io::Cursor;
+
+// Windows-1251, CRLF — как выгружает банк
+const SAMPLE: &[u8] = include_bytes!("../../examples/sample_1c.txt");
+
+#[test]
+fn reads_cp1251_exchange() {
+    assert_eq!(detect(SAMPLE).unwrap().format, FormatKind::OneC);
+
+    let ex = OneC::read_exchange(Cursor::new(SAMPLE)).unwrap();
+    assert_eq!(ex.documents.len(), 2);
+    let doc = &ex.documents[0];
+    assert_eq!(doc.payer.inn.as_deref(), Some("7701234567"));
+    assert_eq!(doc.payee.kpp.as_deref(), Some("770901001"));
+    assert_eq!(doc.payee.bank.as_deref(), Some("АО \"Альфа-Банк\""));
+    assert!(doc.extra.contains(&("Очередность".into(), "5".into())));
+
+    let st = OneC::read(Cursor::new(SAMPLE)).unwrap();
+    // БИК своего банка — из документов по счёту
+    assert_eq!(st.account_id, "044525225/40702810900000000001");
+    assert_eq!(st.opening_balance.as_ref().unwrap().amount, dec!(100000.00));
+    assert_eq!(st.closing_balance.as_ref().unwrap().amount, dec!(137499.50));
+
+    let (out, inc) = (&st.entries[0], &st.entries[1]);
+    assert_eq!(out.dc, DebitCredit::Debit);
+    assert_eq!(out.amount, dec!(12500.50));
+    assert_eq!(out.reference.as_deref(), Some("118"));
+    assert!(out.description.starts_with("Оплата по счёту №45"));
+    assert_eq!(inc.dc, DebitCredit::Credit);
+    assert_eq!(inc.booking_date.to_string(), "2025-10-16");
+}
+
+#[test]
+fn write_roundtrip() {
+    let ex = OneC::read_exchange(Cursor::new(SAMPLE)).unwrap();
+    let mut out = Vec::new();
+    OneC::write_exchange(&mut out, &ex).unwrap();
+    // на выходе тоже cp1251, не UTF-8
+    assert!(std::str::from_utf8(&out).is_err());
+    assert_eq!(OneC::read_exchange(Cursor::new(&out)).unwrap(), ex);
+
+    let st = OneC::read(Cursor::new(SAMPLE)).unwrap();
+    let mut out = Vec::new();
+    OneC::write(&mut out, &st).unwrap();
+    let back = OneC::read(Cursor::new(&out)).unwrap();
+    assert_eq!(back, st);
+}
+
+#[test]
+fn write_rejects_chars_outside_cp1251() {
+    let mut ex = OneC::read_exchange(Cursor::new(SAMPLE)).unwrap();
+    ex.documents[0].purpose = "Оплата €100 ✓".into();
+    let err = OneC::write_exchange(&mut Vec::new(), &ex).unwrap_err();
+    assert!(err.to_string().contains("cannot be encoded"), "{err}");
+}
+
+#[test]
+fn statement_roundtrip_keeps_party_details() {
+    let st = OneC::read(Cursor::new(SAMPLE)).unwrap();
+    assert_eq!(st.account_owner.as_deref(), Some("ИНН 7701234567 ООО \"Ромашка\""));
+    assert_eq!((st.owner_tax_id.as_deref(), st.owner_tax_reason_code.as_deref()), (Some("7701234567"), Some("770101001")));
+    let payee = st.entries[0].counterparty.as_ref().unwrap();
+    assert_eq!((payee.tax_id.as_deref(), payee.tax_reason_code.as_deref()), (Some("7709876543"), Some("770901001")));
+    assert_eq!(payee.bank_name.as_deref(), Some("АО \"Альфа-Банк\""));
+    assert_eq!(payee.bank_corr_account.as_deref(), Some("30101810200000000593"));
+
+    // 1С → выписка → 1С: реквизиты сторон не пустеют
+    let mut out = Vec::new();
+    OneC::write(&mut out, &st).unwrap();
+    let ex = OneC::read_exchange(Cursor::new(&out)).unwrap();
+    let src = OneC::read_exchange(Cursor::new(SAMPLE)).unwrap();
+    assert_eq!(ex.documents[0].payer, src.documents[0].payer);
+    assert_eq!(ex.documents[0].payee, src.documents[0].payee);
+    assert_eq!(ex.documents[1].payer, src.documents[1].payer);
+    let ours = &ex.documents[1].payee;
+    assert_eq!((ours.inn.as_deref(), ours.kpp.as_deref()), (Some("7701234567"), Some("770101001")));
+    assert_eq!(ours.name, src.documents[1].payee.name);
+}
@@ -0,0 +1,65 @@
+use finiolib::{
+    formats::{camt053::Camt053, field86::Layout86, mt940::Mt940},
+    model::CreditorReference,
+    remittance::{find_rf, is_valid_rf},
+    stream,
+    traits::{ReadFormat, StreamRead, StreamWrite, WriteFormat},
+    validate::{validate, FindingKind},
+};
+use std::io::Cursor;
+
+fn mt(lines86: &str) -> String {
+    format!(
+        ":20:S1\n:25:NL91ABNA0417164300\n:60F:C251001EUR100,00\n\
+         :61:2510011001C10,00NTRFNONREF\n:86:{lines86}\n:62F:C251031EUR110,00\n"
+    )
+}
+
+#[test]
+fn rf_checksum() {
+    assert!(is_valid_rf("RF18539007547034"));
+    assert!(is_valid_rf("RF18 5390 0754 7034"));
+    assert!(is_valid_rf("RF712348231"));
+    assert!(!is_valid_rf("RF19539007547034"));
+    assert!(!is_valid_rf("RF18"));
+    assert_eq!(find_rf("EREF+4711SVWZ+RF18539007547034 Rechnung"), Some("RF18539007547034"));
+    assert_eq!(find_rf("RF19539007547034"), None);
+}
+
+#[test]
+fn mt940_references_reach_camt_and_back() {
+    let src = mt("/TRTP/SEPA OVERBOEKING/NAME/J. Jansen/REMI/Factuur 17/CDTRREFTP//CD/SCOR/ISSR/ISO\n/CDTRREF/RF18539007547034");
+    let sts = stream::collect(Mt940::reader(Cursor::new(&src)).layout86(Layout86::Tagged)).unwrap();
+    let e = &sts[0].entries[0];
+    assert_eq!(e.description, "Factuur 17");
+    assert_eq!(e.remittance.creditor_reference, Some(CreditorReference::rf("RF18539007547034")));
+
+    let mut buf = Vec::new();
+    stream::write_all(&mut Mt940::writer(&mut buf).layout86(Layout86::Tagged), &sts).unwrap();
+    // строки :86: режутся по 65 символов
+    let joined = String::from_utf8(buf).unwrap().replace('\n', "");
+    assert!(joined.contains("/CDTRREFTP//CD/SCOR/ISSR/ISO/CDTRREF/RF18539007547034"));
+
+    let mut xml = Vec::new();
+    Camt053::write(&mut xml, &sts[0]).unwrap();
+    let flat: String = String::from_utf8(xml.clone()).unwrap().lines().map(str::trim).collect();
+    assert!(flat.contains("<CdtrRefInf><Tp><CdOrPrtry><Cd>SCOR</Cd></CdOrPrtry><Issr>ISO</Issr></Tp><Ref>RF18539007547034</Ref>"));
+    let back = Camt053::read(Cursor::new(xml)).unwrap();
+    assert_eq!(back.entries[0].remittance, e.remittance);
+
+    // немецкая раскладка: RF из назначения
+    let de = Mt940::read(Cursor::new(mt("166?00SEPA-GUTSCHRIFT?20SVWZ+RF18539007547034"))).unwrap();
+    assert_eq!(de.entries[0].remittance.creditor_reference.as_ref().unwrap().reference, "RF18539007547034");
+}
+
+#[test]
+fn validator_flags_bad_rf() {
+    let mut st = Mt940::read(Cursor::new(mt("/REMI/x/RF/RF19539007547034"))).unwrap();
+    let findings = validate(std::slice::from_ref(&st));
+    assert_eq!(
+        findings.iter().map(|f| &f.kind).collect::<Vec<_>>(),
+        [&FindingKind::InvalidCreditorReference { reference: "RF19539007547034".into() }]
+    );
+    st.entries[0].remittance.creditor_reference = Some(CreditorReference::rf("RF18539007547034"));
+    assert!(validate(&[st]).is_empty());
+}
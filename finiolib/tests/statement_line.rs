@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+use finiolib::{
+    formats::{camt053::Camt053, mt940::Mt940, mt942::Mt942},
+    model::DebitCredit,
+    traits::{ReadFormat, WriteFormat},
+};
+use std::io::Cursor;
+
+const MT: &str = ":20:S1
+:25:DE89370400440532013000
+:28C:1
+:60F:C251231EUR100,00
+:61:2512310102RCR1234,56NCHK123456//BANKREF99
+CHEQUE 4711 RETURNED
+:86:returned cheque
+:61:2601021231D5,00NTRFNONREF
+:61:2512311231C10,00NMSCREF-1
+:86:plain
+:62F:C260102EUR-1129,56
+";
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn full_61_grammar() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+    let e = &st.entries[0];
+    // сторно кредита — дебет
+    assert_eq!((e.dc, e.reversal), (DebitCredit::Debit, true));
+    assert_eq!(e.funds_code, Some('R'));
+    assert_eq!(e.reference.as_deref(), Some("123456"));
+    assert_eq!(e.bank_reference.as_deref(), Some("BANKREF99"));
+    assert_eq!(e.supplementary.as_deref(), Some("CHEQUE 4711 RETURNED"));
+    assert_eq!(e.description, "returned cheque");
+
+    // дата проводки переходит через границу года в обе стороны
+    assert_eq!((e.value_date, e.booking_date), (Some(date(2025, 12, 31)), date(2026, 1, 2)));
+    let e = &st.entries[1];
+    assert_eq!((e.value_date, e.booking_date), (Some(date(2026, 1, 2)), date(2025, 12, 31)));
+    assert!(!e.reversal && e.bank_reference.is_none() && e.supplementary.is_none());
+    assert_eq!(st.entries[2].reference.as_deref(), Some("REF-1"));
+
+    let mut buf = Vec::new();
+    Mt940::write(&mut buf, &st).unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains(":61:2512310102RCR1234,56NCHK123456//BANKREF99\nCHEQUE 4711 RETURNED\n:86:"));
+    assert_eq!(Mt940::read(Cursor::new(&out)).unwrap(), st);
+
+    // MT942 разбирает :61: тем же кодом
+    let mt942 = MT.replace(":60F:C251231EUR100,00", ":34F:EUR0,").replace(":62F:C260102EUR-1129,56\n", "");
+    let rep = Mt942::read_reports(Cursor::new(mt942)).unwrap();
+    assert_eq!(rep[0].statement.entries[0].supplementary.as_deref(), Some("CHEQUE 4711 RETURNED"));
+}
+
+#[test]
+fn camt_keeps_reversal_and_bank_reference() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+    let mut buf = Vec::new();
+    Camt053::write(&mut buf, &st).unwrap();
+    let xml = String::from_utf8(buf).unwrap();
+    assert!(xml.contains("<RvslInd>true</RvslInd>") && xml.contains("<AcctSvcrRef>BANKREF99</AcctSvcrRef>"));
+
+    let back = Camt053::read(Cursor::new(xml.as_bytes())).unwrap();
+    let e = &back.entries[0];
+    assert_eq!((e.dc, e.reversal), (DebitCredit::Debit, true));
+    assert_eq!(e.bank_reference.as_deref(), Some("BANKREF99"));
+    assert!(!back.entries[1].reversal);
+}
@@ -0,0 +1,96 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use finiolib::{
+    currency::Currency,
+    formats::{
+        camt053::{Camt053, CamtVersion},
+        csv::Csv,
+        mt940::Mt940,
+        xml::SimpleXml,
+    },
+    model::{Period, Statement},
+    stream,
+    traits::{ReadFormat, StreamWrite, WriteFormat},
+};
+use std::io::Cursor;
+
+const MT: &str = ":20:S1\n:25:NL91ABNA0417164300\n:28C:42/3\n:60F:C251001EUR100,00\n\
+                  :61:2510011001C10,00NTRFNONREF\n:62F:C251031EUR110,00\n";
+
+fn dt(s: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap()
+}
+
+fn with_meta() -> Statement {
+    let mut st = Mt940::read(Cursor::new(MT)).unwrap();
+    st.electronic_seq = Some(7);
+    st.last_page = Some(false);
+    st.created = Some(dt("2025-11-01T08:30:00"));
+    st.period = Some(Period::days(
+        NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2025, 10, 31).unwrap(),
+    ));
+    st.account_currency = Some(Currency::EUR);
+    st.account_owner = Some("Muster GmbH".into());
+    st.servicer_bic = Some("ABNANL2AXXX".into());
+    st
+}
+
+#[test]
+fn mt940_keeps_statement_number() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+    assert_eq!((st.legal_seq, st.page), (Some(42), Some(3)));
+
+    let mut buf = Vec::new();
+    Mt940::write(&mut buf, &st).unwrap();
+    assert!(String::from_utf8(buf).unwrap().contains(":25:NL91ABNA0417164300\n:28C:42/3\n"));
+
+    // без номера пишется обязательный :28C:1
+    let mut buf = Vec::new();
+    Mt940::write(&mut buf, &Statement::default()).unwrap();
+    assert!(String::from_utf8(buf).unwrap().contains(":28C:1\n"));
+
+    let err = Mt940::read(Cursor::new(MT.replace("42/3", "42/x"))).unwrap_err();
+    assert_eq!(err.diagnostic().unwrap().field.as_deref(), Some(":28C:"));
+}
+
+#[test]
+fn camt_writes_group_header_and_statement_meta() {
+    let st = with_meta();
+    let mut buf = Vec::new();
+    Camt053::write(&mut buf, &st).unwrap();
+    let flat: String = String::from_utf8(buf).unwrap().lines().map(str::trim).collect();
+    assert!(flat.contains("<BkToCstmrStmt><GrpHdr><MsgId>S1</MsgId><CreDtTm>2025-11-01T08:30:00</CreDtTm></GrpHdr>"));
+    assert!(flat.contains(
+        "<Id>S1</Id><ElctrncSeqNb>7</ElctrncSeqNb><LglSeqNb>42</LglSeqNb><CreDtTm>2025-11-01T08:30:00</CreDtTm>\
+         <FrToDt><FrDtTm>2025-10-01T00:00:00</FrDtTm><ToDtTm>2025-10-31T23:59:59</ToDtTm></FrToDt>"
+    ));
+    assert!(flat.contains("<Ccy>EUR</Ccy><Ownr><Nm>Muster GmbH</Nm></Ownr><Svcr><FinInstnId><BIC>ABNANL2AXXX</BIC>"));
+    // нумерация страниц — только с 001.03
+    assert!(!flat.contains("StmtPgntn"));
+    let mut back = Camt053::read(Cursor::new(flat)).unwrap();
+    back.page = st.page;
+    back.last_page = st.last_page;
+    assert_eq!(back, st);
+
+    let mut out = Vec::new();
+    let mut wr = Camt053::writer(&mut out).version(CamtVersion::V08);
+    stream::write_all(&mut wr, std::slice::from_ref(&st)).unwrap();
+    drop(wr);
+    let xml = String::from_utf8(out).unwrap();
+    let flat: String = xml.lines().map(str::trim).collect();
+    assert!(flat.contains("<StmtPgntn><PgNb>3</PgNb><LastPgInd>false</LastPgInd></StmtPgntn>"));
+    assert_eq!(Camt053::read(Cursor::new(xml)).unwrap(), st);
+}
+
+#[test]
+fn csv_and_xml_keep_statement_meta() {
+    let st = with_meta();
+
+    let mut csv = Vec::new();
+    Csv::write(&mut csv, &st).unwrap();
+    assert_eq!(Csv::read(Cursor::new(csv)).unwrap(), st);
+
+    let mut xml = Vec::new();
+    SimpleXml::write(&mut xml, &st).unwrap();
+    assert_eq!(SimpleXml::read(Cursor::new(xml)).unwrap(), st);
+}
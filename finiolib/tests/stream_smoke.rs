@@ -0,0 +1,54 @@
+use finiolib::{
+    formats::{camt053::Camt053, csv::Csv, mt940::Mt940},
+    stream::{self, StatementEvent},
+    traits::{ReadFormat, StreamRead, StreamWrite},
+};
+use std::io::Cursor;
+
+const MT: &str = r#":20:STMT1
+:25:DE0012345678
+:60F:C251001EUR1000,00
+:61:2510011001C100,00NTRFNONREF
+:86:Salary
+:86:October
+:61:2510021002D25,50NTRFNONREF
+:62F:C251031EUR1074,50
+"#;
+
+#[test]
+fn mt940_events_in_order() {
+    let evs: Vec<StatementEvent> = Mt940::reader(Cursor::new(MT))
+        .collect::<Result<_, _>>()
+        .expect("mt940 stream");
+    assert_eq!(evs.len(), 4);
+
+    let StatementEvent::Header(h) = &evs[0] else { panic!("header first") };
+    assert_eq!(h.account_id, "DE0012345678");
+    assert!(h.entries.is_empty());
+    assert!(h.closing_balance.is_none());
+
+    let StatementEvent::Entry(e) = &evs[1] else { panic!("entry") };
+    assert_eq!(e.description, "Salary October");
+
+    let StatementEvent::Trailer(t) = &evs[3] else { panic!("trailer last") };
+    assert_eq!(t.closing_balance.as_ref().unwrap().amount.to_string(), "1074.50");
+}
+
+#[test]
+fn pump_mt940_to_camt_and_csv() {
+    let expected = Mt940::read_statements(Cursor::new(MT)).unwrap();
+
+    // CLBD известен только в trailer — writer должен его дождаться
+    let mut camt = Vec::new();
+    stream::pump(Mt940::reader(Cursor::new(MT)), &mut Camt053::writer(&mut camt)).unwrap();
+    let back = Camt053::read(Cursor::new(&camt)).unwrap();
+    assert_eq!(back.entries, expected[0].entries);
+    let text = String::from_utf8(camt).unwrap();
+    assert!(text.find("CLBD").unwrap() < text.find("<Ntry>").unwrap());
+
+    let mut csv = Vec::new();
+    stream::pump(Mt940::reader(Cursor::new(MT)), &mut Csv::writer(&mut csv)).unwrap();
+    let back = Csv::read(Cursor::new(csv)).unwrap();
+    assert_eq!(back.closing_balance, expected[0].closing_balance);
+    assert_eq!(back.entries, expected[0].entries);
+}
@@ -0,0 +1,115 @@
+use finiolib::{
+    convert,
+    formats::{
+        camt053::{Camt053, CamtVersion},
+        mt940::Mt940,
+    },
+    model::Entry,
+    stream,
+    traits::{ReadFormat, StreamRead, StreamWrite, WriteFormat},
+};
+use chrono::NaiveDate;
+use rust_decimal_macros::dec;
+use std::io::Cursor;
+
+const BATCH: &str = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+<BkToCstmrStmt><Stmt><Id>S1</Id><CreDtTm>2025-11-01T08:00:00</CreDtTm><Acct><Id><IBAN>DE89370400440532013000</IBAN></Id></Acct>
+<Ntry><Amt Ccy="EUR">30.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Sts>BOOK</Sts>
+<BookgDt><Dt>2025-10-01</Dt></BookgDt><AcctSvcrRef>B1</AcctSvcrRef>
+<NtryDtls><Btch><NbOfTxs>2</NbOfTxs></Btch>
+<TxDtls><Refs><EndToEndId>E1</EndToEndId><MndtId>M1</MndtId></Refs>
+<AmtDtls><TxAmt><Amt Ccy="EUR">10.00</Amt></TxAmt></AmtDtls>
+<RltdPties><Dbtr><Nm>Anna</Nm></Dbtr></RltdPties>
+<RmtInf><Ustrd>Invoice 1</Ustrd><Strd><CdtrRefInf><Ref>RF18539007547034</Ref></CdtrRefInf></Strd></RmtInf></TxDtls>
+<TxDtls><Refs><EndToEndId>E2</EndToEndId></Refs>
+<AmtDtls><TxAmt><Amt Ccy="EUR">20.00</Amt></TxAmt></AmtDtls>
+<RltdPties><Dbtr><Nm>Boris</Nm></Dbtr></RltdPties>
+<RmtInf><Ustrd>Invoice 2</Ustrd></RmtInf></TxDtls>
+</NtryDtls><AddtlNtryInf>SEPA batch</AddtlNtryInf></Ntry>
+</Stmt></BkToCstmrStmt></Document>"#;
+
+#[test]
+fn camt_batch_details_roundtrip() {
+    let st = Camt053::read(Cursor::new(BATCH)).unwrap();
+    let e = &st.entries[0];
+    assert_eq!(e.counterparty, None);
+    assert_eq!(e.details.len(), 2);
+    let d = &e.details[0];
+    assert_eq!((d.amount, d.currency.map(|c| c.code())), (Some(dec!(10.00)), Some("EUR")));
+    assert_eq!(d.refs.end_to_end_id.as_deref(), Some("E1"));
+    assert_eq!(d.refs.mandate_id.as_deref(), Some("M1"));
+    assert_eq!(d.remittance.unstructured, ["Invoice 1"]);
+    assert_eq!(d.remittance.creditor_reference.as_ref().unwrap().reference, "RF18539007547034");
+    assert_eq!(d.counterparty.as_ref().unwrap().name.as_deref(), Some("Anna"));
+
+    let mut buf = Vec::new();
+    Camt053::write(&mut buf, &st).unwrap();
+    assert!(String::from_utf8_lossy(&buf).contains("<NbOfTxs>2</NbOfTxs>"));
+    assert_eq!(Camt053::read(Cursor::new(buf)).unwrap(), st);
+}
+
+#[test]
+fn single_tx_keeps_counterparty_on_entry() {
+    let mut st = Camt053::read(Cursor::new(BATCH)).unwrap();
+    let e = &mut st.entries[0];
+    e.details.truncate(1);
+    e.counterparty = e.details[0].counterparty.take();
+    e.remittance = std::mem::take(&mut e.details[0].remittance);
+
+    let mut buf = Vec::new();
+    Camt053::write(&mut buf, &st).unwrap();
+    let back = Camt053::read(Cursor::new(buf)).unwrap();
+    assert_eq!(back.entries[0].counterparty.as_ref().unwrap().name.as_deref(), Some("Anna"));
+    assert_eq!(back.entries[0], st.entries[0]);
+    assert_eq!(back.entries[0].details[0].refs.end_to_end_id.as_deref(), Some("E1"));
+}
+
+#[test]
+fn expand_batches_for_mt940() {
+    let events = convert::expand_batches(Camt053::reader(Cursor::new(BATCH)));
+    let st = stream::collect(events).unwrap().remove(0);
+    let summary: Vec<_> = st
+        .entries
+        .iter()
+        .map(|e: &Entry| (e.amount, e.reference.as_deref(), e.description.as_str()))
+        .collect();
+    assert_eq!(summary, [(dec!(10.00), Some("E1"), "Invoice 1"), (dec!(20.00), Some("E2"), "Invoice 2")]);
+    assert_eq!(st.entries[1].counterparty.as_ref().unwrap().name.as_deref(), Some("Boris"));
+    assert_eq!(st.entries[1].bank_reference.as_deref(), Some("B1"));
+
+    let mut buf = Vec::new();
+    stream::write_all(&mut Mt940::writer(&mut buf), std::slice::from_ref(&st)).unwrap();
+    let mt = String::from_utf8(buf).unwrap();
+    assert!(mt.contains("C10,00NTRFE1//B1") && mt.contains("C20,00NTRFE2//B1"), "{mt}");
+}
+
+#[test]
+fn camt_related_dates_roundtrip() {
+    let xml = BATCH.replace(
+        "<RmtInf><Ustrd>Invoice 2</Ustrd></RmtInf></TxDtls>",
+        "<RmtInf><Ustrd>Invoice 2</Ustrd></RmtInf><RltdDts><AccptncDtTm>2025-09-30T14:05:00+02:00</AccptncDtTm>\
+         <IntrBkSttlmDt>2025-10-01</IntrBkSttlmDt><TxDtTm>2025-09-30T14:06:00</TxDtTm></RltdDts></TxDtls>",
+    );
+    let st = Camt053::read(Cursor::new(xml)).unwrap();
+    let dates = &st.entries[0].details[1].dates;
+    let day = NaiveDate::from_ymd_opt(2025, 9, 30).unwrap();
+    assert_eq!(dates.acceptance, day.and_hms_opt(14, 5, 0));
+    assert_eq!(dates.interbank_settlement, NaiveDate::from_ymd_opt(2025, 10, 1));
+    assert_eq!(dates.transaction, day.and_hms_opt(14, 6, 0));
+    assert!(st.entries[0].details[0].dates.is_empty());
+
+    for version in [CamtVersion::V02, CamtVersion::V08] {
+        let mut out = Vec::new();
+        let mut wr = Camt053::writer(&mut out).version(version);
+        stream::write_all(&mut wr, std::slice::from_ref(&st)).unwrap();
+        drop(wr);
+        let flat: String = String::from_utf8(out.clone()).unwrap().lines().map(str::trim).collect();
+        assert_eq!(flat.matches("<RltdDts>").count(), 1);
+        assert!(flat.contains(
+            "</RmtInf><RltdDts><AccptncDtTm>2025-09-30T14:05:00</AccptncDtTm>\
+             <IntrBkSttlmDt>2025-10-01</IntrBkSttlmDt><TxDtTm>2025-09-30T14:06:00</TxDtTm></RltdDts></TxDtls>"
+        ));
+        let (back, _) = Camt053::reader(Cursor::new(out)).into_statements().unwrap();
+        assert_eq!(back[0].entries[0].details, st.entries[0].details);
+    }
+}
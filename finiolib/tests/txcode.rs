@@ -0,0 +1,86 @@
+use finiolib::{
+    formats::{camt053::Camt053, mt940::Mt940},
+    model::{BankTxCode, DebitCredit, IsoTxCode},
+    txcode,
+    traits::{ReadFormat, WriteFormat},
+};
+use std::io::Cursor;
+
+const MT: &str = ":20:S1
+:25:DE89370400440532013000
+:60F:C251001EUR100,00
+:61:2510011001C10,00NTRFNONREF
+:86:166?00SEPA-GUTSCHRIFT?20Rechnung 4711
+:61:2510021002D5,00NCHKNONREF
+:86:cheque
+:61:2510031003D1,00S103NONREF
+:86:wire
+:61:2510041004D2,00NXYZNONREF
+:86:misc
+:62F:C251031EUR102,00
+";
+
+fn iso(s: &str) -> IsoTxCode {
+    s.parse().unwrap()
+}
+
+#[test]
+fn swift_iso_mapping() {
+    assert_eq!(txcode::swift_to_iso("NTRF", DebitCredit::Credit), Some(iso("PMNT/RCDT/ESCT")));
+    assert_eq!(txcode::swift_to_iso("FTRF", DebitCredit::Debit), Some(iso("PMNT/ICDT/ESCT")));
+    assert_eq!(txcode::swift_to_iso("S103", DebitCredit::Debit), Some(iso("PMNT/ICDT/XBCT")));
+    assert_eq!(txcode::swift_to_iso("NXYZ", DebitCredit::Debit), None);
+    assert_eq!(txcode::iso_to_swift(&iso("PMNT/RCHQ/CCHQ")).as_deref(), Some("NCHK"));
+    assert_eq!(txcode::iso_to_swift(&iso("PMNT/RCDT/XBCT")).as_deref(), Some("S103"));
+    assert_eq!(txcode::iso_to_swift(&iso("LDAS/CSLN/DDWN")), None);
+    for (swift, credit, debit) in [
+        ("NCOL", "PMNT/RDDT/OTHR", "PMNT/IDDT/OTHR"),
+        ("NDIV", "SECU/CORP/DVCA", "SECU/CORP/DVCA"),
+        ("NSEC", "SECU/SETT/TRAD", "SECU/SETT/TRAD"),
+        ("NRTI", "PMNT/RCDT/RRTN", "PMNT/ICDT/RRTN"),
+        ("NCLR", "PMNT/RCHQ/BCHQ", "PMNT/ICHQ/BCHQ"),
+        ("NCMI", "CAMT/ACCB/OTHR", "CAMT/ACCB/OTHR"),
+        ("NFEX", "FORX/SPOT/OTHR", "FORX/SPOT/OTHR"),
+        ("NCAS", "PMNT/CNTR/CDPT", "PMNT/CNTR/CWDL"),
+    ] {
+        assert_eq!(txcode::swift_to_iso(swift, DebitCredit::Credit), Some(iso(credit)), "{swift}");
+        assert_eq!(txcode::swift_to_iso(swift, DebitCredit::Debit), Some(iso(debit)), "{swift}");
+        assert_eq!(txcode::iso_to_swift(&iso(debit)).as_deref(), Some(swift));
+    }
+    assert!("PMNT/RCDT".parse::<IsoTxCode>().is_err());
+
+    // ISO-код без пары не превращается в NMSC в модели
+    let loan = BankTxCode { iso: Some(iso("LDAS/CSLN/DDWN")), ..BankTxCode::default() };
+    assert_eq!(loan.swift_code(), None);
+    assert_eq!(BankTxCode::from_swift("NXYZ").iso_code(DebitCredit::Credit), None);
+}
+
+#[test]
+fn mt940_camt_mt940_keeps_codes() {
+    let st = Mt940::read(Cursor::new(MT)).unwrap();
+    let swift: Vec<_> = st.entries.iter().map(|e| e.bank_tx_code.as_ref().unwrap().swift.clone()).collect();
+    assert_eq!(swift, ["NTRF", "NCHK", "S103", "NXYZ"].map(|s| Some(s.to_string())));
+
+    let mut buf = Vec::new();
+    Camt053::write(&mut buf, &st).unwrap();
+    let xml = String::from_utf8(buf).unwrap();
+    let flat: String = xml.lines().map(str::trim).collect();
+    assert!(flat.contains("<Domn><Cd>PMNT</Cd><Fmly><Cd>RCDT</Cd><SubFmlyCd>ESCT</SubFmlyCd></Fmly></Domn>"));
+    assert!(flat.contains("<Prtry><Cd>NCHK</Cd><Issr>SWIFT</Issr></Prtry>"));
+    // у GVC свой проприетарный код, неизвестный SWIFT-тип — без Domn
+    assert!(flat.contains("<Prtry><Cd>166</Cd><Issr>DK</Issr></Prtry>"));
+    assert!(flat.contains("<Prtry><Cd>NXYZ</Cd><Issr>SWIFT</Issr></Prtry>"));
+
+    let camt = Camt053::read(Cursor::new(xml.as_bytes())).unwrap();
+    let codes: Vec<_> = camt.entries.iter().map(|e| e.bank_tx_code.clone().unwrap()).collect();
+    assert_eq!(codes[1], BankTxCode::from_swift("NCHK"));
+    assert_eq!(codes[3], BankTxCode::from_swift("NXYZ"));
+
+    let mut buf = Vec::new();
+    Mt940::write(&mut buf, &camt).unwrap();
+    let back = Mt940::read(Cursor::new(&buf)).unwrap();
+    for (a, b) in back.entries.iter().zip(&st.entries) {
+        assert_eq!(a.bank_tx_code.as_ref().unwrap().swift_code(), b.bank_tx_code.as_ref().unwrap().swift_code());
+    }
+    assert_eq!(back.entries[0].bank_tx_code.as_ref().unwrap().proprietary, st.entries[0].bank_tx_code.as_ref().unwrap().proprietary);
+}
@@ -0,0 +1,46 @@
+use finiolib::{
+    currency::Currency,
+    formats::mt940::Mt940,
+    traits::ReadFormat,
+    validate::{has_errors, validate, FindingKind},
+};
+use rust_decimal::Decimal;
+use std::io::Cursor;
+
+#[test]
+fn balanced_statement_passes() {
+    let s = ":20:S1\n:25:ACCT\n:60F:C251001EUR1000,00\n:61:2510011001C100,00NTRFNONREF\n:62F:C251031EUR1100,00\n";
+    let sts = Mt940::read_statements(Cursor::new(s)).unwrap();
+    assert!(validate(&sts).is_empty());
+}
+
+#[test]
+fn reports_arithmetic_currency_and_dates() {
+    let s = r#":20:S1
+:25:ACCT
+:60F:C251001EUR1000,00
+:61:2510011001C100,00NTRFNONREF
+:61:2511051105DUSD25,00NTRFNONREF
+:62F:C251031EUR1100,00
+"#;
+    let sts = Mt940::read_statements(Cursor::new(s)).unwrap();
+    let findings = validate(&sts);
+    assert!(has_errors(&findings));
+
+    let kinds: Vec<&FindingKind> = findings.iter().map(|f| &f.kind).collect();
+    assert!(kinds.contains(&&FindingKind::CurrencyMismatch { expected: Currency::EUR, found: Currency::USD }));
+    assert!(findings.iter().any(|f| f.entry == Some(1) && matches!(f.kind, FindingKind::DateOutOfRange { .. })));
+    assert!(kinds.contains(&&FindingKind::BalanceMismatch {
+        expected: Decimal::new(107500, 2),
+        actual: Decimal::new(110000, 2),
+    }));
+}
+
+#[test]
+fn missing_balance_is_only_a_warning() {
+    let s = ":20:S1\n:25:ACCT\n:61:2510011001C100,00NTRFNONREF\n";
+    let sts = Mt940::read_statements(Cursor::new(s)).unwrap();
+    let findings = validate(&sts);
+    assert_eq!(findings.len(), 1);
+    assert!(!has_errors(&findings));
+}
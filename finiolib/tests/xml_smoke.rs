@@ -1,4 +1,4 @@
-use finiolib::{formats::xml::SimpleXml, traits::{ReadFormat, WriteFormat}, model::{Statement, Entry, Balance, DebitCredit}};
+use finiolib::{currency::Currency, formats::xml::SimpleXml, traits::{ReadFormat, WriteFormat}, model::{Statement, Entry, Balance, DebitCredit}};
 use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use std::io::Cursor;
@@ -11,18 +11,30 @@ fn simple_xml_roundtrip() {
         opening_balance: Some(Balance {
             date: NaiveDate::from_ymd_opt(2025,10,1).unwrap(),
             amount: Decimal::from_str_exact("1.00").unwrap(),
-            currency: "EUR".into(),
+            currency: Currency::EUR,
         }),
         closing_balance: None,
+        balances: vec![],
         entries: vec![Entry{
             booking_date: NaiveDate::from_ymd_opt(2025,10,1).unwrap(),
             value_date: None,
             amount: Decimal::from_str_exact("2.50").unwrap(),
-            currency: "EUR".into(),
+            currency: Currency::EUR,
             dc: DebitCredit::Debit,
             description: "Test".into(),
             reference: None,
+            reversal: false,
+            status: Default::default(),
+            funds_code: None,
+            bank_reference: None,
+            supplementary: None,
+            counterparty: None,
+            posting_text: None,
+            bank_tx_code: None,
+            remittance: Default::default(),
+            details: vec![],
         }],
+        ..Statement::default()
     };
 
     let mut out = Vec::new();